@@ -0,0 +1,168 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use jiff::Timestamp;
+use serde::Serialize;
+
+use uv_fs::Simplified;
+use uv_static::EnvVars;
+use uv_warnings::warn_user_once;
+
+/// The path of the audit log, if overridden, e.g., in tests.
+static AUDIT_LOG_PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// The records that are yet to be written to the audit log.
+static PENDING: Mutex<Vec<AuditRecord>> = Mutex::new(Vec::new());
+
+/// An operation on credentials recorded in the audit log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AuditOperation {
+    /// A password was read from the keyring.
+    Fetch,
+    /// A password was stored in the keyring.
+    Set,
+    /// A password was removed from the keyring.
+    Unset,
+    /// Credentials were added for an index, e.g., with `uv index credentials set`.
+    AddCredentials,
+    /// Credentials were removed for an index, e.g., with `uv index credentials unset`.
+    RemoveCredentials,
+    /// Credentials were listed for an index, with `uv index credentials list`.
+    ListCredentials,
+}
+
+/// The outcome of an [`AuditOperation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AuditOutcome {
+    /// A password was found.
+    Found,
+    /// No password was found.
+    NotFound,
+    /// The password was stored.
+    Stored,
+    /// The password was removed.
+    Removed,
+    /// The password was not stored or removed, e.g., because there was no such entry.
+    Failed,
+    /// The keyring could not be queried.
+    Error,
+}
+
+/// A line of the audit log.
+///
+/// Secrets are never recorded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct AuditRecord {
+    timestamp: Timestamp,
+    operation: AuditOperation,
+    service: String,
+    username: Option<String>,
+    outcome: AuditOutcome,
+}
+
+/// Override the path of the audit log, in place of `UV_AUTH_AUDIT_LOG`.
+///
+/// Pass `None` to go back to the path in the environment.
+pub fn set_audit_log_path(path: Option<PathBuf>) {
+    *AUDIT_LOG_PATH.lock().unwrap() = path;
+}
+
+/// The path of the audit log, or `None` if it's disabled.
+fn audit_log_path() -> Option<PathBuf> {
+    if let Some(path) = AUDIT_LOG_PATH.lock().unwrap().clone() {
+        return Some(path);
+    }
+    std::env::var_os(EnvVars::UV_AUTH_AUDIT_LOG)
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Record an operation on the credentials for a keyring service name, if the audit log is enabled.
+///
+/// The record is buffered until [`flush_audit_log`].
+pub fn audit(
+    operation: AuditOperation,
+    service: &str,
+    username: Option<&str>,
+    outcome: AuditOutcome,
+) {
+    if audit_log_path().is_none() {
+        return;
+    }
+    PENDING.lock().unwrap().push(AuditRecord {
+        timestamp: Timestamp::now(),
+        operation,
+        service: service.to_string(),
+        username: username.map(ToString::to_string),
+        outcome,
+    });
+}
+
+/// Append the buffered records to the audit log, as JSON lines.
+///
+/// If the log can't be written, e.g., because the path is read-only, a warning is shown once and
+/// the records are dropped.
+pub fn flush_audit_log() {
+    let records = std::mem::take(&mut *PENDING.lock().unwrap());
+    if records.is_empty() {
+        return;
+    }
+    let Some(path) = audit_log_path() else {
+        return;
+    };
+    if let Err(err) = append_records(&path, &records) {
+        warn_user_once!(
+            "Failed to write to the auth audit log at `{}`: {err}",
+            path.user_display()
+        );
+    }
+}
+
+fn append_records(path: &Path, records: &[AuditRecord]) -> std::io::Result<()> {
+    let mut contents = Vec::new();
+    for record in records {
+        serde_json::to_writer(&mut contents, record)?;
+        contents.push(b'\n');
+    }
+    if let Some(parent) = path.parent() {
+        fs_err::create_dir_all(parent)?;
+    }
+    // Write all the records at once, to keep them together if several processes append to the
+    // log concurrently.
+    let mut file = fs_err::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    file.write_all(&contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn append_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs").join("audit.jsonl");
+        let record = AuditRecord {
+            timestamp: "2026-01-01T00:00:00Z".parse().unwrap(),
+            operation: AuditOperation::Set,
+            service: "https://example.com".to_string(),
+            username: Some("user".to_string()),
+            outcome: AuditOutcome::Stored,
+        };
+        append_records(&path, std::slice::from_ref(&record)).unwrap();
+        append_records(&path, &[record]).unwrap();
+        let line = r#"{"timestamp":"2026-01-01T00:00:00Z","operation":"set","service":"https://example.com","username":"user","outcome":"stored"}"#;
+        assert_eq!(
+            fs_err::read_to_string(&path).unwrap(),
+            format!("{line}\n{line}\n")
+        );
+
+        // A directory can't be appended to.
+        assert!(append_records(dir.path(), &[]).is_err());
+    }
+}
@@ -1,6 +1,6 @@
 use std::hash::BuildHasherDefault;
 use std::sync::Arc;
-use std::sync::RwLock;
+use std::sync::{Mutex, RwLock};
 
 use rustc_hash::{FxHashMap, FxHasher};
 use tracing::trace;
@@ -20,6 +20,9 @@ pub struct CredentialsCache {
     pub(crate) fetches: FxOnceMap<(Realm, Username), Option<Arc<Credentials>>>,
     /// A cache per URL, uses a trie for efficient prefix queries.
     urls: RwLock<UrlTrie>,
+    /// The usernames discovered in the keyring for URLs without an entry in the auth config, at
+    /// most one per realm.
+    discovered_usernames: Mutex<Vec<(Url, String)>>,
 }
 
 impl Default for CredentialsCache {
@@ -35,9 +38,28 @@ impl CredentialsCache {
             fetches: FxOnceMap::default(),
             realms: RwLock::new(FxHashMap::default()),
             urls: RwLock::new(UrlTrie::new()),
+            discovered_usernames: Mutex::new(Vec::new()),
         }
     }
 
+    /// Record a username discovered in the keyring for a URL, unless one was already discovered
+    /// for its realm.
+    pub(crate) fn record_discovered_username(&self, url: &Url, username: &str) {
+        let mut discovered = self.discovered_usernames.lock().unwrap();
+        if !discovered
+            .iter()
+            .any(|(discovered_url, _)| Realm::from(discovered_url) == Realm::from(url))
+        {
+            discovered.push((url.clone(), username.to_string()));
+        }
+    }
+
+    /// Take the usernames discovered in the keyring, as pairs of the URL that required
+    /// authentication and the username.
+    pub(crate) fn take_discovered_usernames(&self) -> Vec<(Url, String)> {
+        std::mem::take(&mut *self.discovered_usernames.lock().unwrap())
+    }
+
     /// Return the credentials that should be used for a realm and username, if any.
     pub(crate) fn get_realm(&self, realm: Realm, username: Username) -> Option<Arc<Credentials>> {
         let realms = self.realms.read().unwrap();
@@ -0,0 +1,711 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tracing::{debug, trace};
+
+use crate::credentials::Credentials;
+use crate::keyring_command::{KeyringCommand, KeyringExecutable};
+
+/// The username of the credentials for AWS CodeArtifact, which authenticates with the token as
+/// the password.
+pub const CODEARTIFACT_USERNAME: &str = "aws";
+
+/// The username of the credentials for Google Artifact Registry, which authenticates with an OAuth
+/// access token as the password.
+pub const GCLOUD_USERNAME: &str = "oauth2accesstoken";
+
+/// The username of the credentials for Azure Artifacts, which accepts any username with a personal
+/// access token or an Entra ID access token as the password.
+pub const AZURE_USERNAME: &str = "azure";
+
+/// The application ID of Azure DevOps, the resource to request access tokens for.
+const AZURE_DEVOPS_RESOURCE: &str = "499b84ac-1321-427f-aa17-267ca6975798";
+
+/// The lifetime of a CodeArtifact authorization token, as issued by `aws codeartifact
+/// get-authorization-token` without `--duration-seconds`.
+const CODEARTIFACT_TOKEN_LIFETIME: Duration = Duration::from_secs(12 * 60 * 60);
+
+/// How long an access token printed by `gcloud` or `az` is assumed to be valid.
+///
+/// Access tokens are valid for an hour or more, but both CLIs print a cached token that may have
+/// been issued earlier, so only half of that is assumed.
+const ACCESS_TOKEN_LIFETIME: Duration = Duration::from_secs(30 * 60);
+
+/// How long before its expiry a token is refreshed, so that it doesn't expire during a request.
+const EXPIRY_MARGIN: Duration = Duration::from_secs(5 * 60);
+
+/// A token that was rejected is only refreshed if it was issued at least this long ago, so that
+/// a token that lacks permissions isn't requested again for every request.
+const MIN_REFRESH_AGE: Duration = Duration::from_secs(60);
+
+/// The maximum duration to wait for the helper to issue a token.
+///
+/// The AWS CLI and `gcloud` may need to refresh a session first, which is slower than a keyring
+/// lookup.
+const HELPER_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The maximum number of bytes of stderr to capture from the helper.
+const STDERR_LIMIT: usize = 64 * 1024;
+
+/// A credential helper recorded for an index in the [`AuthConfig`](crate::AuthConfig), which
+/// issues short-lived credentials when the index requests authentication, instead of a password
+/// stored in the keyring.
+#[derive(Serialize, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[serde(tag = "helper", rename_all = "kebab-case")]
+pub enum CredentialHelper {
+    /// Request an authorization token for an AWS CodeArtifact domain with the AWS CLI.
+    AwsCodeartifact {
+        /// The name of the CodeArtifact domain.
+        domain: String,
+        /// The AWS account ID that owns the domain.
+        #[serde(rename = "domain-owner")]
+        domain_owner: String,
+        /// The AWS region of the domain, if not the default region of the AWS CLI.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        region: Option<String>,
+    },
+    /// Request an Entra ID access token for Azure Artifacts with the Azure CLI.
+    Azure,
+    /// Request an OAuth access token for Google Artifact Registry with `gcloud`.
+    Gcloud {
+        /// Whether to use the application default credentials instead of the credentials of the
+        /// active `gcloud` account.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        adc: bool,
+    },
+}
+
+impl CredentialHelper {
+    /// Parse the helper of an auth config entry from the `helper` key and its parameters.
+    pub(crate) fn from_parts(
+        helper: &str,
+        domain: Option<String>,
+        domain_owner: Option<String>,
+        region: Option<String>,
+        adc: Option<bool>,
+    ) -> Result<Self, String> {
+        match helper {
+            "aws-codeartifact" => Ok(Self::AwsCodeartifact {
+                domain: domain.ok_or("missing field `domain` for helper `aws-codeartifact`")?,
+                domain_owner: domain_owner
+                    .ok_or("missing field `domain-owner` for helper `aws-codeartifact`")?,
+                region,
+            }),
+            "azure" => Ok(Self::Azure),
+            "gcloud" => Ok(Self::Gcloud {
+                adc: adc.unwrap_or(false),
+            }),
+            helper => Err(format!(
+                "unknown credential helper `{helper}`, expected `aws-codeartifact`, `azure`, or `gcloud`"
+            )),
+        }
+    }
+
+    /// The username of the credentials issued by the helper.
+    pub fn username(&self) -> &'static str {
+        match self {
+            Self::AwsCodeartifact { .. } => CODEARTIFACT_USERNAME,
+            Self::Azure => AZURE_USERNAME,
+            Self::Gcloud { .. } => GCLOUD_USERNAME,
+        }
+    }
+
+    /// The command that issues tokens, and the name of the CLI that provides it.
+    fn program(&self) -> (&'static str, &'static str) {
+        match self {
+            Self::AwsCodeartifact { .. } => ("aws", "AWS CLI"),
+            Self::Azure => ("az", "Azure CLI"),
+            Self::Gcloud { .. } => ("gcloud", "Google Cloud CLI"),
+        }
+    }
+
+    /// The command to run to log in, if the helper failed because the user is not logged in.
+    fn login_command(&self, stderr: &str) -> Option<&'static str> {
+        match self {
+            Self::AwsCodeartifact { .. } => None,
+            Self::Azure => {
+                (stderr.contains("az login") || stderr.contains("AADSTS")).then_some("az login")
+            }
+            Self::Gcloud { adc } => {
+                let logged_out = [
+                    "gcloud auth login",
+                    "gcloud auth application-default login",
+                    "do not currently have an active account",
+                    "Reauthentication",
+                ]
+                .iter()
+                .any(|needle| stderr.contains(needle));
+                if !logged_out {
+                    None
+                } else if *adc {
+                    Some("gcloud auth application-default login")
+                } else {
+                    Some("gcloud auth login")
+                }
+            }
+        }
+    }
+
+    /// The arguments of the command that prints a token.
+    fn args(&self) -> Vec<&str> {
+        match self {
+            Self::AwsCodeartifact {
+                domain,
+                domain_owner,
+                region,
+            } => {
+                let mut args = vec![
+                    "codeartifact",
+                    "get-authorization-token",
+                    "--domain",
+                    domain,
+                    "--domain-owner",
+                    domain_owner,
+                    "--query",
+                    "authorizationToken",
+                    "--output",
+                    "text",
+                ];
+                if let Some(region) = region {
+                    args.extend(["--region", region]);
+                }
+                args
+            }
+            Self::Azure => vec![
+                "account",
+                "get-access-token",
+                "--resource",
+                AZURE_DEVOPS_RESOURCE,
+                "--query",
+                "accessToken",
+                "--output",
+                "tsv",
+            ],
+            Self::Gcloud { adc: false } => vec!["auth", "print-access-token"],
+            Self::Gcloud { adc: true } => {
+                vec!["auth", "application-default", "print-access-token"]
+            }
+        }
+    }
+
+    /// How long a token issued by the helper is valid.
+    fn token_lifetime(&self) -> Duration {
+        match self {
+            Self::AwsCodeartifact { .. } => CODEARTIFACT_TOKEN_LIFETIME,
+            Self::Azure | Self::Gcloud { .. } => ACCESS_TOKEN_LIFETIME,
+        }
+    }
+}
+
+impl std::fmt::Display for CredentialHelper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AwsCodeartifact { .. } => f.write_str("aws-codeartifact"),
+            Self::Azure => f.write_str("azure"),
+            Self::Gcloud { .. } => f.write_str("gcloud"),
+        }
+    }
+}
+
+/// A failure to obtain a token from a credential helper.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CredentialHelperError {
+    #[error(
+        "`{program}` command not found; install the {cli} to use the `{helper}` credential helper"
+    )]
+    NotInstalled {
+        program: &'static str,
+        cli: &'static str,
+        helper: String,
+    },
+    #[error("The `{helper}` credential helper failed: {message}")]
+    Failed { helper: String, message: String },
+    #[error("The `{helper}` credential helper is not logged in; run `{login}` and try again")]
+    NotLoggedIn { helper: String, login: &'static str },
+    #[error("The `{0}` credential helper did not complete")]
+    TimedOut(String),
+    #[error("The `{0}` credential helper returned an empty or invalid token")]
+    InvalidToken(String),
+}
+
+/// A token issued by a credential helper, or the failure to obtain one.
+#[derive(Debug)]
+enum HelperState {
+    Token {
+        credentials: Arc<Credentials>,
+        issued_at: Instant,
+        expires_at: Instant,
+    },
+    Failed(CredentialHelperError),
+}
+
+/// Issues credentials with a [`CredentialHelper`], caching the token in memory until it expires.
+///
+/// Concurrent requests for a token wait for the same invocation of the helper. A failure is
+/// cached too, so that a helper that can't issue a token isn't run for every request.
+#[derive(Debug)]
+pub struct CredentialHelperProvider {
+    helper: CredentialHelper,
+    /// The name of the index the helper issues credentials for, to name it in warnings.
+    index: Option<String>,
+    command: Arc<dyn KeyringCommand>,
+    token_lifetime: Duration,
+    min_refresh_age: Duration,
+    state: tokio::sync::Mutex<Option<HelperState>>,
+    /// Whether the command was not found, in which case it's not spawned again.
+    not_installed: AtomicBool,
+}
+
+impl CredentialHelperProvider {
+    /// Create a provider that runs the CLI of the helper on the `PATH`.
+    pub fn new(helper: CredentialHelper) -> Self {
+        let (program, _) = helper.program();
+        Self::with_command(helper, Arc::new(KeyringExecutable::new(program)))
+    }
+
+    /// Set the name of the index the helper issues credentials for.
+    #[must_use]
+    pub fn with_index(mut self, index: impl Into<String>) -> Self {
+        self.index = Some(index.into());
+        self
+    }
+
+    /// The helper that issues the credentials.
+    pub fn helper(&self) -> &CredentialHelper {
+        &self.helper
+    }
+
+    /// The name of the index the helper issues credentials for, if known.
+    pub fn index(&self) -> Option<&str> {
+        self.index.as_deref()
+    }
+
+    pub(crate) fn with_command(helper: CredentialHelper, command: Arc<dyn KeyringCommand>) -> Self {
+        Self {
+            token_lifetime: helper.token_lifetime(),
+            min_refresh_age: MIN_REFRESH_AGE,
+            helper,
+            index: None,
+            command,
+            state: tokio::sync::Mutex::new(None),
+            not_installed: AtomicBool::new(false),
+        }
+    }
+
+    /// Override the lifetime of the issued tokens.
+    #[cfg(test)]
+    #[must_use]
+    pub(crate) fn with_token_lifetime(mut self, token_lifetime: Duration) -> Self {
+        self.token_lifetime = token_lifetime;
+        self
+    }
+
+    /// Override how long ago a rejected token must have been issued to be refreshed.
+    #[cfg(test)]
+    #[must_use]
+    pub(crate) fn with_min_refresh_age(mut self, min_refresh_age: Duration) -> Self {
+        self.min_refresh_age = min_refresh_age;
+        self
+    }
+
+    /// Return the credentials of the current token, running the helper if there is no token or
+    /// if it's about to expire.
+    pub async fn fetch(&self) -> Result<Arc<Credentials>, CredentialHelperError> {
+        let mut state = self.state.lock().await;
+        match &*state {
+            Some(HelperState::Token {
+                credentials,
+                expires_at,
+                ..
+            }) if Instant::now() < *expires_at => {
+                trace!("Using cached token from the `{}` helper", self.helper);
+                return Ok(Arc::clone(credentials));
+            }
+            Some(HelperState::Token { .. }) => {
+                debug!("The token from the `{}` helper expired", self.helper);
+            }
+            Some(HelperState::Failed(err)) => return Err(err.clone()),
+            None => {}
+        }
+        let result = self.issue().await;
+        *state = Some(match &result {
+            Ok(credentials) => {
+                let issued_at = Instant::now();
+                HelperState::Token {
+                    credentials: Arc::clone(credentials),
+                    issued_at,
+                    expires_at: issued_at + self.token_lifetime.saturating_sub(EXPIRY_MARGIN),
+                }
+            }
+            Err(err) => HelperState::Failed(err.clone()),
+        });
+        result
+    }
+
+    /// Replace a token that the index rejected, e.g., because it expired earlier than expected.
+    ///
+    /// Returns [`None`] if the rejected token was issued too recently to have expired, in which
+    /// case it lacks permissions and a new token wouldn't be accepted either.
+    pub async fn refresh(
+        &self,
+        rejected: &Credentials,
+    ) -> Result<Option<Arc<Credentials>>, CredentialHelperError> {
+        {
+            let mut state = self.state.lock().await;
+            if let Some(HelperState::Token {
+                credentials,
+                issued_at,
+                ..
+            }) = &*state
+            {
+                if **credentials != *rejected {
+                    // Another request refreshed the token already.
+                    return Ok(Some(Arc::clone(credentials)));
+                }
+                if issued_at.elapsed() < self.min_refresh_age {
+                    return Ok(None);
+                }
+                debug!(
+                    "The token from the `{}` helper was rejected, requesting a new one",
+                    self.helper
+                );
+                *state = None;
+            }
+        }
+        self.fetch().await.map(Some)
+    }
+
+    /// Run the helper to issue a new token.
+    async fn issue(&self) -> Result<Arc<Credentials>, CredentialHelperError> {
+        if self.not_installed.load(Ordering::Relaxed) {
+            return Err(self.not_installed_error());
+        }
+        debug!("Requesting a token from the `{}` helper", self.helper);
+        let args = self.helper.args();
+        let output =
+            tokio::time::timeout(HELPER_TIMEOUT, self.command.run(&args, None, STDERR_LIMIT))
+                .await
+                .map_err(|_| CredentialHelperError::TimedOut(self.helper.to_string()))?
+                .map_err(|err| {
+                    if err.kind() == std::io::ErrorKind::NotFound {
+                        self.not_installed.store(true, Ordering::Relaxed);
+                        self.not_installed_error()
+                    } else {
+                        CredentialHelperError::Failed {
+                            helper: self.helper.to_string(),
+                            message: err.to_string(),
+                        }
+                    }
+                })?;
+
+        if !output.success {
+            if let Some(login) = self.helper.login_command(&output.stderr) {
+                return Err(CredentialHelperError::NotLoggedIn {
+                    helper: self.helper.to_string(),
+                    login,
+                });
+            }
+            let message = output.stderr.trim().lines().next().unwrap_or_default();
+            return Err(CredentialHelperError::Failed {
+                helper: self.helper.to_string(),
+                message: if message.is_empty() {
+                    "the command exited with an error".to_string()
+                } else {
+                    message.to_string()
+                },
+            });
+        }
+        let token = String::from_utf8(output.stdout)
+            .map_err(|_| CredentialHelperError::InvalidToken(self.helper.to_string()))?;
+        let token = token.trim();
+        if token.is_empty() || token.contains(char::is_whitespace) || token == "None" {
+            return Err(CredentialHelperError::InvalidToken(self.helper.to_string()));
+        }
+        Ok(Arc::new(Credentials::new(
+            Some(self.helper.username().to_string()),
+            Some(token.to_string()),
+        )))
+    }
+
+    fn not_installed_error(&self) -> CredentialHelperError {
+        let (program, cli) = self.helper.program();
+        CredentialHelperError::NotInstalled {
+            program,
+            cli,
+            helper: self.helper.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyring_command::{KeyringOutput, ScriptedCommand};
+    use std::sync::atomic::AtomicUsize;
+
+    fn codeartifact() -> CredentialHelper {
+        CredentialHelper::AwsCodeartifact {
+            domain: "my-domain".to_string(),
+            domain_owner: "111122223333".to_string(),
+            region: None,
+        }
+    }
+
+    /// An `aws` command that issues a new token on each invocation.
+    fn aws() -> ScriptedCommand {
+        let issued = AtomicUsize::new(0);
+        ScriptedCommand::new(move |_| {
+            let n = issued.fetch_add(1, Ordering::Relaxed) + 1;
+            Ok(KeyringOutput {
+                success: true,
+                stdout: format!("token-{n}\n").into_bytes(),
+                stderr: String::new(),
+            })
+        })
+    }
+
+    fn token(token: &str) -> Credentials {
+        Credentials::new(Some("aws".to_string()), Some(token.to_string()))
+    }
+
+    #[tokio::test]
+    async fn fetch_caches_token() {
+        let command = Arc::new(aws());
+        let provider = CredentialHelperProvider::with_command(codeartifact(), command.clone());
+
+        assert_eq!(*provider.fetch().await.unwrap(), token("token-1"));
+        assert_eq!(*provider.fetch().await.unwrap(), token("token-1"));
+        assert_eq!(
+            command.calls(),
+            [(
+                [
+                    "codeartifact",
+                    "get-authorization-token",
+                    "--domain",
+                    "my-domain",
+                    "--domain-owner",
+                    "111122223333",
+                    "--query",
+                    "authorizationToken",
+                    "--output",
+                    "text"
+                ]
+                .map(ToString::to_string)
+                .to_vec(),
+                None
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_refreshes_expired_token() {
+        let command = Arc::new(aws());
+        // The token expires as soon as it's issued.
+        let provider = CredentialHelperProvider::with_command(codeartifact(), command.clone())
+            .with_token_lifetime(Duration::ZERO);
+
+        assert_eq!(*provider.fetch().await.unwrap(), token("token-1"));
+        assert_eq!(*provider.fetch().await.unwrap(), token("token-2"));
+        assert_eq!(command.calls().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn refresh_rejected_token() {
+        let command = Arc::new(aws());
+        let provider = CredentialHelperProvider::with_command(codeartifact(), command.clone());
+        let rejected = provider.fetch().await.unwrap();
+
+        // A token that was just issued is not replaced.
+        assert_eq!(provider.refresh(&rejected).await.unwrap(), None);
+        assert_eq!(command.calls().len(), 1);
+
+        // An older token is, once.
+        let provider = provider.with_min_refresh_age(Duration::ZERO);
+        assert_eq!(
+            provider.refresh(&rejected).await.unwrap().as_deref(),
+            Some(&token("token-2"))
+        );
+        assert_eq!(
+            provider.refresh(&rejected).await.unwrap().as_deref(),
+            Some(&token("token-2"))
+        );
+        assert_eq!(command.calls().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn aws_not_installed() {
+        let command = Arc::new(ScriptedCommand::new(|_| {
+            Err(std::io::Error::from(std::io::ErrorKind::NotFound))
+        }));
+        let provider = CredentialHelperProvider::with_command(codeartifact(), command.clone());
+
+        let err = provider.fetch().await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`aws` command not found; install the AWS CLI to use the `aws-codeartifact` credential helper"
+        );
+        // The failure is cached.
+        assert_eq!(provider.fetch().await.unwrap_err(), err);
+        assert_eq!(command.calls().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn aws_failure() {
+        let command = Arc::new(ScriptedCommand::exit(
+            false,
+            b"",
+            "\nAn error occurred (ExpiredTokenException) when calling the GetAuthorizationToken operation: The security token included in the request is expired\n",
+        ));
+        let provider = CredentialHelperProvider::with_command(codeartifact(), command);
+        assert_eq!(
+            provider.fetch().await.unwrap_err(),
+            CredentialHelperError::Failed {
+                helper: "aws-codeartifact".to_string(),
+                message: "An error occurred (ExpiredTokenException) when calling the GetAuthorizationToken operation: The security token included in the request is expired".to_string(),
+            }
+        );
+
+        // `--query` prints `None` if the response has no token.
+        let command = Arc::new(ScriptedCommand::exit(true, b"None\n", ""));
+        let provider = CredentialHelperProvider::with_command(codeartifact(), command);
+        assert_eq!(
+            provider.fetch().await.unwrap_err(),
+            CredentialHelperError::InvalidToken("aws-codeartifact".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn gcloud_fetch() {
+        let command = Arc::new(ScriptedCommand::exit(true, b"ya29.token\n", ""));
+        let provider = CredentialHelperProvider::with_command(
+            CredentialHelper::Gcloud { adc: false },
+            command.clone(),
+        );
+        assert_eq!(
+            *provider.fetch().await.unwrap(),
+            Credentials::new(
+                Some("oauth2accesstoken".to_string()),
+                Some("ya29.token".to_string())
+            )
+        );
+
+        let adc = CredentialHelperProvider::with_command(
+            CredentialHelper::Gcloud { adc: true },
+            command.clone(),
+        );
+        adc.fetch().await.unwrap();
+        assert_eq!(
+            command
+                .calls()
+                .into_iter()
+                .map(|(args, _)| args.join(" "))
+                .collect::<Vec<_>>(),
+            [
+                "auth print-access-token",
+                "auth application-default print-access-token"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn gcloud_not_logged_in() {
+        let command = Arc::new(ScriptedCommand::exit(
+            false,
+            b"",
+            "ERROR: (gcloud.auth.print-access-token) You do not currently have an active account selected.\nPlease run:\n\n  $ gcloud auth login\n\nto obtain new credentials.\n",
+        ));
+        let provider = CredentialHelperProvider::with_command(
+            CredentialHelper::Gcloud { adc: false },
+            command,
+        );
+        assert_eq!(
+            provider.fetch().await.unwrap_err().to_string(),
+            "The `gcloud` credential helper is not logged in; run `gcloud auth login` and try again"
+        );
+
+        let command = Arc::new(ScriptedCommand::exit(
+            false,
+            b"",
+            "ERROR: (gcloud.auth.application-default.print-access-token) Your default credentials were not found. To set up Application Default Credentials, see https://cloud.google.com/docs/authentication/external/set-up-adc for more information.\n",
+        ));
+        let provider =
+            CredentialHelperProvider::with_command(CredentialHelper::Gcloud { adc: true }, command);
+        assert_eq!(
+            provider.fetch().await.unwrap_err(),
+            CredentialHelperError::Failed {
+                helper: "gcloud".to_string(),
+                message: "ERROR: (gcloud.auth.application-default.print-access-token) Your default credentials were not found. To set up Application Default Credentials, see https://cloud.google.com/docs/authentication/external/set-up-adc for more information.".to_string(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn azure_fetch() {
+        let command = Arc::new(ScriptedCommand::exit(true, b"eyJ0eXAi.token\r\n", ""));
+        let provider =
+            CredentialHelperProvider::with_command(CredentialHelper::Azure, command.clone());
+        assert_eq!(
+            *provider.fetch().await.unwrap(),
+            Credentials::new(
+                Some("azure".to_string()),
+                Some("eyJ0eXAi.token".to_string())
+            )
+        );
+        assert_eq!(
+            command.calls()[0].0.join(" "),
+            "account get-access-token --resource 499b84ac-1321-427f-aa17-267ca6975798 --query accessToken --output tsv"
+        );
+
+        let command = Arc::new(ScriptedCommand::exit(
+            false,
+            b"",
+            "ERROR: Please run 'az login' to setup account.\n",
+        ));
+        let provider = CredentialHelperProvider::with_command(CredentialHelper::Azure, command);
+        assert_eq!(
+            provider.fetch().await.unwrap_err().to_string(),
+            "The `azure` credential helper is not logged in; run `az login` and try again"
+        );
+    }
+
+    #[test]
+    fn from_parts() {
+        assert_eq!(
+            CredentialHelper::from_parts(
+                "aws-codeartifact",
+                Some("my-domain".to_string()),
+                Some("111122223333".to_string()),
+                Some("eu-west-1".to_string()),
+                None,
+            ),
+            Ok(CredentialHelper::AwsCodeartifact {
+                domain: "my-domain".to_string(),
+                domain_owner: "111122223333".to_string(),
+                region: Some("eu-west-1".to_string()),
+            })
+        );
+        assert_eq!(
+            CredentialHelper::from_parts(
+                "aws-codeartifact",
+                Some("my-domain".to_string()),
+                None,
+                None,
+                None
+            ),
+            Err("missing field `domain-owner` for helper `aws-codeartifact`".to_string())
+        );
+        assert_eq!(
+            CredentialHelper::from_parts("gcloud", None, None, None, Some(true)),
+            Ok(CredentialHelper::Gcloud { adc: true })
+        );
+        assert_eq!(
+            CredentialHelper::from_parts("jfrog", None, None, None, None),
+            Err(
+                "unknown credential helper `jfrog`, expected `aws-codeartifact`, `azure`, or `gcloud`"
+                    .to_string()
+            )
+        );
+    }
+}
@@ -0,0 +1,211 @@
+use url::Url;
+
+use crate::keyring::KeyringError;
+use crate::SecretString;
+
+/// A credential description, as exchanged with `git credential` on stdin and stdout.
+///
+/// See <https://git-scm.com/docs/git-credential#IOFMT> for the format.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct GitCredential {
+    pub(crate) protocol: Option<String>,
+    pub(crate) host: Option<String>,
+    pub(crate) path: Option<String>,
+    pub(crate) username: Option<String>,
+    pub(crate) password: Option<String>,
+}
+
+impl GitCredential {
+    /// Describe the credentials for a keyring service name.
+    ///
+    /// Service names are URLs, or a host (and port) for entries stored by previous versions of
+    /// uv, which are looked up for `https`. Returns [`None`] for URLs without a host.
+    pub(crate) fn for_service(service_name: &str) -> Option<Self> {
+        // A host with a port, e.g., `example.com:8080`, parses as a URL with the scheme
+        // `example.com`, so only URLs with a host are taken as such.
+        if let Ok(url) = Url::parse(service_name) {
+            if let Some(host) = url.host_str() {
+                let host = match url.port() {
+                    Some(port) => format!("{host}:{port}"),
+                    None => host.to_string(),
+                };
+                let path = url.path().trim_matches('/');
+                return Some(Self {
+                    protocol: Some(url.scheme().to_string()),
+                    host: Some(host),
+                    path: (!path.is_empty()).then(|| path.to_string()),
+                    ..Self::default()
+                });
+            }
+        }
+        if service_name.is_empty() || service_name.contains('/') {
+            return None;
+        }
+        Some(Self {
+            protocol: Some("https".to_string()),
+            host: Some(service_name.to_string()),
+            ..Self::default()
+        })
+    }
+
+    /// Encode the description for the stdin of `git credential`, terminated by a blank line.
+    ///
+    /// Fails if a value contains a newline or a NUL byte, which the format can't represent.
+    ///
+    /// The description may include the password, so it's returned as a [`SecretString`], sized
+    /// up front so that no partial copy is left behind when it grows.
+    pub(crate) fn encode(&self) -> Result<SecretString, KeyringError> {
+        let fields = [
+            ("protocol", &self.protocol),
+            ("host", &self.host),
+            ("path", &self.path),
+            ("username", &self.username),
+            ("password", &self.password),
+        ];
+        let capacity = fields
+            .iter()
+            .filter_map(|(key, value)| Some(key.len() + value.as_ref()?.len() + 2))
+            .sum::<usize>()
+            + 1;
+        let mut encoded = String::with_capacity(capacity);
+        for (key, value) in fields {
+            let Some(value) = value else {
+                continue;
+            };
+            if value.contains(['\n', '\0']) {
+                return Err(KeyringError::BackendError(format!(
+                    "The {key} contains a newline or NUL byte, which can't be passed to `git credential`"
+                )));
+            }
+            encoded.push_str(key);
+            encoded.push('=');
+            encoded.push_str(value);
+            encoded.push('\n');
+        }
+        encoded.push('\n');
+        Ok(SecretString::new(encoded))
+    }
+
+    /// Decode the output of `git credential fill`.
+    ///
+    /// Each line is split at the first `=`, so values may contain `=`. Unknown attributes, e.g.,
+    /// `password_expiry_utc`, are ignored, as are lines without a `=`. The output ends at the
+    /// first blank line.
+    pub(crate) fn decode(output: &str) -> Self {
+        let mut credential = Self::default();
+        for line in output.lines() {
+            if line.is_empty() {
+                break;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = Some(value.to_string());
+            match key {
+                "protocol" => credential.protocol = value,
+                "host" => credential.host = value,
+                "path" => credential.path = value,
+                "username" => credential.username = value,
+                "password" => credential.password = value,
+                _ => {}
+            }
+        }
+        credential
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn for_service_url() {
+        assert_eq!(
+            GitCredential::for_service("https://example.com:8080/simple/"),
+            Some(GitCredential {
+                protocol: Some("https".to_string()),
+                host: Some("example.com:8080".to_string()),
+                path: Some("simple".to_string()),
+                ..GitCredential::default()
+            })
+        );
+        assert_eq!(
+            GitCredential::for_service("http://example.com"),
+            Some(GitCredential {
+                protocol: Some("http".to_string()),
+                host: Some("example.com".to_string()),
+                ..GitCredential::default()
+            })
+        );
+    }
+
+    #[test]
+    fn for_service_host() {
+        assert_eq!(
+            GitCredential::for_service("example.com:8080"),
+            Some(GitCredential {
+                protocol: Some("https".to_string()),
+                host: Some("example.com:8080".to_string()),
+                ..GitCredential::default()
+            })
+        );
+        assert_eq!(GitCredential::for_service("file:///tmp/wheels"), None);
+    }
+
+    #[test]
+    fn encode() {
+        let credential = GitCredential {
+            protocol: Some("https".to_string()),
+            host: Some("example.com".to_string()),
+            path: Some("simple".to_string()),
+            username: Some("user".to_string()),
+            password: Some("a=b=c".to_string()),
+        };
+        assert_eq!(
+            credential.encode().unwrap().expose(),
+            "protocol=https\nhost=example.com\npath=simple\nusername=user\npassword=a=b=c\n\n"
+        );
+
+        // Values can't span lines, which would inject another attribute.
+        let credential = GitCredential {
+            username: Some("user\nhost=attacker.example.com".to_string()),
+            ..credential
+        };
+        assert_eq!(
+            credential.encode().unwrap_err(),
+            KeyringError::BackendError(
+                "The username contains a newline or NUL byte, which can't be passed to `git credential`"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn decode() {
+        let credential = GitCredential::decode(
+            "protocol=https\nhost=example.com\nusername=user\npassword=pa=ss==\npassword_expiry_utc=1700000000\n",
+        );
+        assert_eq!(
+            credential,
+            GitCredential {
+                protocol: Some("https".to_string()),
+                host: Some("example.com".to_string()),
+                path: None,
+                username: Some("user".to_string()),
+                password: Some("pa=ss==".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn decode_edge_cases() {
+        // Windows line endings, an empty value, a line without `=`, and output after a blank line.
+        let credential = GitCredential::decode(
+            "username=\r\npassword==leading\r\ngarbage\n\npassword=ignored\n",
+        );
+        assert_eq!(credential.username.as_deref(), Some(""));
+        assert_eq!(credential.password.as_deref(), Some("=leading"));
+
+        assert_eq!(GitCredential::decode(""), GitCredential::default());
+    }
+}
@@ -0,0 +1,293 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use tempfile::TempDir;
+use tokio::io::AsyncWriteExt;
+use tokio::process::{Child, Command};
+use tracing::{debug, warn};
+
+/// How a secret is delivered to a helper subprocess.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecretDelivery {
+    /// Write the secret to the stdin of the subprocess.
+    Stdin,
+    /// Write the secret to a private file, and pass the path to the file in the given environment
+    /// variable of the subprocess.
+    File { path_var: String },
+    /// Set the secret in the given environment variable of the subprocess.
+    Env { var: String },
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SecretHandoffError {
+    #[error("Delivering secrets via {0} is not supported on this platform")]
+    Unsupported(&'static str),
+    #[error("Failed to create a private file for the secret")]
+    Io(#[from] std::io::Error),
+}
+
+/// A secret to hand off to a helper subprocess.
+///
+/// Secrets are delivered via stdin by default. Some helpers only accept secrets via a file or an
+/// environment variable; in that case, the secret is written to a file that is only readable by
+/// the current user, in a private directory that is removed once the handoff is dropped, or set in
+/// the environment of the subprocess only, never in uv's own environment.
+pub struct SecretHandoff<'a> {
+    secret: &'a str,
+    delivery: SecretDelivery,
+}
+
+impl std::fmt::Debug for SecretHandoff<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecretHandoff")
+            .field("secret", &"****")
+            .field("delivery", &self.delivery)
+            .finish()
+    }
+}
+
+impl<'a> SecretHandoff<'a> {
+    pub fn new(secret: &'a str, delivery: SecretDelivery) -> Self {
+        Self { secret, delivery }
+    }
+
+    /// Configure the command to receive the secret.
+    ///
+    /// The returned guard must be kept alive until the subprocess has exited, as any file
+    /// containing the secret is removed when it is dropped.
+    pub fn prepare(&self, command: &mut Command) -> Result<SecretGuard<'a>, SecretHandoffError> {
+        match &self.delivery {
+            SecretDelivery::Stdin => {
+                debug!("Delivering secret to helper via stdin");
+                command.stdin(std::process::Stdio::piped());
+                Ok(SecretGuard {
+                    stdin: Some(self.secret),
+                    dir: None,
+                })
+            }
+            SecretDelivery::File { path_var } => {
+                if !cfg!(unix) {
+                    return Err(SecretHandoffError::Unsupported("a file"));
+                }
+                debug!("Delivering secret to helper via a private file in `{path_var}`");
+                let dir = PrivateDir::new()?;
+                let path = dir.write_secret(self.secret)?;
+                command.env(path_var, path);
+                Ok(SecretGuard {
+                    stdin: None,
+                    dir: Some(dir),
+                })
+            }
+            SecretDelivery::Env { var } => {
+                if !cfg!(unix) {
+                    return Err(SecretHandoffError::Unsupported("an environment variable"));
+                }
+                debug!("Delivering secret to helper via the environment variable `{var}`");
+                command.env(var, self.secret);
+                Ok(SecretGuard {
+                    stdin: None,
+                    dir: None,
+                })
+            }
+        }
+    }
+}
+
+/// A guard for a prepared [`SecretHandoff`].
+///
+/// Removes any file containing the secret when dropped, including on panic.
+#[derive(Debug)]
+pub struct SecretGuard<'a> {
+    /// The secret to write to stdin, if delivered via stdin.
+    stdin: Option<&'a str>,
+    /// The private directory containing the secret, if delivered via a file.
+    dir: Option<PrivateDir>,
+}
+
+impl SecretGuard<'_> {
+    /// Deliver the secret to the spawned subprocess, if it is delivered via stdin.
+    pub async fn deliver(&mut self, child: &mut Child) -> std::io::Result<()> {
+        let Some(secret) = self.stdin.take() else {
+            return Ok(());
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(secret.as_bytes()).await?;
+            stdin.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// The path of the file containing the secret, if delivered via a file.
+    pub fn path(&self) -> Option<&Path> {
+        self.dir.as_ref().map(|dir| dir.file.as_path())
+    }
+}
+
+/// A directory that is only accessible by the current user, containing a secret file.
+#[derive(Debug)]
+struct PrivateDir {
+    dir: TempDir,
+    file: PathBuf,
+}
+
+impl PrivateDir {
+    fn new() -> std::io::Result<Self> {
+        let dir = tempfile::Builder::new().prefix("uv-secret-").tempdir()?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs_err::set_permissions(dir.path(), std::fs::Permissions::from_mode(0o700))?;
+        }
+        let file = dir.path().join("secret");
+        Ok(Self { dir, file })
+    }
+
+    /// Write the secret to a file that is only readable by the current user.
+    fn write_secret(&self, secret: &str) -> std::io::Result<&Path> {
+        let mut options = fs_err::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use fs_err::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&self.file)?;
+        file.write_all(secret.as_bytes())?;
+        file.flush()?;
+        Ok(&self.file)
+    }
+}
+
+impl Drop for PrivateDir {
+    fn drop(&mut self) {
+        // Overwrite the secret before removing it, in case the file outlives the directory, e.g.,
+        // if a helper retained a hard link to it.
+        if let Ok(metadata) = fs_err::metadata(&self.file) {
+            if let Ok(mut file) = fs_err::OpenOptions::new().write(true).open(&self.file) {
+                let length = usize::try_from(metadata.len()).unwrap_or_default();
+                let _ = file.write_all(&vec![0; length]);
+                let _ = file.flush();
+            }
+        }
+        if let Err(err) = fs_err::remove_dir_all(self.dir.path()) {
+            warn!("Failed to remove secret directory: {err}");
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::process::Stdio;
+
+    use super::*;
+
+    async fn run(command: &str, delivery: SecretDelivery) -> (Option<PathBuf>, bool, String) {
+        let handoff = SecretHandoff::new("hunter2", delivery);
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(command).stdout(Stdio::piped());
+        let mut guard = handoff.prepare(&mut cmd).unwrap();
+        let mut child = cmd.spawn().unwrap();
+        guard.deliver(&mut child).await.unwrap();
+        let output = child.wait_with_output().await.unwrap();
+        let path = guard.path().map(Path::to_path_buf);
+        drop(guard);
+        (
+            path,
+            output.status.success(),
+            String::from_utf8(output.stdout).unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn stdin() {
+        let (path, success, stdout) = run("cat", SecretDelivery::Stdin).await;
+        assert!(path.is_none());
+        assert!(success);
+        assert_eq!(stdout, "hunter2");
+    }
+
+    #[tokio::test]
+    async fn file_removed_on_success() {
+        let delivery = SecretDelivery::File {
+            path_var: "SECRET_FILE".to_string(),
+        };
+        let (path, success, stdout) = run(r#"cat "$SECRET_FILE""#, delivery).await;
+        assert!(success);
+        assert_eq!(stdout, "hunter2");
+        let path = path.unwrap();
+        assert!(!path.exists());
+        assert!(!path.parent().unwrap().exists());
+    }
+
+    #[test]
+    fn file_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let handoff = SecretHandoff::new(
+            "hunter2",
+            SecretDelivery::File {
+                path_var: "SECRET_FILE".to_string(),
+            },
+        );
+        let guard = handoff.prepare(&mut Command::new("true")).unwrap();
+        let path = guard.path().unwrap();
+        let mode = |path: &Path| fs_err::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(path), 0o600);
+        assert_eq!(mode(path.parent().unwrap()), 0o700);
+    }
+
+    #[tokio::test]
+    async fn file_removed_on_failure() {
+        let delivery = SecretDelivery::File {
+            path_var: "SECRET_FILE".to_string(),
+        };
+        let (path, success, _) = run("exit 1", delivery).await;
+        assert!(!success);
+        assert!(!path.unwrap().parent().unwrap().exists());
+    }
+
+    #[test]
+    fn file_removed_on_panic() {
+        let handoff = SecretHandoff::new(
+            "hunter2",
+            SecretDelivery::File {
+                path_var: "SECRET_FILE".to_string(),
+            },
+        );
+        let mut path = None;
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let guard = handoff.prepare(&mut Command::new("true")).unwrap();
+            path = guard.path().map(Path::to_path_buf);
+            assert!(path.as_ref().unwrap().exists());
+            panic!("Simulated failure while the helper is running");
+        }));
+        assert!(result.is_err());
+        assert!(!path.unwrap().parent().unwrap().exists());
+    }
+
+    #[tokio::test]
+    async fn env_only_in_child() {
+        let delivery = SecretDelivery::Env {
+            var: "UV_TEST_HANDOFF_SECRET".to_string(),
+        };
+        let (path, success, stdout) = run(r#"printf %s "$UV_TEST_HANDOFF_SECRET""#, delivery).await;
+        assert!(path.is_none());
+        assert!(success);
+        assert_eq!(stdout, "hunter2");
+
+        assert!(std::env::var_os("UV_TEST_HANDOFF_SECRET").is_none());
+        #[cfg(target_os = "linux")]
+        {
+            let environ = fs_err::read("/proc/self/environ").unwrap();
+            assert!(!environ
+                .windows("hunter2".len())
+                .any(|window| window == b"hunter2"));
+        }
+    }
+
+    #[test]
+    fn debug_redacts_secret() {
+        let handoff = SecretHandoff::new("hunter2", SecretDelivery::Stdin);
+        assert!(!format!("{handoff:?}").contains("hunter2"));
+    }
+}
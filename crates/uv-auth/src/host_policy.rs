@@ -0,0 +1,321 @@
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock};
+
+use url::{Host, Url};
+use uv_static::EnvVars;
+use uv_warnings::warn_user_once;
+
+/// The policy for the hosts that the keyring is queried for, set once per invocation.
+static KEYRING_HOST_POLICY: OnceLock<Arc<HostPolicy>> = OnceLock::new();
+
+/// Set the policy for the hosts that the keyring is queried for, for all keyring providers
+/// created afterwards.
+///
+/// Returns `false` if a policy was already set, in which case it's kept.
+pub fn set_keyring_host_policy(policy: HostPolicy) -> bool {
+    KEYRING_HOST_POLICY.set(Arc::new(policy)).is_ok()
+}
+
+/// The policy for the hosts that the keyring is queried for.
+///
+/// Without a policy set with [`set_keyring_host_policy`], the policy is read from
+/// `UV_KEYRING_ALLOW_HOSTS` and `UV_KEYRING_DENY_HOSTS`. If either is invalid, the keyring is not
+/// queried for any host.
+pub(crate) fn keyring_host_policy() -> Arc<HostPolicy> {
+    Arc::clone(KEYRING_HOST_POLICY.get_or_init(|| {
+        Arc::new(HostPolicy::resolve(None, None).unwrap_or_else(|err| {
+            warn_user_once!("{err}; the keyring is not queried for any host");
+            HostPolicy::deny_all()
+        }))
+    }))
+}
+
+/// An invalid pattern in a [`HostPolicy`].
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid host pattern `{pattern}`: {reason}")]
+pub struct HostPatternError {
+    pattern: String,
+    reason: String,
+}
+
+/// A pattern that matches the host of a URL.
+///
+/// Either an exact host, e.g., `pypi.example.com`, or a wildcard for its subdomains, e.g.,
+/// `*.example.com`, which doesn't match `example.com` itself. Either may be followed by a port,
+/// e.g., `localhost:8080`, to only match that port. Hosts are compared case-insensitively, with
+/// internationalized domain names in their ASCII form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostPattern {
+    host: HostMatch,
+    port: Option<u16>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum HostMatch {
+    /// The host itself.
+    Exact(Host),
+    /// Any subdomain of the domain.
+    Subdomains(String),
+}
+
+impl HostPattern {
+    /// Whether the host and port of the URL match the pattern.
+    pub fn matches(&self, url: &Url) -> bool {
+        let Some(host) = url.host() else {
+            return false;
+        };
+        if self
+            .port
+            .is_some_and(|port| url.port_or_known_default() != Some(port))
+        {
+            return false;
+        }
+        match (&self.host, host) {
+            (HostMatch::Exact(expected), host) => *expected == host.to_owned(),
+            (HostMatch::Subdomains(domain), Host::Domain(host)) => host
+                .strip_suffix(domain.as_str())
+                .is_some_and(|prefix| prefix.len() > 1 && prefix.ends_with('.')),
+            (HostMatch::Subdomains(_), _) => false,
+        }
+    }
+}
+
+impl FromStr for HostPattern {
+    type Err = HostPatternError;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        let err = |reason: &str| HostPatternError {
+            pattern: pattern.to_string(),
+            reason: reason.to_string(),
+        };
+
+        // Split the port, unless the colon is part of an IPv6 address, e.g., `[::1]`.
+        let (host, port) = match pattern.rsplit_once(':') {
+            Some((host, port)) if !host.contains(':') || host.ends_with(']') => {
+                let port = port.parse::<u16>().map_err(|_| err("invalid port"))?;
+                (host, Some(port))
+            }
+            _ => (pattern, None),
+        };
+
+        let host = if let Some(domain) = host.strip_prefix("*.") {
+            match Host::parse(domain).map_err(|parse_err| err(&parse_err.to_string()))? {
+                Host::Domain(domain) => HostMatch::Subdomains(domain),
+                _ => return Err(err("wildcards are only supported for domains")),
+            }
+        } else if host.contains('*') {
+            return Err(err("wildcards are only supported as a `*.` prefix"));
+        } else {
+            HostMatch::Exact(Host::parse(host).map_err(|parse_err| err(&parse_err.to_string()))?)
+        };
+        Ok(Self { host, port })
+    }
+}
+
+impl std::fmt::Display for HostPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.host {
+            HostMatch::Exact(host) => write!(f, "{host}")?,
+            HostMatch::Subdomains(domain) => write!(f, "*.{domain}")?,
+        }
+        if let Some(port) = self.port {
+            write!(f, ":{port}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The hosts that the keyring may be queried for, so that credentials are never sent to hosts
+/// outside of them, e.g., a lookalike of an internal index.
+///
+/// A host is denied if it matches a pattern of the denylist, or if there is an allowlist and it
+/// matches none of its patterns.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HostPolicy {
+    allow: Option<Vec<HostPattern>>,
+    deny: Vec<HostPattern>,
+}
+
+impl HostPolicy {
+    pub fn new(allow: Option<Vec<HostPattern>>, deny: Vec<HostPattern>) -> Self {
+        Self { allow, deny }
+    }
+
+    /// A policy that denies every host.
+    fn deny_all() -> Self {
+        Self {
+            allow: Some(Vec::new()),
+            deny: Vec::new(),
+        }
+    }
+
+    /// Resolve the policy from the given lists of patterns, e.g., from the configuration file, and
+    /// `UV_KEYRING_ALLOW_HOSTS` and `UV_KEYRING_DENY_HOSTS`, which take precedence.
+    ///
+    /// Patterns in the environment variables are separated by commas or whitespace.
+    pub fn resolve(
+        allow: Option<Vec<String>>,
+        deny: Option<Vec<String>>,
+    ) -> Result<Self, HostPatternError> {
+        let from_env = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .filter(|value| !value.trim().is_empty())
+                .map(|value| {
+                    value
+                        .split(|c: char| c == ',' || c.is_whitespace())
+                        .filter(|pattern| !pattern.is_empty())
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                })
+        };
+        let parse = |patterns: Vec<String>| {
+            patterns
+                .iter()
+                .map(|pattern| pattern.trim().parse())
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let allow = from_env(EnvVars::UV_KEYRING_ALLOW_HOSTS)
+            .or(allow)
+            .map(parse)
+            .transpose()?;
+        let deny = from_env(EnvVars::UV_KEYRING_DENY_HOSTS)
+            .or(deny)
+            .map(parse)
+            .transpose()?
+            .unwrap_or_default();
+        Ok(Self::new(allow, deny))
+    }
+
+    /// Whether the keyring may be queried for the host of the URL.
+    pub fn allows(&self, url: &Url) -> bool {
+        if self.deny.iter().any(|pattern| pattern.matches(url)) {
+            return false;
+        }
+        self.allow
+            .as_ref()
+            .is_none_or(|allow| allow.iter().any(|pattern| pattern.matches(url)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(pattern: &str) -> HostPattern {
+        pattern.parse().unwrap()
+    }
+
+    fn matches(pattern: &str, url: &str) -> bool {
+        self::pattern(pattern).matches(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn exact_host() {
+        assert!(matches(
+            "pypi.example.com",
+            "https://pypi.example.com/simple/"
+        ));
+        assert!(!matches("pypi.example.com", "https://example.com/simple/"));
+        assert!(!matches(
+            "pypi.example.com",
+            "https://pypi.example.com.evil.io/simple/"
+        ));
+        assert!(matches("127.0.0.1", "http://127.0.0.1:8080/simple/"));
+        assert!(matches("[::1]", "http://[::1]:8080/simple/"));
+    }
+
+    #[test]
+    fn subdomain_wildcard() {
+        assert!(matches("*.example.com", "https://pypi.example.com/simple/"));
+        assert!(matches("*.example.com", "https://a.b.example.com/simple/"));
+        // The wildcard doesn't match the domain itself, or lookalikes.
+        assert!(!matches("*.example.com", "https://example.com/simple/"));
+        assert!(!matches("*.example.com", "https://evilexample.com/simple/"));
+        assert!(!matches(
+            "*.example.com",
+            "https://pypi.example.com.evil.io/simple/"
+        ));
+        assert!(!matches("*.example.com", "https://127.0.0.1/simple/"));
+    }
+
+    #[test]
+    fn case_insensitive() {
+        assert!(matches("PyPI.Example.COM", "https://pypi.example.com/"));
+        assert!(matches("*.EXAMPLE.com", "https://PYPI.example.COM/"));
+    }
+
+    #[test]
+    fn internationalized_domain_names() {
+        // Both the pattern and the URL are compared in their ASCII form.
+        assert!(matches("bücher.example", "https://xn--bcher-kva.example/"));
+        assert!(matches("xn--bcher-kva.example", "https://bücher.example/"));
+        assert!(matches("*.bücher.example", "https://pypi.BÜCHER.example/"));
+        assert!(!matches("bücher.example", "https://bucher.example/"));
+        assert_eq!(
+            pattern("*.Bücher.example").to_string(),
+            "*.xn--bcher-kva.example"
+        );
+    }
+
+    #[test]
+    fn ports() {
+        assert!(matches("localhost:8080", "http://localhost:8080/simple/"));
+        assert!(!matches("localhost:8080", "http://localhost:8081/simple/"));
+        assert!(!matches("localhost:8080", "http://localhost/simple/"));
+        // The default port of the scheme is matched.
+        assert!(matches("example.com:443", "https://example.com/simple/"));
+        assert!(matches("*.example.com:443", "https://pypi.example.com/"));
+        assert!(!matches("*.example.com:443", "http://pypi.example.com/"));
+        assert!(matches("[::1]:8080", "http://[::1]:8080/"));
+        // Without a port, any port is matched.
+        assert!(matches("localhost", "http://localhost:8081/simple/"));
+    }
+
+    #[test]
+    fn invalid_patterns() {
+        for (pattern, message) in [
+            (
+                "example.com:http",
+                "Invalid host pattern `example.com:http`: invalid port",
+            ),
+            (
+                "pypi.*.example.com",
+                "Invalid host pattern `pypi.*.example.com`: wildcards are only supported as a `*.` prefix",
+            ),
+            (
+                "*.127.0.0.1",
+                "Invalid host pattern `*.127.0.0.1`: wildcards are only supported for domains",
+            ),
+            ("", "Invalid host pattern ``: empty host"),
+        ] {
+            assert_eq!(
+                pattern.parse::<HostPattern>().unwrap_err().to_string(),
+                message
+            );
+        }
+    }
+
+    #[test]
+    fn policy() {
+        let url = |url: &str| Url::parse(url).unwrap();
+
+        // Without lists, every host is allowed.
+        assert!(HostPolicy::default().allows(&url("https://example.com")));
+
+        // With an allowlist, hosts that aren't listed are denied.
+        let policy = HostPolicy::new(Some(vec![pattern("*.corp.example")]), vec![]);
+        assert!(policy.allows(&url("https://pypi.corp.example/simple/")));
+        assert!(!policy.allows(&url("https://pypi.corp-example.com/simple/")));
+
+        // The denylist takes precedence.
+        let policy = HostPolicy::new(
+            Some(vec![pattern("*.corp.example")]),
+            vec![pattern("public.corp.example")],
+        );
+        assert!(policy.allows(&url("https://pypi.corp.example/simple/")));
+        assert!(!policy.allows(&url("https://public.corp.example/simple/")));
+
+        assert!(!HostPolicy::deny_all().allows(&url("https://pypi.corp.example/")));
+    }
+}
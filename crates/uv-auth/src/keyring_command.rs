@@ -0,0 +1,699 @@
+use std::fmt::{Debug, Write};
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::{Arc, LazyLock, OnceLock};
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::process::{Child, ChildStdin, Command};
+use tracing::{debug, trace};
+use uv_static::EnvVars;
+
+use crate::secret::fingerprint;
+
+/// The `keyring` command on Windows, resolved once per process, see
+/// [`FallbackCommand::windows`].
+static WINDOWS_KEYRING: LazyLock<Arc<FallbackCommand>> =
+    LazyLock::new(|| Arc::new(FallbackCommand::windows()));
+
+/// The command that runs the `keyring` CLI by default.
+///
+/// On Windows, the `keyring` CLI may only be reachable as `keyring.exe` or `py -m keyring`, so
+/// those are tried in turn, see [`FallbackCommand`].
+pub(crate) fn default_keyring_command() -> Arc<dyn KeyringCommand> {
+    if cfg!(windows) {
+        WINDOWS_KEYRING.clone()
+    } else {
+        Arc::new(KeyringExecutable::new("keyring"))
+    }
+}
+
+/// A way of running the `keyring` command.
+///
+/// The real implementation spawns a process, while tests can substitute a scripted runner to
+/// exercise the handling of the output without an executable on disk.
+#[async_trait::async_trait]
+pub(crate) trait KeyringCommand: Debug + Send + Sync {
+    /// Run the command with the given arguments and wait for it to exit.
+    ///
+    /// If `stdin` is provided, it's written to the standard input of the command, and redacted
+    /// from the captured stderr. At most `stderr_limit` bytes of stderr are captured.
+    ///
+    /// Dropping the returned future must terminate the command, so that it can be bounded by a
+    /// timeout.
+    async fn run(
+        &self,
+        args: &[&str],
+        stdin: Option<&str>,
+        stderr_limit: usize,
+    ) -> std::io::Result<KeyringOutput>;
+}
+
+/// Run an executable, e.g., the `keyring` CLI on the `PATH`.
+#[derive(Debug)]
+pub(crate) struct KeyringExecutable {
+    program: PathBuf,
+    /// Arguments passed before those of the `keyring` CLI, e.g., `-m keyring` for `python3`.
+    prefix: Vec<String>,
+    /// Whether to pass `PYTHONHOME` and `PYTHONPATH` through to the command.
+    inherit_python_env: bool,
+    /// Environment variables to set for the command, or to remove if the value is [`None`].
+    envs: Vec<(&'static str, Option<&'static str>)>,
+}
+
+impl KeyringExecutable {
+    pub(crate) fn new(program: impl Into<PathBuf>) -> Self {
+        Self {
+            program: program.into(),
+            prefix: Vec::new(),
+            inherit_python_env: inherit_python_env(),
+            envs: Vec::new(),
+        }
+    }
+
+    /// Run `git credential`, which never prompts for credentials that no helper provides.
+    ///
+    /// Terminal prompts are disabled with `GIT_TERMINAL_PROMPT=0`, and askpass programs by
+    /// removing `GIT_ASKPASS` and `SSH_ASKPASS` and clearing `core.askPass`, so `git credential
+    /// fill` fails instead.
+    pub(crate) fn git_credential() -> Self {
+        Self {
+            program: PathBuf::from("git"),
+            prefix: vec![
+                "-c".to_string(),
+                "core.askPass=".to_string(),
+                "credential".to_string(),
+            ],
+            inherit_python_env: true,
+            envs: vec![
+                (EnvVars::GIT_TERMINAL_PROMPT, Some("0")),
+                (EnvVars::GIT_ASKPASS, None),
+                (EnvVars::SSH_ASKPASS, None),
+            ],
+        }
+    }
+
+    /// Parse a command line that stands in for `keyring`, e.g., `python3 -m keyring`.
+    ///
+    /// The command line is split into words like a POSIX shell would, so arguments containing
+    /// spaces can be quoted.
+    pub(crate) fn from_command_line(command_line: &str) -> Result<Self, KeyringCommandError> {
+        let words = shlex::split(command_line)
+            .ok_or_else(|| KeyringCommandError::Malformed(command_line.to_string()))?;
+        let mut words = words.into_iter();
+        let program = words.next().ok_or(KeyringCommandError::Empty)?;
+        Ok(Self {
+            program: PathBuf::from(program),
+            prefix: words.collect(),
+            inherit_python_env: inherit_python_env(),
+            envs: Vec::new(),
+        })
+    }
+
+    /// Build the process for the given arguments of the `keyring` CLI.
+    fn command(&self, args: &[&str]) -> Command {
+        let mut command = Command::new(&self.program);
+        command.args(&self.prefix).args(args);
+        // The `keyring` CLI runs on its own interpreter, with its backends installed there. The
+        // `PYTHONHOME` or `PYTHONPATH` of a project environment would make it fail to import them.
+        if !self.inherit_python_env {
+            command
+                .env_remove(EnvVars::PYTHONHOME)
+                .env_remove(EnvVars::PYTHONPATH);
+        }
+        // Decode the output predictably, regardless of the locale, and don't pass on the password
+        // that `uv index credentials set` read from the environment.
+        command
+            .env(EnvVars::PYTHONIOENCODING, "utf-8")
+            .env_remove(EnvVars::UV_ADD_CREDENTIALS_PASSWORD);
+        for (key, value) in &self.envs {
+            match value {
+                Some(value) => command.env(key, value),
+                None => command.env_remove(key),
+            };
+        }
+        command
+    }
+}
+
+/// Whether `UV_KEYRING_INHERIT_ENV=1` is set, to pass `PYTHONHOME` and `PYTHONPATH` through to
+/// the `keyring` command.
+fn inherit_python_env() -> bool {
+    std::env::var(EnvVars::UV_KEYRING_INHERIT_ENV).as_deref() == Ok("1")
+}
+
+/// An invalid override of the `keyring` command.
+#[derive(Debug, thiserror::Error)]
+pub enum KeyringCommandError {
+    #[error("The keyring command in `UV_KEYRING_COMMAND` is empty")]
+    Empty,
+    #[error(
+        "The keyring command in `UV_KEYRING_COMMAND` has an unterminated quote or escape: `{0}`"
+    )]
+    Malformed(String),
+}
+
+#[async_trait::async_trait]
+impl KeyringCommand for KeyringExecutable {
+    async fn run(
+        &self,
+        args: &[&str],
+        stdin: Option<&str>,
+        stderr_limit: usize,
+    ) -> std::io::Result<KeyringOutput> {
+        let mut child = self
+            .command(args)
+            .stdin(if stdin.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+
+        if let (Some(password), Some(pipe)) = (stdin, child.stdin.take()) {
+            write_password(pipe, password).await?;
+        }
+        wait_with_bounded_output(child, stderr_limit, stdin).await
+    }
+}
+
+/// Run the first of several invocations of the `keyring` CLI that is installed, e.g.,
+/// `keyring.exe` if `keyring` is not found.
+///
+/// An invocation is skipped if it can't be spawned because the program is not found, or if Python
+/// reports that the `keyring` module is not installed. The first invocation that runs is used for
+/// every later call; if none does, every call fails as not found, without spawning again.
+#[derive(Debug)]
+pub(crate) struct FallbackCommand {
+    candidates: Vec<(String, Arc<dyn KeyringCommand>)>,
+    /// The position of the invocation that runs, or [`None`] if none does, once resolved.
+    selected: OnceLock<Option<usize>>,
+}
+
+impl FallbackCommand {
+    /// Try the given invocations in order, each with a description for logging.
+    pub(crate) fn new(candidates: Vec<(String, Arc<dyn KeyringCommand>)>) -> Self {
+        Self {
+            candidates,
+            selected: OnceLock::new(),
+        }
+    }
+
+    /// Try `keyring`, `keyring.exe`, and `py -m keyring`, the ways the `keyring` CLI is commonly
+    /// installed on Windows.
+    fn windows() -> Self {
+        Self::new(vec![
+            (
+                "keyring".to_string(),
+                Arc::new(KeyringExecutable::new("keyring")),
+            ),
+            (
+                "keyring.exe".to_string(),
+                Arc::new(KeyringExecutable::new("keyring.exe")),
+            ),
+            (
+                "py -m keyring".to_string(),
+                Arc::new(
+                    KeyringExecutable::from_command_line("py -m keyring")
+                        .expect("The command line should be valid"),
+                ),
+            ),
+        ])
+    }
+
+    /// Whether the output shows that Python could not find the `keyring` module, e.g., for
+    /// `py -m keyring`.
+    fn is_missing_module(output: &KeyringOutput) -> bool {
+        !output.success && output.stderr.contains("No module named keyring")
+    }
+}
+
+#[async_trait::async_trait]
+impl KeyringCommand for FallbackCommand {
+    async fn run(
+        &self,
+        args: &[&str],
+        stdin: Option<&str>,
+        stderr_limit: usize,
+    ) -> std::io::Result<KeyringOutput> {
+        if let Some(selected) = self.selected.get() {
+            return match selected {
+                Some(position) => {
+                    self.candidates[*position]
+                        .1
+                        .run(args, stdin, stderr_limit)
+                        .await
+                }
+                None => Err(std::io::ErrorKind::NotFound.into()),
+            };
+        }
+
+        for (position, (description, command)) in self.candidates.iter().enumerate() {
+            match command.run(args, stdin, stderr_limit).await {
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                    trace!("`{description}` was not found");
+                }
+                Ok(output) if Self::is_missing_module(&output) => {
+                    trace!("`{description}` is not installed");
+                }
+                result => {
+                    if self.selected.set(Some(position)).is_ok() {
+                        debug!("Using `{description}` to run the `keyring` CLI");
+                    }
+                    return result;
+                }
+            }
+        }
+        if self.selected.set(None).is_ok() {
+            debug!(
+                "None of {} was found",
+                self.candidates
+                    .iter()
+                    .map(|(description, _)| format!("`{description}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        Err(std::io::ErrorKind::NotFound.into())
+    }
+}
+
+/// Write the password to the stdin of the `keyring` process.
+///
+/// The entire password is written, even if the pipe only accepts partial writes, and stdin is
+/// closed afterwards so that the process sees EOF.
+async fn write_password(mut stdin: ChildStdin, password: &str) -> std::io::Result<()> {
+    stdin.write_all(password.as_bytes()).await?;
+    stdin.flush().await?;
+    drop(stdin);
+    Ok(())
+}
+
+/// The output of a `keyring` process.
+#[derive(Debug)]
+pub(crate) struct KeyringOutput {
+    /// Whether the process exited successfully.
+    pub(crate) success: bool,
+    pub(crate) stdout: Vec<u8>,
+    /// The captured stderr, bounded in size and with any secret redacted.
+    pub(crate) stderr: String,
+}
+
+/// Wait for the `keyring` process to exit, capturing at most `stderr_limit` bytes of stderr.
+///
+/// Both streams are drained concurrently until the process exits, so a process that writes more
+/// than the limit cannot block on a full pipe. If a `secret` is provided, any occurrence of it in
+/// the captured stderr is replaced with its [`fingerprint`], e.g., `[sha256:f52fbd32]`.
+async fn wait_with_bounded_output(
+    mut child: Child,
+    stderr_limit: usize,
+    secret: Option<&str>,
+) -> std::io::Result<KeyringOutput> {
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+
+    // Capture enough additional output that a secret straddling the limit is still redacted
+    // before the output is truncated.
+    let capture_limit = stderr_limit.saturating_add(secret.map_or(0, str::len));
+
+    let read_stdout = async {
+        let mut buffer = Vec::new();
+        if let Some(mut stdout) = stdout {
+            stdout.read_to_end(&mut buffer).await?;
+        }
+        Ok(buffer)
+    };
+    let read_stderr = async {
+        match stderr {
+            Some(stderr) => read_bounded(stderr, capture_limit).await,
+            None => Ok((Vec::new(), 0)),
+        }
+    };
+
+    let (stdout, (stderr, stderr_len), status) =
+        tokio::try_join!(read_stdout, read_stderr, child.wait())?;
+
+    let mut stderr = String::from_utf8_lossy(&stderr).into_owned();
+    if let Some(secret) = secret.filter(|secret| !secret.is_empty()) {
+        stderr = stderr.replace(secret, &format!("[{}]", fingerprint(secret)));
+    }
+    if stderr_len > stderr_limit {
+        let mut end = stderr_limit.min(stderr.len());
+        while !stderr.is_char_boundary(end) {
+            end -= 1;
+        }
+        stderr.truncate(end);
+        write!(
+            stderr,
+            "... [truncated, {stderr_len} bytes of stderr in total]"
+        )
+        .expect("writing to a `String` cannot fail");
+    }
+
+    Ok(KeyringOutput {
+        success: status.success(),
+        stdout,
+        stderr,
+    })
+}
+
+/// Read the given stream to the end, retaining at most `limit` bytes.
+///
+/// Returns the retained bytes and the total number of bytes read.
+async fn read_bounded(
+    mut reader: impl AsyncRead + Unpin,
+    limit: usize,
+) -> std::io::Result<(Vec<u8>, usize)> {
+    let mut buffer = Vec::new();
+    let mut chunk = vec![0; 8192];
+    let mut total = 0;
+    loop {
+        let read = reader.read(&mut chunk).await?;
+        if read == 0 {
+            break;
+        }
+        total += read;
+        let retained = read.min(limit.saturating_sub(buffer.len()));
+        buffer.extend_from_slice(&chunk[..retained]);
+    }
+    Ok((buffer, total))
+}
+
+/// The arguments and stdin of an invocation of a [`ScriptedCommand`].
+#[cfg(test)]
+pub(crate) type Invocation = (Vec<String>, Option<String>);
+
+/// The response of a [`ScriptedCommand`] to the arguments of an invocation.
+#[cfg(test)]
+type Script = dyn Fn(&[&str]) -> std::io::Result<KeyringOutput> + Send + Sync;
+
+/// A [`KeyringCommand`] that returns scripted output, for testing.
+///
+/// Each invocation is recorded, along with the data written to stdin.
+#[cfg(test)]
+pub(crate) struct ScriptedCommand {
+    script: Box<Script>,
+    calls: std::sync::Mutex<Vec<Invocation>>,
+}
+
+#[cfg(test)]
+impl ScriptedCommand {
+    /// Respond to each invocation by calling `script` with its arguments.
+    pub(crate) fn new(
+        script: impl Fn(&[&str]) -> std::io::Result<KeyringOutput> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            script: Box::new(script),
+            calls: std::sync::Mutex::default(),
+        }
+    }
+
+    /// Respond to every invocation with the given exit status, stdout, and stderr.
+    pub(crate) fn exit(success: bool, stdout: &[u8], stderr: &str) -> Self {
+        let stdout = stdout.to_vec();
+        let stderr = stderr.to_string();
+        Self::new(move |_| {
+            Ok(KeyringOutput {
+                success,
+                stdout: stdout.clone(),
+                stderr: stderr.clone(),
+            })
+        })
+    }
+
+    /// Return the arguments and stdin of each invocation so far.
+    pub(crate) fn calls(&self) -> Vec<Invocation> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+impl Debug for ScriptedCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScriptedCommand").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+#[async_trait::async_trait]
+impl KeyringCommand for ScriptedCommand {
+    async fn run(
+        &self,
+        args: &[&str],
+        stdin: Option<&str>,
+        _stderr_limit: usize,
+    ) -> std::io::Result<KeyringOutput> {
+        self.calls.lock().unwrap().push((
+            args.iter().map(ToString::to_string).collect(),
+            stdin.map(ToString::to_string),
+        ));
+        // Yield like a process would, so that concurrent callers interleave.
+        tokio::task::yield_now().await;
+        (self.script)(args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_line() {
+        let command = KeyringExecutable::from_command_line("python3 -m keyring").unwrap();
+        assert_eq!(command.program, PathBuf::from("python3"));
+        assert_eq!(command.prefix, ["-m", "keyring"]);
+
+        let command = KeyringExecutable::from_command_line("corp-keyring").unwrap();
+        assert_eq!(command.program, PathBuf::from("corp-keyring"));
+        assert!(command.prefix.is_empty());
+    }
+
+    #[test]
+    fn command_line_quoted() {
+        let command = KeyringExecutable::from_command_line(
+            r#""/opt/corp tools/corp-keyring" --profile 'team a' --label team\ b"#,
+        )
+        .unwrap();
+        assert_eq!(
+            command.program,
+            PathBuf::from("/opt/corp tools/corp-keyring")
+        );
+        assert_eq!(command.prefix, ["--profile", "team a", "--label", "team b"]);
+    }
+
+    #[test]
+    fn python_env() {
+        let envs = |command: &KeyringExecutable| -> Vec<(String, Option<String>)> {
+            let mut envs: Vec<_> = command
+                .command(&["get", "example.com", "user"])
+                .as_std()
+                .get_envs()
+                .map(|(key, value)| {
+                    (
+                        key.to_string_lossy().into_owned(),
+                        value.map(|value| value.to_string_lossy().into_owned()),
+                    )
+                })
+                .collect();
+            envs.sort();
+            envs
+        };
+
+        let mut command = KeyringExecutable::from_command_line("python3 -m keyring").unwrap();
+        command.inherit_python_env = false;
+        assert_eq!(
+            envs(&command),
+            [
+                ("PYTHONHOME".to_string(), None),
+                ("PYTHONIOENCODING".to_string(), Some("utf-8".to_string())),
+                ("PYTHONPATH".to_string(), None),
+                ("UV_ADD_CREDENTIALS_PASSWORD".to_string(), None),
+            ]
+        );
+
+        // With `UV_KEYRING_INHERIT_ENV=1`, only the encoding is set, and the password removed.
+        command.inherit_python_env = true;
+        assert_eq!(
+            envs(&command),
+            [
+                ("PYTHONIOENCODING".to_string(), Some("utf-8".to_string())),
+                ("UV_ADD_CREDENTIALS_PASSWORD".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn command_line_invalid() {
+        assert!(matches!(
+            KeyringExecutable::from_command_line(""),
+            Err(KeyringCommandError::Empty)
+        ));
+        assert!(matches!(
+            KeyringExecutable::from_command_line("  "),
+            Err(KeyringCommandError::Empty)
+        ));
+        let err = KeyringExecutable::from_command_line("python3 -m 'keyring").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The keyring command in `UV_KEYRING_COMMAND` has an unterminated quote or escape: `python3 -m 'keyring`"
+        );
+    }
+
+    #[tokio::test]
+    async fn fallback_command() {
+        let not_found = Arc::new(ScriptedCommand::new(|_| {
+            Err(std::io::ErrorKind::NotFound.into())
+        }));
+        let no_module = Arc::new(ScriptedCommand::exit(
+            false,
+            b"",
+            "C:\\Python312\\python.exe: No module named keyring",
+        ));
+        let found = Arc::new(ScriptedCommand::exit(true, b"password", ""));
+        let command = FallbackCommand::new(vec![
+            (
+                "keyring".to_string(),
+                not_found.clone() as Arc<dyn KeyringCommand>,
+            ),
+            ("py -m keyring".to_string(), no_module.clone()),
+            ("keyring.exe".to_string(), found.clone()),
+        ]);
+
+        let output = command
+            .run(&["get", "example.com", "user"], None, 1024)
+            .await;
+        assert_eq!(output.unwrap().stdout, b"password");
+
+        // The invocation that ran is used from then on.
+        let output = command
+            .run(&["get", "example.com", "other"], None, 1024)
+            .await;
+        assert_eq!(output.unwrap().stdout, b"password");
+        assert_eq!(not_found.calls().len(), 1);
+        assert_eq!(no_module.calls().len(), 1);
+        assert_eq!(found.calls().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn fallback_command_not_found() {
+        let candidates = (0..3)
+            .map(|_| {
+                Arc::new(ScriptedCommand::new(|_| {
+                    Err(std::io::ErrorKind::NotFound.into())
+                }))
+            })
+            .collect::<Vec<_>>();
+        let command = FallbackCommand::new(
+            candidates
+                .iter()
+                .enumerate()
+                .map(|(position, candidate)| {
+                    (
+                        position.to_string(),
+                        candidate.clone() as Arc<dyn KeyringCommand>,
+                    )
+                })
+                .collect(),
+        );
+
+        // Once none was found, none is spawned again.
+        for _ in 0..2 {
+            let err = command
+                .run(&["get", "example.com", "user"], None, 1024)
+                .await;
+            assert_eq!(err.unwrap_err().kind(), std::io::ErrorKind::NotFound);
+        }
+        for candidate in candidates {
+            assert_eq!(candidate.calls().len(), 1);
+        }
+    }
+
+    /// Long passwords (e.g., generated tokens) must reach the `keyring` process intact.
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn write_password_long() {
+        let password = "x".repeat(4096);
+
+        // `cat` echoes its stdin and only exits once stdin is closed.
+        let mut child = Command::new("cat")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        write_password(child.stdin.take().unwrap(), &password)
+            .await
+            .unwrap();
+        let output = child.wait_with_output().await.unwrap();
+
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), password);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn bounded_output_truncates_stderr() {
+        // Emit far more stderr than the limit, then write to stdout to ensure that the process
+        // can't block on a full stderr pipe.
+        let child = Command::new("sh")
+            .arg("-c")
+            .arg("head -c 1000000 /dev/zero | tr '\\0' 'e' >&2; echo done")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let output = wait_with_bounded_output(child, 1024, None).await.unwrap();
+
+        assert!(output.success);
+        assert_eq!(
+            output.stderr,
+            format!(
+                "{}... [truncated, 1000000 bytes of stderr in total]",
+                "e".repeat(1024)
+            )
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn bounded_output_redacts_secret() {
+        let child = Command::new("sh")
+            .arg("-c")
+            .arg("echo 'Failed to store hunter2 for user' >&2; exit 1")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let output = wait_with_bounded_output(child, 1024, Some("hunter2"))
+            .await
+            .unwrap();
+
+        assert!(!output.success);
+        assert_eq!(
+            output.stderr,
+            "Failed to store [sha256:f52fbd32] for user\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn bounded_output_redacts_secret_at_limit() {
+        // The secret straddles the limit, and must not be partially retained.
+        let child = Command::new("sh")
+            .arg("-c")
+            .arg("printf 'abcdhunter2' >&2")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let output = wait_with_bounded_output(child, 6, Some("hunter2"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            output.stderr,
+            "abcd[s... [truncated, 11 bytes of stderr in total]"
+        );
+    }
+}
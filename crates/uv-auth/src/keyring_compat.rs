@@ -0,0 +1,112 @@
+use std::sync::{Arc, OnceLock};
+
+use url::Url;
+
+/// The tools whose keyring entries are checked, set once per invocation.
+static KEYRING_COMPAT: OnceLock<Arc<[KeyringCompat]>> = OnceLock::new();
+
+/// Set the tools whose keyring entries are checked, for all keyring providers created afterwards.
+///
+/// Returns `false` if the tools were already set, in which case they're kept.
+pub fn set_keyring_compat(compat: Vec<KeyringCompat>) -> bool {
+    KEYRING_COMPAT.set(Arc::from(compat)).is_ok()
+}
+
+/// The tools whose keyring entries are checked.
+///
+/// Without a call to [`set_keyring_compat`], only the service names used by uv are checked.
+pub(crate) fn keyring_compat() -> Arc<[KeyringCompat]> {
+    Arc::clone(KEYRING_COMPAT.get_or_init(|| Arc::from([])))
+}
+
+/// Another tool that stores credentials in the keyring, whose service names are checked after
+/// those used by uv.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyringCompat {
+    /// `twine`, which stores credentials under the upload URL of the repository, e.g.,
+    /// `https://upload.pypi.org/legacy/`.
+    Twine,
+    /// `poetry`, which stores credentials under `poetry-repository-<name>`, with the name of the
+    /// repository.
+    Poetry,
+}
+
+/// The index that a keyring lookup is for, used to find the credentials stored by other tools.
+#[derive(Debug, Clone, Copy)]
+pub struct KeyringIndex<'a> {
+    /// The name of the index.
+    pub name: Option<&'a str>,
+    /// The URL that packages are published to for the index, if any.
+    pub publish_url: Option<&'a Url>,
+}
+
+impl<'a> KeyringIndex<'a> {
+    /// An index with the given name, without a publish URL.
+    pub fn named(name: &'a str) -> Self {
+        Self {
+            name: Some(name),
+            publish_url: None,
+        }
+    }
+}
+
+/// Return the service names that the given tools store the credentials for an index under, in
+/// order.
+///
+/// `twine` uses the upload URL as configured, so its entries are found only if the publish URL of
+/// the index is known. Names that uv checks for the URL itself are skipped by the caller.
+pub(crate) fn compat_service_names(
+    compat: &[KeyringCompat],
+    index: Option<&KeyringIndex>,
+) -> Vec<String> {
+    let Some(index) = index else {
+        return Vec::new();
+    };
+    let mut names = Vec::new();
+    for tool in compat {
+        let name = match tool {
+            KeyringCompat::Twine => index.publish_url.map(ToString::to_string),
+            KeyringCompat::Poetry => index.name.map(|name| format!("poetry-repository-{name}")),
+        };
+        if let Some(name) = name {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn service_names() {
+        let publish_url = Url::parse("https://upload.example.com/legacy/").unwrap();
+        let index = KeyringIndex {
+            name: Some("internal"),
+            publish_url: Some(&publish_url),
+        };
+
+        assert!(compat_service_names(&[], Some(&index)).is_empty());
+        assert!(
+            compat_service_names(&[KeyringCompat::Twine, KeyringCompat::Poetry], None).is_empty()
+        );
+        assert_eq!(
+            compat_service_names(&[KeyringCompat::Poetry, KeyringCompat::Twine], Some(&index)),
+            [
+                "poetry-repository-internal",
+                "https://upload.example.com/legacy/"
+            ]
+        );
+        // Without a publish URL, there's no `twine` entry to check.
+        assert_eq!(
+            compat_service_names(
+                &[KeyringCompat::Twine, KeyringCompat::Poetry],
+                Some(&KeyringIndex::named("internal"))
+            ),
+            ["poetry-repository-internal"]
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use std::fmt;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// The outcome of a query to a keyring backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LookupOutcome {
+    /// Credentials were found.
+    Hit,
+    /// No credentials were found.
+    Miss,
+    /// The backend could not be queried.
+    Error,
+}
+
+impl LookupOutcome {
+    /// The outcome of a query that returned the given result.
+    pub(crate) fn of<T, E>(result: &Result<Option<T>, E>) -> Self {
+        match result {
+            Ok(Some(_)) => Self::Hit,
+            Ok(None) => Self::Miss,
+            Err(_) => Self::Error,
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Hit => "hit",
+            Self::Miss => "miss",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// Counters of the queries to a keyring backend, excluding cached lookups.
+#[derive(Debug, Default)]
+pub struct KeyringMetrics {
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+    errors: AtomicUsize,
+    /// The total duration of the queries, in microseconds.
+    elapsed: AtomicU64,
+}
+
+impl KeyringMetrics {
+    /// Record a query that took the given duration.
+    pub(crate) fn record(&self, outcome: LookupOutcome, elapsed: Duration) {
+        let counter = match outcome {
+            LookupOutcome::Hit => &self.hits,
+            LookupOutcome::Miss => &self.misses,
+            LookupOutcome::Error => &self.errors,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        self.elapsed.fetch_add(
+            u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX),
+            Ordering::Relaxed,
+        );
+    }
+
+    /// The number of queries to the backend.
+    pub fn calls(&self) -> usize {
+        self.hits() + self.misses() + self.errors()
+    }
+
+    /// The number of queries that found credentials.
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// The number of queries that found no credentials.
+    pub fn misses(&self) -> usize {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// The number of queries that failed.
+    pub fn errors(&self) -> usize {
+        self.errors.load(Ordering::Relaxed)
+    }
+
+    /// The total duration of the queries.
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_micros(self.elapsed.load(Ordering::Relaxed))
+    }
+}
+
+impl fmt::Display for KeyringMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} keyring {} ({} hit, {} miss, {} error) in {}ms",
+            self.calls(),
+            if self.calls() == 1 { "call" } else { "calls" },
+            self.hits(),
+            self.misses(),
+            self.errors(),
+            self.elapsed().as_millis()
+        )
+    }
+}
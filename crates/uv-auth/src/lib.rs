@@ -3,20 +3,70 @@ use std::sync::{Arc, LazyLock};
 use tracing::trace;
 use url::Url;
 
+pub use audit::{audit, flush_audit_log, set_audit_log_path, AuditOperation, AuditOutcome};
 use cache::CredentialsCache;
-pub use credentials::Credentials;
-pub use keyring::KeyringProvider;
-pub use keyring_config::AuthConfig;
-pub use keyring_config::ConfigFile;
+pub use credential_helper::{
+    CredentialHelper, CredentialHelperError, CredentialHelperProvider, AZURE_USERNAME,
+    CODEARTIFACT_USERNAME, GCLOUD_USERNAME,
+};
+pub use credentials::{AuthScheme, CredentialOrigin, Credentials, BEARER_USERNAME};
+pub use env::{CredentialSource, EnvCredentialProvider};
+pub use handoff::{SecretDelivery, SecretGuard, SecretHandoff, SecretHandoffError};
+pub use host_policy::{set_keyring_host_policy, HostPattern, HostPatternError, HostPolicy};
+pub use keyring::{InvalidKeyringRequest, KeyringError, KeyringProvider, KeyringScope};
+pub use keyring_command::KeyringCommandError;
+pub use keyring_compat::{set_keyring_compat, KeyringCompat, KeyringIndex};
+#[cfg(any(test, feature = "test-utils"))]
+pub use keyring_config::{reset_config_path, set_test_config_path};
+pub use keyring_config::{
+    set_config_path, set_project_config_path, ConfigError as AuthConfigError, ConfigFile,
+    ConfigLayer,
+};
+pub use keyring_config::{
+    AuthConfig, AuthConfigExport, ClientIdentity, EntryKind as AuthConfigEntryKind, ImportOutcome,
+    ImportedEntry, Index as AuthConfigEntry, IndexKeyringProvider, ProxyEntry,
+    AUTH_CONFIG_EXPORT_VERSION, AUTH_CONFIG_VERSION,
+};
+pub use keyring_metrics::KeyringMetrics;
 pub use middleware::AuthMiddleware;
+pub use netrc::NetrcProvider;
+pub use provenance::{
+    flush_provenance_log, read_provenance_log, record_provenance, set_provenance_log_path,
+    write_provenance_log, CredentialProvenance, ProvenanceRecord,
+};
 use realm::Realm;
+pub use secret::{fingerprint, SecretIssues, SecretString, SuspiciousChar};
+pub use sources::CredentialSources;
+pub use store::{ChainedStore, CredentialStore, StoreError};
 
+mod audit;
 mod cache;
+mod credential_helper;
 mod credentials;
+mod env;
+mod git_credential;
+mod handoff;
+mod host_policy;
 mod keyring;
+mod keyring_command;
+mod keyring_compat;
 mod keyring_config;
+mod keyring_metrics;
 mod middleware;
+#[cfg(any(feature = "native-keyring", test))]
+mod native;
+mod netrc;
+#[cfg(any(feature = "onepassword", test))]
+mod onepassword;
+mod provenance;
 mod realm;
+mod secret;
+mod sources;
+mod store;
+#[cfg(test)]
+mod testing;
+#[cfg(any(feature = "vault", test))]
+mod vault;
 
 // TODO(zanieb): Consider passing a cache explicitly throughout
 
@@ -39,6 +89,16 @@ pub fn store_credentials_from_url(url: &Url) -> bool {
     }
 }
 
+/// Take the usernames discovered in the keyring during this invocation, as pairs of the URL that
+/// required authentication and the username.
+///
+/// Discovery is performed when a request to a configured index is rejected and no username is
+/// known for the URL, see [`AuthMiddleware::with_username_discovery`]. The discovered usernames
+/// may be persisted to the [`AuthConfig`], so that future invocations can skip discovery.
+pub fn take_discovered_usernames() -> Vec<(Url, String)> {
+    CREDENTIALS_CACHE.take_discovered_usernames()
+}
+
 /// Populate the global authentication store with credentials on a URL, if there are any.
 ///
 /// Returns `true` if the store was updated.
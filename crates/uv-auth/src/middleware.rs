@@ -1,45 +1,39 @@
+use std::collections::HashMap;
 use std::sync::{Arc, LazyLock};
 
 use http::{Extensions, StatusCode};
 use url::Url;
 
 use crate::{
-    credentials::{Credentials, Username},
+    credentials::{AuthScheme, CredentialOrigin, Credentials, Username},
+    keyring::default_discovery_candidates,
+    provenance::{provenance_enabled, CredentialProvenance},
     realm::Realm,
-    CredentialsCache, KeyringProvider, CREDENTIALS_CACHE,
+    AuthConfig, CredentialHelperError, CredentialHelperProvider, CredentialSource,
+    CredentialsCache, KeyringIndex, KeyringProvider, NetrcProvider, CREDENTIALS_CACHE,
 };
 use anyhow::{anyhow, format_err};
-use netrc::Netrc;
 use reqwest::{Request, Response};
 use reqwest_middleware::{Error, Middleware, Next};
-use tracing::{debug, trace, warn};
+use tracing::{debug, trace};
+use uv_warnings::warn_user_once;
 
 /// Strategy for loading netrc files.
 enum NetrcMode {
-    Automatic(LazyLock<Option<Netrc>>),
-    Enabled(Netrc),
+    Automatic(LazyLock<Option<NetrcProvider>>),
+    Enabled(NetrcProvider),
     Disabled,
 }
 
 impl Default for NetrcMode {
     fn default() -> Self {
-        NetrcMode::Automatic(LazyLock::new(|| match Netrc::new() {
-            Ok(netrc) => Some(netrc),
-            Err(netrc::Error::Io(err)) if err.kind() == std::io::ErrorKind::NotFound => {
-                debug!("No netrc file found");
-                None
-            }
-            Err(err) => {
-                warn!("Error reading netrc file: {err}");
-                None
-            }
-        }))
+        NetrcMode::Automatic(LazyLock::new(NetrcProvider::load))
     }
 }
 
 impl NetrcMode {
     /// Get the parsed netrc file if enabled.
-    fn get(&self) -> Option<&Netrc> {
+    fn get(&self) -> Option<&NetrcProvider> {
         match self {
             NetrcMode::Automatic(lock) => lock.as_ref(),
             NetrcMode::Enabled(netrc) => Some(netrc),
@@ -55,7 +49,23 @@ impl NetrcMode {
 pub struct AuthMiddleware {
     netrc: NetrcMode,
     keyring: Option<KeyringProvider>,
+    /// The keyring providers for individual indexes, keyed by host (and port) as in the
+    /// [`AuthConfig`].
+    index_keyrings: HashMap<String, Option<KeyringProvider>>,
+    /// The usernames recorded for indexes and URLs, used for keyring lookups when a request has
+    /// no username.
+    auth_config: Option<AuthConfig>,
+    /// The credential helpers recorded for indexes in the [`AuthConfig`], keyed by host (and
+    /// port).
+    index_helpers: HashMap<String, Arc<CredentialHelperProvider>>,
     cache: Option<CredentialsCache>,
+    /// The URLs of the indexes for which a username is discovered in the keyring when none is
+    /// known, see [`AuthMiddleware::with_username_discovery`].
+    discovery_indexes: Vec<Url>,
+    /// The usernames to try when discovering a username in the keyring.
+    discovery_candidates: Vec<String>,
+    /// The URLs and names of the indexes, for the provenance of the credentials sent to them.
+    index_names: Vec<(Url, String)>,
     /// We know that the endpoint needs authentication, so we don't try to send an unauthenticated
     /// request, avoiding cloning an uncloneable request.
     only_authenticated: bool,
@@ -66,16 +76,22 @@ impl AuthMiddleware {
         Self {
             netrc: NetrcMode::default(),
             keyring: None,
+            index_keyrings: HashMap::new(),
+            auth_config: None,
+            index_helpers: HashMap::new(),
             cache: None,
+            discovery_indexes: Vec::new(),
+            discovery_candidates: default_discovery_candidates(),
+            index_names: Vec::new(),
             only_authenticated: false,
         }
     }
 
-    /// Configure the [`Netrc`] credential file to use.
+    /// Configure the [`NetrcProvider`] to use.
     ///
     /// `None` disables authentication via netrc.
     #[must_use]
-    pub fn with_netrc(mut self, netrc: Option<Netrc>) -> Self {
+    pub fn with_netrc(mut self, netrc: Option<NetrcProvider>) -> Self {
         self.netrc = if let Some(netrc) = netrc {
             NetrcMode::Enabled(netrc)
         } else {
@@ -91,6 +107,85 @@ impl AuthMiddleware {
         self
     }
 
+    /// Configure the [`KeyringProvider`] to use for individual indexes, in place of the one
+    /// configured with [`AuthMiddleware::with_keyring`].
+    ///
+    /// The providers are keyed by the host (and port) of the index, as in the [`AuthConfig`].
+    /// `None` disables keyring lookups for the index.
+    #[must_use]
+    pub fn with_index_keyrings(
+        mut self,
+        index_keyrings: HashMap<String, Option<KeyringProvider>>,
+    ) -> Self {
+        self.index_keyrings = index_keyrings;
+        self
+    }
+
+    /// Configure the [`AuthConfig`] to read usernames from.
+    ///
+    /// When a request has no username, the username recorded for the URL, or for the index on its
+    /// host, is used to look up the password in the keyring. Indexes with a credential helper use
+    /// the credentials it issues instead.
+    #[must_use]
+    pub fn with_auth_config(mut self, auth_config: Option<AuthConfig>) -> Self {
+        self.index_helpers = auth_config
+            .iter()
+            .flat_map(|auth_config| &auth_config.indexes)
+            .filter_map(|(host, entry)| {
+                let helper = entry.helper.clone()?;
+                let index = entry.name.clone().unwrap_or_else(|| host.clone());
+                Some((
+                    host.clone(),
+                    Arc::new(CredentialHelperProvider::new(helper).with_index(index)),
+                ))
+            })
+            .collect();
+        self.auth_config = auth_config;
+        self
+    }
+
+    /// Configure the credential helper for an index, in place of the one recorded in the
+    /// [`AuthConfig`].
+    #[cfg(test)]
+    #[must_use]
+    fn with_index_helper(mut self, host: &str, helper: CredentialHelperProvider) -> Self {
+        self.index_helpers
+            .insert(host.to_string(), Arc::new(helper.with_index(host)));
+        self
+    }
+
+    /// Discover a username in the keyring for requests to the given indexes, when no username is
+    /// known for the URL.
+    ///
+    /// A request to one of the indexes that's rejected is retried with the first of a few common
+    /// usernames, e.g., `__token__`, that the keyring has a password for, or else with the username
+    /// and password that the keyring stores for the URL, see
+    /// [`KeyringProvider::discover_credentials`]. A request to any other URL never triggers
+    /// discovery, so that the keyring isn't probed for every host that responds with a 401, 403,
+    /// or 404. The discovered usernames are recorded in the [`CredentialsCache`].
+    #[must_use]
+    pub fn with_username_discovery(mut self, indexes: Vec<Url>) -> Self {
+        self.discovery_indexes = indexes;
+        self
+    }
+
+    /// Configure the names of the indexes, to record which index the credentials of a response
+    /// were sent to, in addition to the names recorded in the [`AuthConfig`].
+    #[must_use]
+    pub fn with_index_names(mut self, index_names: Vec<(Url, String)>) -> Self {
+        self.index_names = index_names;
+        self
+    }
+
+    /// Configure the usernames to try when discovering a username in the keyring, in place of
+    /// `__token__` and the name of the current user.
+    #[cfg(test)]
+    #[must_use]
+    fn with_discovery_candidates(mut self, candidates: &[&str]) -> Self {
+        self.discovery_candidates = candidates.iter().map(ToString::to_string).collect();
+        self
+    }
+
     /// Configure the [`CredentialsCache`] to use.
     #[must_use]
     pub fn with_cache(mut self, cache: CredentialsCache) -> Self {
@@ -106,6 +201,87 @@ impl AuthMiddleware {
         self
     }
 
+    /// Return the [`KeyringProvider`] to use for the given URL, if any.
+    fn keyring_for(&self, url: &Url) -> Option<&KeyringProvider> {
+        match self.index_keyrings.get(&AuthConfig::url_to_string(url)) {
+            Some(keyring) => keyring.as_ref(),
+            None => self.keyring.as_ref(),
+        }
+    }
+
+    /// Return the usernames recorded for the given URL in the [`AuthConfig`], in the order they
+    /// should be tried.
+    ///
+    /// An entry for the URL itself takes precedence over the entry for the index on its host.
+    fn configured_usernames(&self, url: &Url) -> Vec<&str> {
+        let Some(auth_config) = self.auth_config.as_ref() else {
+            return Vec::new();
+        };
+        auth_config
+            .find_url_entry(url)
+            .or_else(|| auth_config.find_entry(url))
+            .map(|entry| entry.usernames.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    /// Return the authentication scheme recorded for the given URL in the [`AuthConfig`], see
+    /// [`AuthMiddleware::configured_usernames`].
+    fn configured_scheme(&self, url: &Url) -> AuthScheme {
+        let Some(auth_config) = self.auth_config.as_ref() else {
+            return AuthScheme::Basic;
+        };
+        auth_config
+            .find_url_entry(url)
+            .or_else(|| auth_config.find_entry(url))
+            .map(|entry| entry.scheme)
+            .unwrap_or_default()
+    }
+
+    /// Return the index that the given URL belongs to, for keyring lookups of the entries that
+    /// other tools store for it, using the name recorded for the index in the [`AuthConfig`].
+    fn keyring_index(&self, url: &Url) -> Option<KeyringIndex<'_>> {
+        let name = self
+            .auth_config
+            .as_ref()?
+            .find_entry(url)?
+            .name
+            .as_deref()?;
+        Some(KeyringIndex::named(name))
+    }
+
+    /// Whether to discover a username in the keyring for the given URL, i.e., whether it's on the
+    /// realm of an index configured with [`AuthMiddleware::with_username_discovery`].
+    fn discovers_username(&self, url: &Url) -> bool {
+        let realm = Realm::from(url);
+        self.discovery_indexes
+            .iter()
+            .any(|index| Realm::from(index) == realm)
+    }
+
+    /// The provenance of the credentials attached to a request for the given URL, if provenance
+    /// is recorded.
+    ///
+    /// The index is the one configured with [`AuthMiddleware::with_index_names`] on the realm of
+    /// the URL, preferring one that the URL is under, or else the one recorded in the
+    /// [`AuthConfig`].
+    fn provenance(&self, url: &Url, credentials: &Credentials) -> Option<CredentialProvenance> {
+        if !provenance_enabled() {
+            return None;
+        }
+        let realm = Realm::from(url);
+        let mut indexes = self
+            .index_names
+            .iter()
+            .filter(|(index, _)| Realm::from(index) == realm);
+        let index = indexes
+            .clone()
+            .find(|(index, _)| url.as_str().starts_with(index.as_str()))
+            .or_else(|| indexes.next())
+            .map(|(_, name)| name.as_str())
+            .or_else(|| self.auth_config.as_ref()?.find_entry(url)?.name.as_deref());
+        CredentialProvenance::new(url, index, credentials)
+    }
+
     /// Get the configured authentication store.
     ///
     /// If not set, the global store is used.
@@ -138,8 +314,8 @@ impl Middleware for AuthMiddleware {
     /// The discovered credentials must have the requested username to be used.
     ///
     /// - Check the cache (realm key) for a password
-    /// - Check the netrc for a password
     /// - Check the keyring for a password
+    /// - Check the netrc for a password
     /// - Perform the request
     /// - Add the username and password to the cache if successful
     ///
@@ -155,14 +331,52 @@ impl Middleware for AuthMiddleware {
     /// - Perform the request
     /// - On 401, 403, or 404 check for authentication if there was a cache miss
     ///     - Check the cache (realm key) for the username and password
+    ///     - Check the keyring for a password, with the username from the [`AuthConfig`]
+    ///     - Without a configured username, for a configured index, check the keyring for a
+    ///       guessed username, and then for an entry without a username
     ///     - Check the netrc for a username and password
     ///     - Perform the request again if found
     ///     - Add the username and password to the cache if successful
     async fn handle(
         &self,
-        mut request: Request,
+        request: Request,
         extensions: &mut Extensions,
         next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        self.authenticate(request, &mut NextTransport { next, extensions })
+            .await
+    }
+}
+
+/// The HTTP layer used by the [`AuthMiddleware`] to send requests.
+///
+/// In production, requests are passed to the next middleware in the chain. Abstracting over
+/// the transport allows the authentication logic to be tested without a server.
+#[async_trait::async_trait]
+pub(crate) trait Transport: Send {
+    /// Send a request and return the response.
+    async fn send(&mut self, request: Request) -> reqwest_middleware::Result<Response>;
+}
+
+/// A [`Transport`] that passes requests to the next middleware in the chain.
+struct NextTransport<'a, 'b> {
+    next: Next<'a>,
+    extensions: &'b mut Extensions,
+}
+
+#[async_trait::async_trait]
+impl Transport for NextTransport<'_, '_> {
+    async fn send(&mut self, request: Request) -> reqwest_middleware::Result<Response> {
+        self.next.clone().run(request, self.extensions).await
+    }
+}
+
+impl AuthMiddleware {
+    /// Perform a request with authentication, as described in [`AuthMiddleware::handle`].
+    pub(crate) async fn authenticate(
+        &self,
+        mut request: Request,
+        transport: &mut dyn Transport,
     ) -> reqwest_middleware::Result<Response> {
         // Check for credentials attached to the request already
         let credentials = Credentials::from_request(&request);
@@ -172,46 +386,79 @@ impl Middleware for AuthMiddleware {
         let url = tracing_url(&request, credentials.as_ref());
         trace!("Handling request for {url}");
 
+        // Indexes with a credential helper always use the credentials it issues, even over the
+        // credentials attached to the request.
+        if let Some(helper) = self
+            .index_helpers
+            .get(&AuthConfig::url_to_string(request.url()))
+        {
+            return self
+                .authenticate_with_helper(helper, request, transport)
+                .await;
+        }
+
         if let Some(credentials) = credentials {
             let credentials = Arc::new(credentials);
 
+            // The credentials in the environment, which are seeded into the cache, take precedence
+            // over the ones embedded in the URL, see [`CredentialSource::PRECEDENCE`].
+            if let Some(credentials) = self
+                .cache()
+                .get_url(request.url(), &Username::none())
+                .filter(|credentials| {
+                    credentials.origin() == Some(&CredentialOrigin::Environment)
+                        && credentials.password().is_some()
+                })
+            {
+                trace!("Using credentials from the environment for {url}");
+                debug_credentials(&url, &credentials);
+                request = credentials.authenticate(request);
+                let provenance = self.provenance(request.url(), &credentials);
+                return self
+                    .complete_request(None, provenance, request, transport)
+                    .await;
+            }
+
             // If there's a password, send the request and cache
             if credentials.password().is_some() {
                 trace!("Request for {url} is already fully authenticated");
+                let provenance = self.provenance(request.url(), &credentials);
                 return self
-                    .complete_request(Some(credentials), request, extensions, next)
+                    .complete_request(Some(credentials), provenance, request, transport)
                     .await;
             }
 
             trace!("Request for {url} is missing a password, looking for credentials");
             // There's just a username, try to find a password
-            let credentials = if let Some(credentials) = self
+            let (credentials, provenance) = if let Some(credentials) = self
                 .cache()
                 .get_realm(Realm::from(request.url()), credentials.to_username())
             {
                 request = credentials.authenticate(request);
                 // Do not insert already-cached credentials
-                None
+                (None, self.provenance(request.url(), &credentials))
             } else if let Some(credentials) = self
                 .cache()
                 .get_url(request.url(), &credentials.to_username())
             {
                 request = credentials.authenticate(request);
                 // Do not insert already-cached credentials
-                None
+                (None, self.provenance(request.url(), &credentials))
             } else if let Some(credentials) = self
                 .fetch_credentials(Some(&credentials), request.url())
                 .await
             {
+                debug_credentials(&url, &credentials);
                 request = credentials.authenticate(request);
-                Some(credentials)
+                let provenance = self.provenance(request.url(), &credentials);
+                (Some(credentials), provenance)
             } else {
                 // If we don't find a password, we'll still attempt the request with the existing credentials
-                Some(credentials)
+                (Some(credentials), None)
             };
 
             return self
-                .complete_request(credentials, request, extensions, next)
+                .complete_request(credentials, provenance, request, transport)
                 .await;
         }
 
@@ -226,7 +473,11 @@ impl Middleware for AuthMiddleware {
             // If it's fully authenticated, finish the request
             if credentials.password().is_some() {
                 trace!("Request for {url} is fully authenticated");
-                return self.complete_request(None, request, extensions, next).await;
+                debug_credentials(&url, credentials);
+                let provenance = self.provenance(request.url(), credentials);
+                return self
+                    .complete_request(None, provenance, request, transport)
+                    .await;
             }
 
             // If we just found a username, we'll make the request then look for password elsewhere
@@ -259,7 +510,7 @@ impl Middleware for AuthMiddleware {
                 ))
             })?;
 
-            let response = next.clone().run(request, extensions).await?;
+            let response = transport.send(request).await?;
 
             // If we don't fail with authorization related codes, return the response
             if !matches!(
@@ -292,9 +543,11 @@ impl Middleware for AuthMiddleware {
         if let Some(credentials) = credentials.as_ref() {
             if credentials.password().is_some() {
                 trace!("Retrying request for {url} with credentials from cache {credentials:?}");
+                debug_credentials(&url, credentials);
                 retry_request = credentials.authenticate(retry_request);
+                let provenance = self.provenance(retry_request.url(), credentials);
                 return self
-                    .complete_request(None, retry_request, extensions, next)
+                    .complete_request(None, provenance, retry_request, transport)
                     .await;
             }
         }
@@ -307,8 +560,10 @@ impl Middleware for AuthMiddleware {
         {
             retry_request = credentials.authenticate(retry_request);
             trace!("Retrying request for {url} with {credentials:?}");
+            debug_credentials(&url, &credentials);
+            let provenance = self.provenance(retry_request.url(), &credentials);
             return self
-                .complete_request(Some(credentials), retry_request, extensions, next)
+                .complete_request(Some(credentials), provenance, retry_request, transport)
                 .await;
         }
 
@@ -317,7 +572,7 @@ impl Middleware for AuthMiddleware {
                 trace!("Retrying request for {url} with username from cache {credentials:?}");
                 retry_request = credentials.authenticate(retry_request);
                 return self
-                    .complete_request(None, retry_request, extensions, next)
+                    .complete_request(None, None, retry_request, transport)
                     .await;
             }
         }
@@ -330,26 +585,97 @@ impl Middleware for AuthMiddleware {
             )))
         }
     }
-}
 
-impl AuthMiddleware {
+    /// Perform a request with the credentials issued by a credential helper.
+    ///
+    /// The credentials are attached preemptively, and not added to the cache, since they expire.
+    /// If the index rejects them, e.g., because the token expired earlier than expected, the
+    /// request is retried once with a new token. If the helper fails, the request is sent without
+    /// credentials.
+    async fn authenticate_with_helper(
+        &self,
+        helper: &CredentialHelperProvider,
+        request: Request,
+        transport: &mut dyn Transport,
+    ) -> reqwest_middleware::Result<Response> {
+        let credentials = match helper.fetch().await {
+            Ok(credentials) => credentials,
+            Err(err) => {
+                warn_helper_failure(helper, &err);
+                return transport.send(request).await;
+            }
+        };
+        trace!(
+            "Attaching credentials from the credential helper for {}",
+            request.url()
+        );
+
+        let provenance = self.helper_provenance(request.url(), &credentials);
+        let retry_request = request.try_clone();
+        let response = transport.send(credentials.authenticate(request)).await?;
+        if !matches!(
+            response.status(),
+            StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED
+        ) {
+            return Ok(with_provenance(response, provenance));
+        }
+        let Some(retry_request) = retry_request else {
+            return Ok(response);
+        };
+        match helper.refresh(&credentials).await {
+            Ok(Some(credentials)) => {
+                trace!(
+                    "Retrying request for {} with a new token from the credential helper",
+                    retry_request.url()
+                );
+                let provenance = self.helper_provenance(retry_request.url(), &credentials);
+                let response = transport
+                    .send(credentials.authenticate(retry_request))
+                    .await?;
+                Ok(with_provenance(response, provenance))
+            }
+            Ok(None) => Ok(response),
+            Err(err) => {
+                warn_helper_failure(helper, &err);
+                Ok(response)
+            }
+        }
+    }
+
+    /// The provenance of the credentials issued by a credential helper for a request.
+    fn helper_provenance(
+        &self,
+        url: &Url,
+        credentials: &Credentials,
+    ) -> Option<CredentialProvenance> {
+        self.provenance(url, credentials)
+            .map(|provenance| provenance.with_source("credential-helper"))
+    }
+
     /// Run a request to completion.
     ///
-    /// If credentials are present, insert them into the cache on success.
+    /// If credentials are present, insert them into the cache on success. The provenance of the
+    /// credentials attached to the request, if any, is attached to the response.
     async fn complete_request(
         &self,
         credentials: Option<Arc<Credentials>>,
+        provenance: Option<CredentialProvenance>,
         request: Request,
-        extensions: &mut Extensions,
-        next: Next<'_>,
+        transport: &mut dyn Transport,
     ) -> reqwest_middleware::Result<Response> {
         let Some(credentials) = credentials else {
             // Nothing to insert into the cache if we don't have credentials
-            return next.run(request, extensions).await;
+            return transport
+                .send(request)
+                .await
+                .map(|response| with_provenance(response, provenance));
         };
 
         let url = request.url().clone();
-        let result = next.run(request, extensions).await;
+        let result = transport
+            .send(request)
+            .await
+            .map(|response| with_provenance(response, provenance));
 
         // Update the cache with new credentials on a successful request
         if result
@@ -365,19 +691,32 @@ impl AuthMiddleware {
 
     /// Fetch credentials for a URL.
     ///
-    /// Supports netrc file and keyring lookups.
+    /// Supports keyring and netrc file lookups, in the order of [`CredentialSource::PRECEDENCE`].
     async fn fetch_credentials(
         &self,
         credentials: Option<&Credentials>,
         url: &Url,
     ) -> Option<Arc<Credentials>> {
+        // Without a username on the request, use the ones recorded in the auth config for keyring
+        // lookups.
+        let configured_usernames = if credentials
+            .and_then(|credentials| credentials.username())
+            .is_none()
+        {
+            self.configured_usernames(url)
+        } else {
+            Vec::new()
+        };
+
         // Fetches can be expensive, so we will only run them _once_ per realm and username combination
         // All other requests for the same realm will wait until the first one completes
         let key = (
             Realm::from(url),
             Username::from(
                 credentials
-                    .map(|credentials| credentials.username().unwrap_or_default().to_string()),
+                    .and_then(|credentials| credentials.username())
+                    .or(configured_usernames.first().copied())
+                    .map(ToString::to_string),
             ),
         );
 
@@ -398,46 +737,122 @@ impl AuthMiddleware {
             return credentials;
         }
 
-        // Netrc support based on: <https://github.com/gribouille/netrc>.
-        let credentials = if let Some(credentials) = self.netrc.get().and_then(|netrc| {
-            debug!("Checking netrc for credentials for {url}");
-            Credentials::from_netrc(
-                netrc,
-                url,
-                credentials
-                    .as_ref()
-                    .and_then(|credentials| credentials.username()),
-            )
-        }) {
-            debug!("Found credentials in netrc file for {url}");
-            Some(credentials)
+        // The environment and the URL provide the credentials attached to the request, or seeded
+        // into the cache, which take precedence over the stored ones queried here.
+        let mut found = None;
+        for source in CredentialSource::PRECEDENCE {
+            found = match source {
+                CredentialSource::Environment | CredentialSource::Url => continue,
+                CredentialSource::Keyring => self
+                    .fetch_from_keyring(credentials, &configured_usernames, url)
+                    .await
+                    .map(|credentials| {
+                        debug!("Found credentials in keyring for {url}");
+                        credentials.with_scheme(self.configured_scheme(url))
+                    }),
+                // Netrc support based on: <https://github.com/gribouille/netrc>.
+                CredentialSource::Netrc => self
+                    .netrc
+                    .get()
+                    .and_then(|netrc| {
+                        debug!("Checking netrc for credentials for {url}");
+                        netrc.fetch(
+                            url,
+                            credentials
+                                .as_ref()
+                                .and_then(|credentials| credentials.username()),
+                        )
+                    })
+                    .inspect(|_| debug!("Found credentials in netrc file for {url}")),
+            };
+            if found.is_some() {
+                break;
+            }
+        }
+        let credentials = found.map(Arc::new);
+
+        // Register the fetch for this key
+        self.cache().fetches.done(key.clone(), credentials.clone());
+
+        credentials
+    }
+
+    /// Fetch credentials for a URL from the keyring, if it's enabled for the URL.
+    ///
+    /// Without a username on the request, the usernames in the auth config are tried, or, for a
+    /// configured index, guessed ones.
+    async fn fetch_from_keyring(
+        &self,
+        credentials: Option<&Credentials>,
+        configured_usernames: &[&str],
+        url: &Url,
+    ) -> Option<Credentials> {
         // N.B. The keyring provider performs lookups for the exact URL then
         //      falls back to the host, but we cache the result per realm so if a keyring
         //      implementation returns different credentials for different URLs in the
         //      same realm we will use the wrong credentials.
-        } else if let Some(credentials) = match self.keyring {
-            Some(ref keyring) => {
-                if let Some(username) = credentials.and_then(|credentials| credentials.username()) {
-                    debug!("Checking keyring for credentials for {username}@{url}");
-                    keyring.fetch(url, username).await
-                } else {
-                    debug!("Skipping keyring lookup for {url} with no username");
-                    None
-                }
-            }
-            None => None,
-        } {
-            debug!("Found credentials in keyring for {url}");
-            Some(credentials)
-        } else {
+        let keyring = self.keyring_for(url)?;
+        let index = self.keyring_index(url);
+        let index = index.as_ref();
+        if let Some(username) = credentials.and_then(|credentials| credentials.username()) {
+            debug!("Checking keyring for credentials for {username}@{url}");
+            keyring.fetch_for_index(url, username, index).await
+        } else if !configured_usernames.is_empty() {
+            debug!(
+                "Checking keyring for credentials for {url}, with the usernames from the auth config"
+            );
+            keyring.discover(url, configured_usernames, index).await
+        } else if !self.discovers_username(url) {
+            trace!(
+                "Not discovering a username in the keyring for {url}, which is not a configured index"
+            );
             None
+        } else {
+            debug!("Attempting to discover a username in the keyring for {url}");
+            let candidates = self
+                .discovery_candidates
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>();
+            let discovered = keyring.discover_credentials(url, &candidates, index).await;
+            if let Some(username) = discovered
+                .as_ref()
+                .and_then(|credentials| credentials.username())
+            {
+                debug!("Discovered username {username} in the keyring for {url}");
+                self.cache().record_discovered_username(url, username);
+            }
+            discovered
         }
-        .map(Arc::new);
+    }
+}
 
-        // Register the fetch for this key
-        self.cache().fetches.done(key.clone(), credentials.clone());
+/// Warn that the credential helper of an index could not issue credentials, naming the index.
+fn warn_helper_failure(helper: &CredentialHelperProvider, err: &CredentialHelperError) {
+    if let Some(index) = helper.index() {
+        warn_user_once!("Failed to authenticate to index `{index}`: {err}");
+    } else {
+        warn_user_once!("{err}");
+    }
+}
 
-        credentials
+/// Attach the provenance of the credentials that a request was sent with to its response, for the
+/// cache to record.
+fn with_provenance(mut response: Response, provenance: Option<CredentialProvenance>) -> Response {
+    if let Some(provenance) = provenance {
+        response.extensions_mut().insert(provenance);
+    }
+    response
+}
+
+/// Log which credentials are attached to a request for `url` and where they were found, e.g., the
+/// keyring service name, to tell why an index rejects them. The password is never logged.
+fn debug_credentials(url: &str, credentials: &Credentials) {
+    let username = credentials.username().unwrap_or("<none>");
+    if let Some(origin) = credentials.origin() {
+        debug!("Using credentials for user `{username}` from {origin} for {url}");
+    } else {
+        debug!("Using credentials for user `{username}` for {url}");
     }
 }
 
@@ -466,16 +881,26 @@ fn tracing_url(request: &Request, credentials: Option<&Credentials>) -> String {
 #[cfg(test)]
 mod tests {
     use std::io::Write;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
 
     use reqwest::Client;
     use tempfile::NamedTempFile;
     use test_log::test;
 
     use url::Url;
-    use wiremock::matchers::{basic_auth, method, path_regex};
+    use wiremock::matchers::{basic_auth, header, method, path_regex};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
     use super::*;
+    use crate::keyring::TOKEN_USERNAME;
+    use crate::keyring_command::{KeyringOutput, ScriptedCommand};
+    use crate::native::{MemoryStore, NativeStore};
+    use crate::testing::{fake_get, FakeTransport};
+    use crate::{
+        CredentialHelper, CredentialSources, HostPolicy, KeyringCompat, SecretString,
+        BEARER_USERNAME,
+    };
 
     type Error = Box<dyn std::error::Error>;
 
@@ -703,7 +1128,7 @@ mod tests {
             .with(
                 AuthMiddleware::new()
                     .with_cache(CredentialsCache::new())
-                    .with_netrc(Netrc::from_file(netrc_file.path()).ok()),
+                    .with_netrc(NetrcProvider::from_path(netrc_file.path()).ok()),
             )
             .build();
 
@@ -750,7 +1175,8 @@ mod tests {
                 AuthMiddleware::new()
                     .with_cache(CredentialsCache::new())
                     .with_netrc(Some(
-                        Netrc::from_file(netrc_file.path()).expect("Test has valid netrc file"),
+                        NetrcProvider::from_path(netrc_file.path())
+                            .expect("Test has valid netrc file"),
                     )),
             )
             .build();
@@ -796,7 +1222,8 @@ mod tests {
                 AuthMiddleware::new()
                     .with_cache(CredentialsCache::new())
                     .with_netrc(Some(
-                        Netrc::from_file(netrc_file.path()).expect("Test has valid netrc file"),
+                        NetrcProvider::from_path(netrc_file.path())
+                            .expect("Test has valid netrc file"),
                     )),
             )
             .build();
@@ -838,7 +1265,8 @@ mod tests {
                 AuthMiddleware::new()
                     .with_cache(CredentialsCache::new())
                     .with_netrc(Some(
-                        Netrc::from_file(netrc_file.path()).expect("Test has valid netrc file"),
+                        NetrcProvider::from_path(netrc_file.path())
+                            .expect("Test has valid netrc file"),
                     )),
             )
             .build();
@@ -876,7 +1304,7 @@ mod tests {
                     .with_keyring(Some(KeyringProvider::dummy([(
                         (
                             format!(
-                                "{}:{}",
+                                "http://{}:{}",
                                 base_url.host_str().unwrap(),
                                 base_url.port().unwrap()
                             ),
@@ -929,6 +1357,215 @@ mod tests {
         Ok(())
     }
 
+    #[test(tokio::test)]
+    async fn test_index_keyrings() -> Result<(), Error> {
+        let username = "user";
+        let password = "password";
+        let server_override = start_test_server(username, password).await;
+        let server_disabled = start_test_server(username, password).await;
+        let server_default = start_test_server(username, password).await;
+        let host = |server: &MockServer| {
+            let url = Url::parse(&server.uri()).unwrap();
+            format!("{}:{}", url.host_str().unwrap(), url.port().unwrap())
+        };
+        let service = |server: &MockServer| format!("http://{}", host(server));
+
+        let client = test_client_builder()
+            .with(
+                AuthMiddleware::new()
+                    .with_cache(CredentialsCache::new())
+                    .with_keyring(Some(KeyringProvider::dummy([
+                        ((service(&server_disabled), username), password),
+                        ((service(&server_default), username), password),
+                    ])))
+                    .with_index_keyrings(HashMap::from([
+                        (
+                            host(&server_override),
+                            Some(KeyringProvider::dummy([(
+                                (service(&server_override), username),
+                                password,
+                            )])),
+                        ),
+                        (host(&server_disabled), None),
+                    ])),
+            )
+            .build();
+
+        for (server, status, message) in [
+            (
+                &server_override,
+                200,
+                "Credentials should be pulled from the keyring of the index",
+            ),
+            (
+                &server_disabled,
+                401,
+                "Credentials are not pulled from the keyring when disabled for the index",
+            ),
+            (
+                &server_default,
+                200,
+                "Credentials should be pulled from the default keyring",
+            ),
+        ] {
+            let mut url = Url::parse(&server.uri())?;
+            url.set_username(username).unwrap();
+            assert_eq!(client.get(url).send().await?.status(), status, "{message}");
+        }
+
+        Ok(())
+    }
+
+    /// The username recorded in the auth config is used to look up the password in the keyring
+    /// for requests without a username.
+    #[test(tokio::test)]
+    async fn test_keyring_username_from_auth_config() -> Result<(), Error> {
+        let username = "user";
+        let password = "password";
+        let index_server = start_test_server(username, password).await;
+        let files_server = start_test_server(username, password).await;
+        let service = |server: &MockServer| {
+            let url = Url::parse(&server.uri()).unwrap();
+            format!("http://{}:{}", url.host_str().unwrap(), url.port().unwrap())
+        };
+        let file_url = Url::parse(&files_server.uri())?.join("files/pkg-1.0-py3-none-any.whl")?;
+
+        let mut config = NamedTempFile::new()?;
+        writeln!(
+            config,
+            "[indexes.\"{}\"]\nusername = \"{username}\"\n\n[urls.\"{file_url}\"]\nusername = \"{username}\"",
+            service(&index_server).trim_start_matches("http://"),
+        )?;
+        let auth_config = AuthConfig::load_from_path(&config.path().to_path_buf())?;
+
+        let client = test_client_builder()
+            .with(
+                AuthMiddleware::new()
+                    .with_cache(CredentialsCache::new())
+                    .with_keyring(Some(KeyringProvider::dummy([
+                        ((service(&index_server), username), password),
+                        ((service(&files_server), username), password),
+                    ])))
+                    .with_auth_config(Some(auth_config)),
+            )
+            .build();
+
+        assert_eq!(
+            client.get(index_server.uri()).send().await?.status(),
+            200,
+            "The username of the index should be read from the auth config"
+        );
+        assert_eq!(
+            client
+                .get(format!("{}/files/other", files_server.uri()))
+                .send()
+                .await?
+                .status(),
+            401,
+            "Credentials are not pulled from the keyring for URLs without a recorded username"
+        );
+        assert_eq!(
+            client.get(file_url).send().await?.status(),
+            200,
+            "The username of the URL should be read from the auth config"
+        );
+
+        Ok(())
+    }
+
+    /// The usernames recorded in the auth config are tried in order, until the keyring has a
+    /// password for one of them.
+    #[test(tokio::test)]
+    async fn test_keyring_usernames_from_auth_config() -> Result<(), Error> {
+        let server = start_test_server("ci-bot", "password").await;
+        let base_url = Url::parse(&server.uri())?;
+        let host = format!(
+            "{}:{}",
+            base_url.host_str().unwrap(),
+            base_url.port().unwrap()
+        );
+
+        let mut config = NamedTempFile::new()?;
+        writeln!(
+            config,
+            "[indexes.\"{host}\"]\nusernames = [\"personal\", \"ci-bot\"]"
+        )?;
+        let auth_config = AuthConfig::load_from_path(&config.path().to_path_buf())?;
+
+        let client = test_client_builder()
+            .with(
+                AuthMiddleware::new()
+                    .with_cache(CredentialsCache::new())
+                    .with_keyring(Some(KeyringProvider::dummy([(
+                        (format!("http://{host}"), "ci-bot"),
+                        "password",
+                    )])))
+                    .with_auth_config(Some(auth_config)),
+            )
+            .build();
+
+        assert_eq!(
+            client.get(server.uri()).send().await?.status(),
+            200,
+            "The second username should be used, since the keyring has no password for the first"
+        );
+
+        Ok(())
+    }
+
+    /// The credentials from the keyring are sent with the scheme recorded in the auth config.
+    #[test(tokio::test)]
+    async fn test_keyring_scheme_from_auth_config() -> Result<(), Error> {
+        for (scheme, username, expected) in [
+            ("basic", "user", "Basic dXNlcjp0b2tlbg=="),
+            ("bearer", BEARER_USERNAME, "Bearer token"),
+        ] {
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .and(header("authorization", expected))
+                .respond_with(ResponseTemplate::new(200))
+                .mount(&server)
+                .await;
+            Mock::given(method("GET"))
+                .respond_with(ResponseTemplate::new(401))
+                .mount(&server)
+                .await;
+            let base_url = Url::parse(&server.uri())?;
+            let host = format!(
+                "{}:{}",
+                base_url.host_str().unwrap(),
+                base_url.port().unwrap()
+            );
+
+            let mut config = NamedTempFile::new()?;
+            writeln!(
+                config,
+                "[indexes.\"{host}\"]\nusernames = [\"{username}\"]\nscheme = \"{scheme}\""
+            )?;
+            let auth_config = AuthConfig::load_from_path(&config.path().to_path_buf())?;
+
+            let client = test_client_builder()
+                .with(
+                    AuthMiddleware::new()
+                        .with_cache(CredentialsCache::new())
+                        .with_keyring(Some(KeyringProvider::dummy([(
+                            (format!("http://{host}"), username),
+                            "token",
+                        )])))
+                        .with_auth_config(Some(auth_config)),
+                )
+                .build();
+
+            assert_eq!(
+                client.get(server.uri()).send().await?.status(),
+                200,
+                "The credentials should be sent with the {scheme} scheme"
+            );
+        }
+
+        Ok(())
+    }
+
     /// We include ports in keyring requests, e.g., `localhost:8000` should be distinct from `localhost`,
     /// unless the server is running on a default port, e.g., `localhost:80` is equivalent to `localhost`.
     /// We don't unit test the latter case because it's possible to collide with a server a developer is
@@ -946,7 +1583,7 @@ mod tests {
                     .with_cache(CredentialsCache::new())
                     .with_keyring(Some(KeyringProvider::dummy([(
                         // Omit the port from the keyring entry
-                        (base_url.host_str().unwrap(), username),
+                        (format!("http://{}", base_url.host_str().unwrap()), username),
                         password,
                     )]))),
             )
@@ -983,7 +1620,7 @@ mod tests {
                 KeyringProvider::dummy([(
                     (
                         format!(
-                            "{}:{}",
+                            "http://{}:{}",
                             base_url.host_str().unwrap(),
                             base_url.port().unwrap()
                         ),
@@ -1098,7 +1735,7 @@ mod tests {
                         (
                             (
                                 format!(
-                                    "{}:{}",
+                                    "http://{}:{}",
                                     base_url_1.host_str().unwrap(),
                                     base_url_1.port().unwrap()
                                 ),
@@ -1109,7 +1746,7 @@ mod tests {
                         (
                             (
                                 format!(
-                                    "{}:{}",
+                                    "http://{}:{}",
                                     base_url_2.host_str().unwrap(),
                                     base_url_2.port().unwrap()
                                 ),
@@ -1352,7 +1989,7 @@ mod tests {
                         (
                             (
                                 format!(
-                                    "{}:{}",
+                                    "http://{}:{}",
                                     base_url_1.host_str().unwrap(),
                                     base_url_1.port().unwrap()
                                 ),
@@ -1363,7 +2000,7 @@ mod tests {
                         (
                             (
                                 format!(
-                                    "{}:{}",
+                                    "http://{}:{}",
                                     base_url_2.host_str().unwrap(),
                                     base_url_2.port().unwrap()
                                 ),
@@ -1543,4 +2180,879 @@ mod tests {
 
         Ok(())
     }
+
+    /// A credential source that can be configured in tests, for the index at
+    /// `https://pypi.example.com/simple/`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum TestSource {
+        /// A credential helper configured for the index.
+        Helper,
+        /// The `UV_INDEX_<NAME>_*` environment variables, which uv seeds into the cache.
+        Environment,
+        /// A username and password embedded in the request URL.
+        Url,
+        /// A username in the auth config, with a password in the keyring for the URL of the index.
+        ConfiguredUsername,
+        /// A username in the auth config, with a password in the keyring for the host, which the
+        /// `*.example.com` pattern of the keyring host policy allows.
+        HostPattern,
+        /// The username for bearer tokens in the auth config, with the bearer scheme, and a token
+        /// in the keyring.
+        Bearer,
+        /// A password in the keyring for the `__token__` username, which is discovered.
+        DefaultUsername,
+        /// A password stored in the keyring without a username, which is discovered.
+        NoUsername,
+        /// A matching entry in a netrc file.
+        Netrc,
+    }
+
+    impl TestSource {
+        /// The credential sources, in order of precedence.
+        const PRECEDENCE: [TestSource; 9] = [
+            TestSource::Helper,
+            TestSource::Environment,
+            TestSource::Url,
+            TestSource::ConfiguredUsername,
+            TestSource::HostPattern,
+            TestSource::Bearer,
+            TestSource::DefaultUsername,
+            TestSource::NoUsername,
+            TestSource::Netrc,
+        ];
+
+        fn password(self) -> &'static str {
+            match self {
+                // The first token issued by [`aws_codeartifact`].
+                TestSource::Helper => "token-1",
+                TestSource::Environment => "env-password",
+                TestSource::Url => "url-password",
+                TestSource::ConfiguredUsername => "configured-password",
+                TestSource::HostPattern => "host-password",
+                TestSource::Bearer => "bearer-password",
+                TestSource::DefaultUsername => "default-password",
+                TestSource::NoUsername => "no-username-password",
+                TestSource::Netrc => "netrc-password",
+            }
+        }
+    }
+
+    fn netrc_with(entry: &str) -> Result<Option<NetrcProvider>, Error> {
+        let mut netrc_file = NamedTempFile::new()?;
+        writeln!(netrc_file, "{entry}")?;
+        Ok(NetrcProvider::from_path(netrc_file.path()).ok())
+    }
+
+    fn user_credentials(password: &str) -> Credentials {
+        Credentials::new(Some("user".to_string()), Some(password.to_string()))
+    }
+
+    #[test(tokio::test)]
+    async fn test_transport_public_no_credentials() -> Result<(), Error> {
+        let middleware = AuthMiddleware::new()
+            .with_cache(CredentialsCache::new())
+            .with_netrc(netrc_with(
+                "machine example.com login user password password",
+            )?);
+        let mut transport = FakeTransport::public();
+
+        let response = fake_get(&middleware, &mut transport, "https://example.com/simple/").await?;
+        assert_eq!(response.status(), 200);
+
+        // Credentials are not attached to requests that do not need them.
+        assert_eq!(transport.sent, vec![None]);
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_transport_challenge_then_preemptive() -> Result<(), Error> {
+        // Any of these statuses may indicate missing credentials.
+        for challenge in [
+            StatusCode::UNAUTHORIZED,
+            StatusCode::FORBIDDEN,
+            StatusCode::NOT_FOUND,
+        ] {
+            let middleware = AuthMiddleware::new()
+                .with_cache(CredentialsCache::new())
+                .with_netrc(netrc_with(
+                    "machine example.com login user password password",
+                )?);
+            let mut transport =
+                FakeTransport::protected("user", "password").with_challenge(challenge);
+            let response =
+                fake_get(&middleware, &mut transport, "https://example.com/simple/").await?;
+            assert_eq!(response.status(), 200, "Expected a retry on {challenge}");
+            assert_eq!(
+                transport.sent,
+                vec![None, Some(user_credentials("password"))]
+            );
+        }
+
+        let middleware = AuthMiddleware::new()
+            .with_cache(CredentialsCache::new())
+            .with_netrc(netrc_with(
+                "machine example.com login user password password",
+            )?);
+
+        // The first request is sent without credentials, and retried on the challenge.
+        let mut transport = FakeTransport::protected("user", "password");
+        let response = fake_get(&middleware, &mut transport, "https://example.com/simple/").await?;
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            transport.sent,
+            vec![None, Some(user_credentials("password"))]
+        );
+
+        // Later requests to the same URL attach the cached credentials preemptively.
+        let mut transport = FakeTransport::protected("user", "password");
+        let response = fake_get(&middleware, &mut transport, "https://example.com/simple/").await?;
+        assert_eq!(response.status(), 200);
+        assert_eq!(transport.sent, vec![Some(user_credentials("password"))]);
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_transport_only_authenticated() -> Result<(), Error> {
+        let middleware = AuthMiddleware::new()
+            .with_cache(CredentialsCache::new())
+            .with_netrc(netrc_with(
+                "machine example.com login user password password",
+            )?)
+            .with_only_authenticated(true);
+
+        // The unauthenticated attempt is skipped.
+        let mut transport = FakeTransport::protected("user", "password");
+        let response = fake_get(&middleware, &mut transport, "https://example.com/simple/").await?;
+        assert_eq!(response.status(), 200);
+        assert_eq!(transport.sent, vec![Some(user_credentials("password"))]);
+
+        // Without credentials, no request is sent at all.
+        let middleware = AuthMiddleware::new()
+            .with_cache(CredentialsCache::new())
+            .with_netrc(None)
+            .with_only_authenticated(true);
+        let mut transport = FakeTransport::protected("user", "password");
+        let result = fake_get(&middleware, &mut transport, "https://example.com/simple/").await;
+        assert!(result.is_err());
+        assert_eq!(transport.sent, vec![]);
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_transport_rejected_credentials_not_cached() -> Result<(), Error> {
+        let middleware = AuthMiddleware::new()
+            .with_cache(CredentialsCache::new())
+            .with_netrc(netrc_with("machine example.com login user password wrong")?);
+
+        let mut transport = FakeTransport::protected("user", "password");
+        let response = fake_get(&middleware, &mut transport, "https://example.com/simple/").await?;
+        assert_eq!(response.status(), 401);
+        assert_eq!(transport.sent, vec![None, Some(user_credentials("wrong"))]);
+
+        // The rejected credentials are not attached preemptively on the next request.
+        let mut transport = FakeTransport::protected("user", "password");
+        fake_get(&middleware, &mut transport, "https://example.com/simple/").await?;
+        assert_eq!(transport.sent.first(), Some(&None));
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_transport_failed_fetch_is_cached() -> Result<(), Error> {
+        let store = Arc::new(MemoryStore::default());
+        let middleware = AuthMiddleware::new()
+            .with_cache(CredentialsCache::new())
+            .with_netrc(None)
+            .with_keyring(Some(KeyringProvider::native_with_store(store.clone())));
+
+        let mut transport = FakeTransport::protected("user", "password");
+        let response = fake_get(
+            &middleware,
+            &mut transport,
+            "https://user@example.com/simple/",
+        )
+        .await?;
+        assert_eq!(response.status(), 401);
+
+        // Once a lookup for a realm and username has failed, it is not repeated, even for other
+        // URLs in the same realm.
+        store.set_password("example.com", "user", "password")?;
+        let mut transport = FakeTransport::protected("user", "password");
+        let response = fake_get(
+            &middleware,
+            &mut transport,
+            "https://user@example.com/other/",
+        )
+        .await?;
+        assert_eq!(response.status(), 401);
+
+        // A different username is looked up separately.
+        store.set_password("example.com", "other", "password")?;
+        let mut transport = FakeTransport::protected("other", "password");
+        let response = fake_get(
+            &middleware,
+            &mut transport,
+            "https://other@example.com/simple/",
+        )
+        .await?;
+        assert_eq!(response.status(), 200);
+
+        Ok(())
+    }
+
+    /// An `aws` command that issues a new token on each invocation.
+    fn aws_codeartifact() -> (Arc<ScriptedCommand>, CredentialHelperProvider) {
+        let issued = AtomicUsize::new(0);
+        let command = Arc::new(ScriptedCommand::new(move |_| {
+            let n = issued.fetch_add(1, Ordering::Relaxed) + 1;
+            Ok(KeyringOutput {
+                success: true,
+                stdout: format!("token-{n}\n").into_bytes(),
+                stderr: String::new(),
+            })
+        }));
+        let helper = CredentialHelper::AwsCodeartifact {
+            domain: "my-domain".to_string(),
+            domain_owner: "111122223333".to_string(),
+            region: None,
+        };
+        let provider = CredentialHelperProvider::with_command(helper, command.clone());
+        (command, provider)
+    }
+
+    fn aws_credentials(token: &str) -> Credentials {
+        Credentials::new(Some("aws".to_string()), Some(token.to_string()))
+    }
+
+    #[test(tokio::test)]
+    async fn test_transport_credential_helper() -> Result<(), Error> {
+        let (command, provider) = aws_codeartifact();
+        let middleware = AuthMiddleware::new()
+            .with_cache(CredentialsCache::new())
+            .with_index_helper("example.com", provider);
+
+        // The token is attached preemptively, and reused for later requests.
+        for _ in 0..2 {
+            let mut transport = FakeTransport::protected("aws", "token-1");
+            let response =
+                fake_get(&middleware, &mut transport, "https://example.com/simple/").await?;
+            assert_eq!(response.status(), 200);
+            assert_eq!(transport.sent, vec![Some(aws_credentials("token-1"))]);
+        }
+        assert_eq!(command.calls().len(), 1);
+
+        // Other hosts don't use the helper.
+        let mut transport = FakeTransport::public();
+        fake_get(
+            &middleware,
+            &mut transport,
+            "https://other.example.com/simple/",
+        )
+        .await?;
+        assert_eq!(transport.sent, vec![None]);
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_transport_credential_helper_refresh() -> Result<(), Error> {
+        // The index rejects the first token, as if it expired.
+        let (command, provider) = aws_codeartifact();
+        let middleware = AuthMiddleware::new()
+            .with_cache(CredentialsCache::new())
+            .with_index_helper("example.com", provider.with_min_refresh_age(Duration::ZERO));
+        let mut transport = FakeTransport::protected("aws", "token-2");
+        let response = fake_get(&middleware, &mut transport, "https://example.com/simple/").await?;
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            transport.sent,
+            vec![
+                Some(aws_credentials("token-1")),
+                Some(aws_credentials("token-2"))
+            ]
+        );
+        assert_eq!(command.calls().len(), 2);
+
+        // A token that was just issued is not replaced when rejected.
+        let (command, provider) = aws_codeartifact();
+        let middleware = AuthMiddleware::new()
+            .with_cache(CredentialsCache::new())
+            .with_index_helper("example.com", provider);
+        let mut transport =
+            FakeTransport::protected("aws", "token-2").with_challenge(StatusCode::FORBIDDEN);
+        let response = fake_get(&middleware, &mut transport, "https://example.com/simple/").await?;
+        assert_eq!(response.status(), 403);
+        assert_eq!(transport.sent, vec![Some(aws_credentials("token-1"))]);
+        assert_eq!(command.calls().len(), 1);
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_transport_credential_helper_not_installed() -> Result<(), Error> {
+        let command = Arc::new(ScriptedCommand::new(|_| {
+            Err(std::io::Error::from(std::io::ErrorKind::NotFound))
+        }));
+        let helper = CredentialHelper::AwsCodeartifact {
+            domain: "my-domain".to_string(),
+            domain_owner: "111122223333".to_string(),
+            region: None,
+        };
+        let middleware = AuthMiddleware::new()
+            .with_cache(CredentialsCache::new())
+            .with_index_helper(
+                "example.com",
+                CredentialHelperProvider::with_command(helper, command.clone()),
+            );
+
+        // Without a token, the request is sent without credentials.
+        for _ in 0..2 {
+            let mut transport = FakeTransport::protected("aws", "token-1");
+            let response =
+                fake_get(&middleware, &mut transport, "https://example.com/simple/").await?;
+            assert_eq!(response.status(), 401);
+            assert_eq!(transport.sent, vec![None]);
+        }
+        // The missing command is not spawned again.
+        assert_eq!(command.calls().len(), 1);
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_transport_keyring_username_discovery() -> Result<(), Error> {
+        let middleware = AuthMiddleware::new()
+            .with_cache(CredentialsCache::new())
+            .with_netrc(None)
+            .with_keyring(Some(KeyringProvider::dummy([
+                (("https://discovery.example.com", "__token__"), "token"),
+                (("https://other.example.com", "__token__"), "token"),
+            ])))
+            .with_username_discovery(vec![Url::parse("https://discovery.example.com/simple/")?])
+            .with_discovery_candidates(&["__token__"]);
+
+        // Without a username in the URL, the token username is discovered in the keyring.
+        let mut transport = FakeTransport::protected("__token__", "token");
+        let response = fake_get(
+            &middleware,
+            &mut transport,
+            "https://discovery.example.com/files/pkg-1.0.0.tar.gz",
+        )
+        .await?;
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            transport.sent,
+            vec![
+                None,
+                Some(Credentials::new(
+                    Some("__token__".to_string()),
+                    Some("token".to_string())
+                ))
+            ]
+        );
+
+        // The discovered username is recorded in the cache, so that it can be persisted.
+        let discovered = middleware
+            .cache()
+            .take_discovered_usernames()
+            .into_iter()
+            .map(|(url, username)| (url.to_string(), username))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            discovered,
+            vec![(
+                "https://discovery.example.com/files/pkg-1.0.0.tar.gz".to_string(),
+                "__token__".to_string()
+            )]
+        );
+
+        // A URL that isn't on a configured index never triggers discovery.
+        let mut transport = FakeTransport::protected("__token__", "token");
+        let response = fake_get(
+            &middleware,
+            &mut transport,
+            "https://other.example.com/simple/",
+        )
+        .await?;
+        assert_eq!(response.status(), 401);
+        assert_eq!(transport.sent, vec![None]);
+        assert_eq!(middleware.cache().take_discovered_usernames(), Vec::new());
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_transport_keyring_username_discovery_stored_username() -> Result<(), Error> {
+        let middleware = AuthMiddleware::new()
+            .with_cache(CredentialsCache::new())
+            .with_netrc(None)
+            .with_keyring(Some(KeyringProvider::dummy([(
+                ("https://no-match.example.com", "unrelated-user"),
+                "password",
+            )])))
+            .with_username_discovery(vec![Url::parse("https://no-match.example.com/simple/")?])
+            .with_discovery_candidates(&["__token__", "user"]);
+
+        // None of the candidate usernames are in the keyring, so the keyring is asked for the
+        // username it stores with the password.
+        let mut transport = FakeTransport::protected("unrelated-user", "password");
+        let response = fake_get(
+            &middleware,
+            &mut transport,
+            "https://no-match.example.com/simple/",
+        )
+        .await?;
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            transport.sent,
+            vec![
+                None,
+                Some(Credentials::new(
+                    Some("unrelated-user".to_string()),
+                    Some("password".to_string())
+                ))
+            ]
+        );
+        assert_eq!(
+            middleware
+                .cache()
+                .take_discovered_usernames()
+                .into_iter()
+                .map(|(_, username)| username)
+                .collect::<Vec<_>>(),
+            vec!["unrelated-user".to_string()]
+        );
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_transport_keyring_without_username() -> Result<(), Error> {
+        let middleware = || -> Result<AuthMiddleware, Error> {
+            Ok(AuthMiddleware::new()
+                .with_cache(CredentialsCache::new())
+                .with_netrc(None)
+                .with_keyring(Some(KeyringProvider::dummy([(
+                    ("https://no-username.example.com", ""),
+                    "token",
+                )])))
+                .with_username_discovery(vec![Url::parse(
+                    "https://no-username.example.com/simple/",
+                )?])
+                .with_discovery_candidates(&["__token__"]))
+        };
+
+        // None of the candidate usernames are in the keyring, so the entry without a username is
+        // used.
+        let mut transport = FakeTransport::protected("", "token");
+        let response = fake_get(
+            &middleware()?,
+            &mut transport,
+            "https://no-username.example.com/simple/",
+        )
+        .await?;
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            transport.sent,
+            vec![
+                None,
+                Some(Credentials::new(None, Some("token".to_string())))
+            ]
+        );
+
+        // With a username on the request, the entry is never used.
+        let mut transport = FakeTransport::protected("", "token");
+        let response = fake_get(
+            &middleware()?,
+            &mut transport,
+            "https://user@no-username.example.com/simple/",
+        )
+        .await?;
+        assert_eq!(response.status(), 401);
+        assert!(transport
+            .sent
+            .iter()
+            .flatten()
+            .all(|credentials| credentials.password().is_none()));
+
+        // Without discovery for the URL, the entry is never used either.
+        let middleware = middleware()?.with_username_discovery(Vec::new());
+        let mut transport = FakeTransport::protected("", "token");
+        let response = fake_get(
+            &middleware,
+            &mut transport,
+            "https://no-username.example.com/simple/",
+        )
+        .await?;
+        assert_eq!(response.status(), 401);
+        assert_eq!(transport.sent, vec![None]);
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_transport_keyring_host_policy() -> Result<(), Error> {
+        let policy = HostPolicy::new(Some(vec!["*.corp.example".parse()?]), vec![]);
+        let middleware = AuthMiddleware::new()
+            .with_cache(CredentialsCache::new())
+            .with_netrc(None)
+            .with_keyring(Some(
+                KeyringProvider::dummy([
+                    (("https://pypi.corp.example", "user"), "password"),
+                    (("https://pypi.corp.example.evil.io", "user"), "password"),
+                ])
+                .with_host_policy(policy),
+            ));
+
+        // The keyring is queried for an allowed host.
+        let mut transport = FakeTransport::protected("user", "password");
+        let response = fake_get(
+            &middleware,
+            &mut transport,
+            "https://user@pypi.corp.example/simple/",
+        )
+        .await?;
+        assert_eq!(response.status(), 200);
+        assert_eq!(transport.sent, vec![Some(user_credentials("password"))]);
+
+        // The credentials for a lookalike host outside of the allowlist are withheld.
+        let mut transport = FakeTransport::protected("user", "password");
+        let response = fake_get(
+            &middleware,
+            &mut transport,
+            "https://user@pypi.corp.example.evil.io/simple/",
+        )
+        .await?;
+        assert_eq!(response.status(), 401);
+        assert!(transport
+            .sent
+            .iter()
+            .flatten()
+            .all(|credentials| credentials.password().is_none()));
+
+        Ok(())
+    }
+
+    /// With compatibility with poetry enabled, the credentials that poetry stored for an index are
+    /// used, with the name of the index recorded in the auth config.
+    #[test(tokio::test)]
+    async fn test_transport_keyring_compat() -> Result<(), Error> {
+        let auth_config: AuthConfig = toml::from_str(
+            "[indexes.\"pypi.internal.example\"]\nusernames = [\"user\"]\nname = \"internal\"",
+        )?;
+        let middleware = AuthMiddleware::new()
+            .with_cache(CredentialsCache::new())
+            .with_netrc(None)
+            .with_keyring(Some(
+                KeyringProvider::dummy([(("poetry-repository-internal", "user"), "password")])
+                    .with_compat(vec![KeyringCompat::Poetry]),
+            ))
+            .with_auth_config(Some(auth_config));
+
+        let mut transport = FakeTransport::protected("user", "password");
+        let response = fake_get(
+            &middleware,
+            &mut transport,
+            "https://pypi.internal.example/simple/",
+        )
+        .await?;
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            transport.sent.last(),
+            Some(&Some(user_credentials("password")))
+        );
+
+        Ok(())
+    }
+
+    /// The auth config for the index with the given sources, if any of them records a username.
+    fn test_auth_config(sources: &[TestSource]) -> Result<Option<AuthConfig>, Error> {
+        let mut usernames = Vec::new();
+        if sources.contains(&TestSource::ConfiguredUsername)
+            || sources.contains(&TestSource::HostPattern)
+        {
+            usernames.push("user");
+        }
+        if sources.contains(&TestSource::Bearer) {
+            usernames.push(BEARER_USERNAME);
+        }
+        if usernames.is_empty() {
+            return Ok(None);
+        }
+        let scheme = if sources.contains(&TestSource::Bearer) {
+            "bearer"
+        } else {
+            "basic"
+        };
+        Ok(Some(toml::from_str(&format!(
+            "[indexes.\"pypi.example.com\"]\nusernames = {usernames:?}\nscheme = \"{scheme}\""
+        ))?))
+    }
+
+    /// The keyring with the entries of the given sources.
+    fn test_keyring(sources: &[TestSource]) -> Result<KeyringProvider, Error> {
+        let entries = [
+            (
+                TestSource::ConfiguredUsername,
+                "https://pypi.example.com/simple",
+                "user",
+            ),
+            (TestSource::HostPattern, "https://pypi.example.com", "user"),
+            (
+                TestSource::Bearer,
+                "https://pypi.example.com/simple",
+                BEARER_USERNAME,
+            ),
+            (
+                TestSource::DefaultUsername,
+                "https://pypi.example.com",
+                "__token__",
+            ),
+            (TestSource::NoUsername, "https://pypi.example.com", ""),
+        ];
+        let keyring = KeyringProvider::dummy(
+            entries
+                .into_iter()
+                .filter(|(source, ..)| sources.contains(source))
+                .map(|(source, service, username)| ((service, username), source.password())),
+        );
+        if sources.contains(&TestSource::HostPattern) {
+            let policy = HostPolicy::new(Some(vec!["*.example.com".parse()?]), vec![]);
+            Ok(keyring.with_host_policy(policy))
+        } else {
+            Ok(keyring)
+        }
+    }
+
+    /// The netrc file with the entry of the given sources, if any.
+    fn test_netrc(sources: &[TestSource]) -> Result<Option<NetrcProvider>, Error> {
+        if sources.contains(&TestSource::Netrc) {
+            netrc_with(&format!(
+                "machine pypi.example.com login user password {}",
+                TestSource::Netrc.password()
+            ))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// The request URL for the index with the given sources.
+    fn test_url(sources: &[TestSource]) -> Result<Url, Error> {
+        let mut url = Url::parse("https://pypi.example.com/simple/")?;
+        if sources.contains(&TestSource::Url) {
+            url.set_username("user").unwrap();
+            url.set_password(Some(TestSource::Url.password())).unwrap();
+        }
+        Ok(url)
+    }
+
+    /// Send a request for the index with the given sources through the [`AuthMiddleware`], as uv
+    /// configures it, and return the `Authorization` header it was sent with.
+    async fn send_with_sources(sources: &[TestSource]) -> Result<Option<String>, Error> {
+        let index_url = Url::parse("https://pypi.example.com/simple/")?;
+        let cache = CredentialsCache::new();
+        if sources.contains(&TestSource::Environment) {
+            cache.insert(
+                &index_url,
+                Arc::new(
+                    Credentials::new(
+                        Some("user".to_string()),
+                        Some(TestSource::Environment.password().to_string()),
+                    )
+                    .with_origin(CredentialOrigin::Environment),
+                ),
+            );
+        }
+        let mut middleware = AuthMiddleware::new()
+            .with_cache(cache)
+            .with_only_authenticated(true)
+            .with_keyring(Some(test_keyring(sources)?))
+            .with_netrc(test_netrc(sources)?)
+            .with_auth_config(test_auth_config(sources)?)
+            .with_username_discovery(vec![index_url])
+            .with_discovery_candidates(&[TOKEN_USERNAME]);
+        if sources.contains(&TestSource::Helper) {
+            let (_, helper) = aws_codeartifact();
+            middleware = middleware.with_index_helper("pypi.example.com", helper);
+        }
+
+        let mut transport = FakeTransport::public();
+        let response = fake_get(&middleware, &mut transport, test_url(sources)?.as_str()).await?;
+        assert_eq!(response.status(), 200);
+        Ok(transport.authorization.into_iter().next().flatten())
+    }
+
+    /// Explain which credentials are used for the index with the given sources, as
+    /// `uv index credentials list` does: the credential helper of the index, or else the
+    /// credentials that the [`CredentialSources`] find first for the usernames in the auth config,
+    /// with the scheme recorded there for those from the keyring.
+    async fn explain_with_sources(sources: &[TestSource]) -> Result<Option<Credentials>, Error> {
+        if sources.contains(&TestSource::Helper) {
+            let (_, helper) = aws_codeartifact();
+            return Ok(Some(Credentials::clone(&*helper.fetch().await?)));
+        }
+
+        let url = test_url(sources)?;
+        let keyring = test_keyring(sources)?;
+        let netrc = test_netrc(sources)?;
+        let chain = CredentialSources::new(None, Some(&keyring), netrc.as_ref())
+            .with_discovery_candidates(&[TOKEN_USERNAME]);
+        let (env_username, env_password) = if sources.contains(&TestSource::Environment) {
+            (
+                Some("user".to_string()),
+                Some(TestSource::Environment.password().to_string()),
+            )
+        } else {
+            (None, None)
+        };
+        let auth_config = test_auth_config(sources)?;
+        let entry = auth_config
+            .as_ref()
+            .and_then(|auth_config| auth_config.find_entry(&url));
+        let usernames: Vec<Option<&str>> = if let Some(entry) = entry {
+            entry
+                .usernames
+                .iter()
+                .map(|username| Some(username.as_str()))
+                .collect()
+        } else {
+            vec![Some(url.username()).filter(|username| !username.is_empty())]
+        };
+
+        for username in usernames {
+            let found = chain
+                .fetch_with_env(
+                    env_username.clone(),
+                    env_password.clone(),
+                    &url,
+                    username,
+                    None,
+                )
+                .await?;
+            if let Some((credentials, source)) = found {
+                let scheme = match (source, entry) {
+                    (CredentialSource::Keyring, Some(entry)) => entry.scheme,
+                    _ => AuthScheme::Basic,
+                };
+                return Ok(Some(credentials.with_scheme(scheme)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// For every credential source, and every pair of them, explain which credentials are used
+    /// for an index, as `uv index credentials list` does, and perform a request, asserting that
+    /// the credentials that were actually sent are the explained ones, from the source with the
+    /// highest precedence.
+    ///
+    /// New credential sources should be added to [`TestSource`] so that their precedence is
+    /// covered here.
+    #[test(tokio::test)]
+    async fn test_source_precedence_pairwise() -> Result<(), Error> {
+        let mut combinations = TestSource::PRECEDENCE
+            .iter()
+            .map(|source| vec![*source])
+            .collect::<Vec<_>>();
+        for (index, first) in TestSource::PRECEDENCE.iter().enumerate() {
+            for second in &TestSource::PRECEDENCE[index + 1..] {
+                combinations.push(vec![*first, *second]);
+            }
+        }
+
+        for sources in combinations {
+            let winner = sources[0];
+
+            let explained = explain_with_sources(&sources).await?;
+            assert_eq!(
+                explained
+                    .as_ref()
+                    .and_then(|credentials| credentials.password())
+                    .map(SecretString::expose),
+                Some(winner.password()),
+                "Expected {winner:?} to take precedence in the explanation for {sources:?}"
+            );
+
+            let expected = explained
+                .map(|credentials| {
+                    credentials
+                        .to_header_value()
+                        .to_str()
+                        .map(ToString::to_string)
+                })
+                .transpose()?;
+            assert_eq!(
+                send_with_sources(&sources).await?,
+                expected,
+                "Expected the middleware to send the explained credentials for {sources:?}"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// When provenance is recorded, the responses to requests sent with credentials carry the
+    /// index, source, and fingerprint of the credentials.
+    #[test(tokio::test)]
+    async fn test_transport_provenance() -> Result<(), Error> {
+        let dir = tempfile::tempdir()?;
+        crate::set_provenance_log_path(Some(dir.path().join("provenance.jsonl")));
+
+        let middleware = AuthMiddleware::new()
+            .with_cache(CredentialsCache::new())
+            .with_netrc(netrc_with(
+                "machine example.com login user password password",
+            )?)
+            .with_index_names(vec![
+                (
+                    Url::parse("https://example.com/simple")?,
+                    "internal".to_string(),
+                ),
+                (
+                    Url::parse("https://example.com/private/simple")?,
+                    "private".to_string(),
+                ),
+            ]);
+
+        let mut transport = FakeTransport::public();
+        let response = fake_get(&middleware, &mut transport, "https://example.com/simple/").await?;
+        assert_eq!(response.extensions().get::<CredentialProvenance>(), None);
+
+        let expected = |index: &str| CredentialProvenance {
+            realm: "https://example.com".to_string(),
+            index: Some(index.to_string()),
+            source: "netrc".to_string(),
+            fingerprint: crate::fingerprint("password"),
+        };
+
+        // Files outside of the index URLs are attributed to the first index on the realm.
+        let mut transport = FakeTransport::protected("user", "password");
+        let response = fake_get(
+            &middleware,
+            &mut transport,
+            "https://example.com/files/ok-1.0.0-py3-none-any.whl",
+        )
+        .await?;
+        assert_eq!(
+            response.extensions().get::<CredentialProvenance>(),
+            Some(&expected("internal"))
+        );
+
+        // The cached credentials keep their source.
+        let response = fake_get(
+            &middleware,
+            &mut transport,
+            "https://example.com/private/simple/ok/",
+        )
+        .await?;
+        assert_eq!(
+            response.extensions().get::<CredentialProvenance>(),
+            Some(&expected("private"))
+        );
+
+        crate::set_provenance_log_path(None);
+        Ok(())
+    }
 }
@@ -0,0 +1,133 @@
+use std::fmt::Debug;
+
+/// A credential store provided by the operating system, e.g., the Windows Credential Manager, the
+/// macOS Keychain, or the Secret Service on Linux.
+///
+/// Entries are addressed by a service name and a username, matching the `keyring` CLI.
+pub(crate) trait NativeStore: Debug + Send + Sync {
+    /// Return the password stored for the given service and username, if any.
+    fn get_password(&self, service_name: &str, username: &str) -> Result<Option<String>, Error>;
+
+    /// Store a password for the given service and username, replacing any existing entry.
+    fn set_password(&self, service_name: &str, username: &str, password: &str)
+        -> Result<(), Error>;
+
+    /// Remove the entry for the given service and username.
+    ///
+    /// Returns `false` if there was no such entry.
+    fn delete_password(&self, service_name: &str, username: &str) -> Result<bool, Error>;
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub(crate) struct Error(String);
+
+/// The credential store of the current platform, accessed via the `keyring` crate.
+#[cfg(feature = "native-keyring")]
+#[derive(Debug, Default)]
+pub(crate) struct OsStore;
+
+#[cfg(feature = "native-keyring")]
+impl OsStore {
+    fn entry(service_name: &str, username: &str) -> Result<keyring::Entry, Error> {
+        keyring::Entry::new(service_name, username).map_err(|err| Error(err.to_string()))
+    }
+}
+
+#[cfg(feature = "native-keyring")]
+impl NativeStore for OsStore {
+    fn get_password(&self, service_name: &str, username: &str) -> Result<Option<String>, Error> {
+        match Self::entry(service_name, username)?.get_password() {
+            Ok(password) => Ok(Some(password)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(err) => Err(Error(err.to_string())),
+        }
+    }
+
+    fn set_password(
+        &self,
+        service_name: &str,
+        username: &str,
+        password: &str,
+    ) -> Result<(), Error> {
+        Self::entry(service_name, username)?
+            .set_password(password)
+            .map_err(|err| Error(err.to_string()))
+    }
+
+    fn delete_password(&self, service_name: &str, username: &str) -> Result<bool, Error> {
+        match Self::entry(service_name, username)?.delete_credential() {
+            Ok(()) => Ok(true),
+            Err(keyring::Error::NoEntry) => Ok(false),
+            Err(err) => Err(Error(err.to_string())),
+        }
+    }
+}
+
+/// An in-memory [`NativeStore`] for testing.
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub(crate) struct MemoryStore {
+    entries: std::sync::Mutex<std::collections::HashMap<(String, String), String>>,
+    /// The number of calls to [`NativeStore::get_password`].
+    lookups: std::sync::atomic::AtomicUsize,
+}
+
+#[cfg(test)]
+impl MemoryStore {
+    /// Return the stored entries, sorted by service name and username.
+    pub(crate) fn entries(&self) -> Vec<(String, String, String)> {
+        let mut entries: Vec<_> = self
+            .entries
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|((service_name, username), password)| {
+                (service_name.clone(), username.clone(), password.clone())
+            })
+            .collect();
+        entries.sort();
+        entries
+    }
+
+    /// Return the number of passwords that were looked up.
+    pub(crate) fn lookups(&self) -> usize {
+        self.lookups.load(std::sync::atomic::Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+impl NativeStore for MemoryStore {
+    fn get_password(&self, service_name: &str, username: &str) -> Result<Option<String>, Error> {
+        self.lookups
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        Ok(self
+            .entries
+            .lock()
+            .unwrap()
+            .get(&(service_name.to_string(), username.to_string()))
+            .cloned())
+    }
+
+    fn set_password(
+        &self,
+        service_name: &str,
+        username: &str,
+        password: &str,
+    ) -> Result<(), Error> {
+        self.entries.lock().unwrap().insert(
+            (service_name.to_string(), username.to_string()),
+            password.to_string(),
+        );
+        Ok(())
+    }
+
+    fn delete_password(&self, service_name: &str, username: &str) -> Result<bool, Error> {
+        Ok(self
+            .entries
+            .lock()
+            .unwrap()
+            .remove(&(service_name.to_string(), username.to_string()))
+            .is_some())
+    }
+}
@@ -0,0 +1,374 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use tracing::debug;
+use url::Url;
+
+use uv_static::EnvVars;
+use uv_warnings::warn_user;
+
+use crate::{CredentialOrigin, Credentials};
+
+/// An entry in a netrc file.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct NetrcEntry {
+    login: Option<String>,
+    password: Option<String>,
+}
+
+/// A source of credentials from a [netrc](https://everything.curl.dev/usingcurl/netrc) file.
+///
+/// Supports the `machine`, `default`, `login`, `password`, `account`, and `macdef` tokens, and
+/// values in double quotes. Malformed entries are skipped with a warning, rather than failing
+/// to load the whole file.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct NetrcProvider {
+    machines: HashMap<String, NetrcEntry>,
+    default: Option<NetrcEntry>,
+}
+
+impl NetrcProvider {
+    /// Load the netrc file from the path in `NETRC`, or from the home directory.
+    ///
+    /// Returns [`None`] if there is no netrc file, or if it cannot be read.
+    pub fn load() -> Option<Self> {
+        let path = Self::default_path()?;
+        match Self::from_path(&path) {
+            Ok(netrc) => Some(netrc),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                debug!("No netrc file found at `{}`", path.display());
+                None
+            }
+            Err(err) => {
+                warn_user!("Failed to read netrc file at `{}`: {err}", path.display());
+                None
+            }
+        }
+    }
+
+    /// Read the netrc file at the given path.
+    ///
+    /// Warns if the file is readable by other users, or contains malformed entries.
+    pub fn from_path(path: &Path) -> std::io::Result<Self> {
+        let contents = fs_err::read_to_string(path)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let mode = fs_err::metadata(path)?.permissions().mode();
+            if let Some(warning) = Self::permission_warning(path, mode) {
+                warn_user!("{warning}");
+            }
+        }
+        let (netrc, warnings) = Self::parse(&contents);
+        for warning in warnings {
+            warn_user!(
+                "Ignoring malformed netrc entry in `{}`: {warning}",
+                path.display()
+            );
+        }
+        Ok(netrc)
+    }
+
+    /// Return the path of the netrc file, from `NETRC` or the home directory.
+    fn default_path() -> Option<PathBuf> {
+        if let Some(path) = std::env::var_os(EnvVars::NETRC) {
+            return Some(PathBuf::from(path));
+        }
+        let home = etcetera::home_dir().ok()?;
+        let path = home.join(".netrc");
+        if cfg!(windows) && !path.exists() {
+            return Some(home.join("_netrc"));
+        }
+        Some(path)
+    }
+
+    /// Return a warning if the netrc file is accessible by users other than its owner.
+    #[cfg(unix)]
+    fn permission_warning(path: &Path, mode: u32) -> Option<String> {
+        let group_and_other = mode & 0o077;
+        if group_and_other == 0 {
+            return None;
+        }
+        Some(format!(
+            "The netrc file at `{}` is accessible by other users (mode {:o}); restrict it with `chmod 600`",
+            path.display(),
+            mode & 0o777
+        ))
+    }
+
+    /// Parse the contents of a netrc file, returning the parsed entries and a warning for each
+    /// malformed entry.
+    pub(crate) fn parse(contents: &str) -> (Self, Vec<String>) {
+        let mut netrc = Self::default();
+        let mut warnings = Vec::new();
+        let mut tokens = Tokenizer::new(contents);
+
+        // The entry that `login` and `password` tokens apply to, if any.
+        let mut current: Option<(Option<String>, NetrcEntry)> = None;
+        let mut finish = |current: Option<(Option<String>, NetrcEntry)>| match current {
+            Some((Some(machine), entry)) => {
+                netrc.machines.entry(machine).or_insert(entry);
+            }
+            Some((None, entry)) => {
+                netrc.default.get_or_insert(entry);
+            }
+            None => {}
+        };
+
+        while let Some((line, token)) = tokens.next_token() {
+            match token.as_str() {
+                "machine" => {
+                    finish(current.take());
+                    match tokens.next_token() {
+                        Some((_, machine)) => {
+                            current = Some((Some(machine), NetrcEntry::default()));
+                        }
+                        None => warnings.push(format!("`machine` without a name on line {line}")),
+                    }
+                }
+                "default" => {
+                    finish(current.take());
+                    current = Some((None, NetrcEntry::default()));
+                }
+                "login" | "password" | "account" => {
+                    let Some((_, value)) = tokens.next_token() else {
+                        warnings.push(format!("`{token}` without a value on line {line}"));
+                        continue;
+                    };
+                    let Some((_, entry)) = current.as_mut() else {
+                        warnings.push(format!("`{token}` outside of a machine on line {line}"));
+                        continue;
+                    };
+                    match token.as_str() {
+                        "login" => entry.login = Some(value),
+                        "password" => entry.password = Some(value),
+                        _ => {}
+                    }
+                }
+                "macdef" => {
+                    tokens.skip_macro();
+                }
+                _ => warnings.push(format!("unexpected token `{token}` on line {line}")),
+            }
+        }
+        finish(current.take());
+        warnings.extend(tokens.warnings);
+
+        (netrc, warnings)
+    }
+
+    /// Return the [`Credentials`] for the host of the given URL, falling back to the `default`
+    /// entry.
+    ///
+    /// If a username is provided, it must match the login in the netrc file. Entries without a
+    /// password are ignored.
+    pub fn fetch(&self, url: &Url, username: Option<&str>) -> Option<Credentials> {
+        let host = url.host_str()?;
+        let entry = self.machines.get(host).or(self.default.as_ref())?;
+        if username.is_some_and(|username| Some(username) != entry.login.as_deref()) {
+            return None;
+        }
+        let password = entry.password.clone()?;
+        Some(
+            Credentials::new(entry.login.clone(), Some(password))
+                .with_origin(CredentialOrigin::Netrc),
+        )
+    }
+}
+
+/// Splits the contents of a netrc file into tokens, tracking the line of each token.
+struct Tokenizer<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+    warnings: Vec<String>,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn new(contents: &'a str) -> Self {
+        Self {
+            chars: contents.chars().peekable(),
+            line: 1,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Return the next token and the line it starts on, skipping whitespace and comments.
+    fn next_token(&mut self) -> Option<(usize, String)> {
+        loop {
+            match *self.chars.peek()? {
+                '\n' => {
+                    self.line += 1;
+                    self.chars.next();
+                }
+                c if c.is_whitespace() => {
+                    self.chars.next();
+                }
+                '#' => while self.chars.next_if(|c| *c != '\n').is_some() {},
+                _ => break,
+            }
+        }
+
+        let line = self.line;
+        let mut token = String::new();
+        if self.chars.next_if_eq(&'"').is_some() {
+            loop {
+                match self.chars.next() {
+                    Some('"') => break,
+                    Some('\\') => {
+                        if let Some(c) = self.chars.next() {
+                            token.push(c);
+                        }
+                    }
+                    Some(c) => {
+                        if c == '\n' {
+                            self.line += 1;
+                        }
+                        token.push(c);
+                    }
+                    None => {
+                        self.warnings
+                            .push(format!("unterminated quoted value on line {line}"));
+                        break;
+                    }
+                }
+            }
+        } else {
+            while let Some(c) = self.chars.next_if(|c| !c.is_whitespace()) {
+                token.push(c);
+            }
+        }
+        Some((line, token))
+    }
+
+    /// Skip the body of a macro definition, which ends at the next empty line.
+    fn skip_macro(&mut self) {
+        let mut previous = None;
+        for c in self.chars.by_ref() {
+            if c == '\n' {
+                self.line += 1;
+                if previous == Some('\n') {
+                    break;
+                }
+            }
+            previous = Some(c);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SecretString;
+
+    fn fetch(netrc: &NetrcProvider, url: &str, username: Option<&str>) -> Option<(String, String)> {
+        let credentials = netrc.fetch(&Url::parse(url).unwrap(), username)?;
+        Some((
+            credentials.username().unwrap_or_default().to_string(),
+            credentials
+                .password()
+                .map(SecretString::expose)
+                .unwrap_or_default()
+                .to_string(),
+        ))
+    }
+
+    #[test]
+    fn multiple_machines() {
+        let (netrc, warnings) = NetrcProvider::parse(
+            "# Artifactory\n\
+             machine artifactory.example.com login ci password hunter2\n\
+             machine pypi.example.com\n  login alice\n  password \"correct horse\"\n",
+        );
+        assert!(warnings.is_empty());
+        assert_eq!(
+            fetch(&netrc, "https://artifactory.example.com/simple", None),
+            Some(("ci".to_string(), "hunter2".to_string()))
+        );
+        assert_eq!(
+            fetch(&netrc, "https://pypi.example.com/simple", Some("alice")),
+            Some(("alice".to_string(), "correct horse".to_string()))
+        );
+        assert_eq!(
+            fetch(&netrc, "https://pypi.example.com/simple", Some("bob")),
+            None
+        );
+        assert_eq!(
+            fetch(&netrc, "https://other.example.com/simple", None),
+            None
+        );
+    }
+
+    #[test]
+    fn default_entry() {
+        let (netrc, warnings) = NetrcProvider::parse(
+            "machine pypi.example.com login alice password secret\n\
+             default login anonymous password \"a \\\"quoted\\\" value\"\n",
+        );
+        assert!(warnings.is_empty());
+        assert_eq!(
+            fetch(&netrc, "https://pypi.example.com/simple", None),
+            Some(("alice".to_string(), "secret".to_string()))
+        );
+        assert_eq!(
+            fetch(&netrc, "https://other.example.com/simple", None),
+            Some(("anonymous".to_string(), "a \"quoted\" value".to_string()))
+        );
+    }
+
+    #[test]
+    fn malformed() {
+        let (netrc, warnings) = NetrcProvider::parse(
+            "login orphan\n\
+             machine first.example.com login alice password secret bogus\n\
+             macdef init\ncd /pub\n\n\
+             machine second.example.com login bob password \"unterminated",
+        );
+        assert_eq!(
+            warnings,
+            vec![
+                "`login` outside of a machine on line 1",
+                "unexpected token `bogus` on line 2",
+                "unterminated quoted value on line 6",
+            ]
+        );
+        assert_eq!(
+            fetch(&netrc, "https://first.example.com/simple", None),
+            Some(("alice".to_string(), "secret".to_string()))
+        );
+        assert_eq!(
+            fetch(&netrc, "https://second.example.com/simple", None),
+            Some(("bob".to_string(), "unterminated".to_string()))
+        );
+
+        let (_, warnings) = NetrcProvider::parse("machine example.com login");
+        assert_eq!(warnings, vec!["`login` without a value on line 1"]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn permissions() {
+        use std::io::Write;
+        use std::os::unix::fs::PermissionsExt;
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "machine example.com login user password secret").unwrap();
+        let path = file.path();
+
+        fs_err::set_permissions(path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        let mode = fs_err::metadata(path).unwrap().permissions().mode();
+        let warning = NetrcProvider::permission_warning(path, mode).unwrap();
+        assert!(warning.contains("(mode 644)"), "{warning}");
+
+        fs_err::set_permissions(path, std::fs::Permissions::from_mode(0o600)).unwrap();
+        let mode = fs_err::metadata(path).unwrap().permissions().mode();
+        assert!(NetrcProvider::permission_warning(path, mode).is_none());
+
+        // The file is still loaded despite the warning.
+        let netrc = NetrcProvider::from_path(path).unwrap();
+        assert_eq!(
+            fetch(&netrc, "https://example.com/simple", None),
+            Some(("user".to_string(), "secret".to_string()))
+        );
+    }
+}
@@ -0,0 +1,408 @@
+use serde::{Deserialize, Serialize};
+
+use crate::keyring::KeyringError;
+use crate::SecretString;
+
+/// The default title of the 1Password item for a keyring service name.
+const DEFAULT_ITEM_TEMPLATE: &str = "{service}";
+
+/// How the 1Password backend maps keyring entries to items, see [`OnePasswordConfig::from_env`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct OnePasswordConfig {
+    /// The vault to read and write items in, or [`None`] for the default vaults of `op`.
+    pub(crate) vault: Option<String>,
+    /// The title of the item for an entry, with `{service}` and `{username}` placeholders.
+    pub(crate) item_template: String,
+}
+
+impl Default for OnePasswordConfig {
+    fn default() -> Self {
+        Self {
+            vault: None,
+            item_template: DEFAULT_ITEM_TEMPLATE.to_string(),
+        }
+    }
+}
+
+impl OnePasswordConfig {
+    /// Read the vault from `UV_OP_VAULT` and the item title template from `UV_OP_ITEM_TEMPLATE`.
+    #[cfg(feature = "onepassword")]
+    pub(crate) fn from_env() -> Self {
+        use uv_static::EnvVars;
+
+        let non_empty = |name| std::env::var(name).ok().filter(|value| !value.is_empty());
+        Self {
+            vault: non_empty(EnvVars::UV_OP_VAULT),
+            item_template: non_empty(EnvVars::UV_OP_ITEM_TEMPLATE)
+                .unwrap_or_else(|| DEFAULT_ITEM_TEMPLATE.to_string()),
+        }
+    }
+
+    /// The title of the item for the given service name and username.
+    ///
+    /// Returns [`None`] if the template refers to the username, but none is known.
+    pub(crate) fn item_title(&self, service_name: &str, username: Option<&str>) -> Option<String> {
+        let title = self.item_template.replace("{service}", service_name);
+        if title.contains("{username}") {
+            Some(title.replace("{username}", username?))
+        } else {
+            Some(title)
+        }
+    }
+
+    /// The arguments of `op` to read the username and password of an item.
+    pub(crate) fn get_fields_args(&self, title: &str) -> Vec<String> {
+        self.with_vault(vec![
+            "item".to_string(),
+            "get".to_string(),
+            title.to_string(),
+            "--fields".to_string(),
+            "username,password".to_string(),
+            "--format".to_string(),
+            "json".to_string(),
+        ])
+    }
+
+    /// The arguments of `op` to read an item, including its ID.
+    pub(crate) fn get_item_args(&self, title: &str) -> Vec<String> {
+        self.with_vault(vec![
+            "item".to_string(),
+            "get".to_string(),
+            title.to_string(),
+            "--format".to_string(),
+            "json".to_string(),
+        ])
+    }
+
+    /// The arguments of `op` to create an item from the JSON template on stdin, see
+    /// [`login_item`].
+    pub(crate) fn create_args(&self) -> Vec<String> {
+        self.with_vault(vec![
+            "item".to_string(),
+            "create".to_string(),
+            "--format".to_string(),
+            "json".to_string(),
+        ])
+    }
+
+    /// The arguments of `op` to delete the item with the given ID.
+    pub(crate) fn delete_args(&self, id: &str) -> Vec<String> {
+        self.with_vault(vec![
+            "item".to_string(),
+            "delete".to_string(),
+            id.to_string(),
+        ])
+    }
+
+    fn with_vault(&self, mut args: Vec<String>) -> Vec<String> {
+        if let Some(vault) = &self.vault {
+            args.push("--vault".to_string());
+            args.push(vault.clone());
+        }
+        args
+    }
+}
+
+/// A field of a 1Password item, as printed by `op item get --format json`.
+#[derive(Debug, Deserialize)]
+struct OpField {
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    label: String,
+    #[serde(default)]
+    purpose: Option<String>,
+    #[serde(default)]
+    value: Option<String>,
+}
+
+impl OpField {
+    /// Whether this is the username (or password) field of a login item.
+    fn is(&self, name: &str, purpose: &str) -> bool {
+        self.purpose.as_deref() == Some(purpose) || self.id == name || self.label == name
+    }
+}
+
+/// A 1Password item, as printed by `op item get --format json`.
+#[derive(Debug, Deserialize)]
+struct OpItem {
+    id: String,
+    #[serde(default)]
+    fields: Vec<OpField>,
+}
+
+/// The username and password of a 1Password item.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct OpLogin {
+    pub(crate) username: Option<String>,
+    pub(crate) password: Option<String>,
+}
+
+impl OpLogin {
+    fn from_fields(fields: Vec<OpField>) -> Self {
+        let mut login = Self::default();
+        for field in fields {
+            if field.is("username", "USERNAME") {
+                login.username = field.value.filter(|value| !value.is_empty());
+            } else if field.is("password", "PASSWORD") {
+                login.password = field.value.filter(|value| !value.is_empty());
+            }
+        }
+        login
+    }
+}
+
+/// Parse the output of `op item get --fields username,password --format json`.
+///
+/// `op` prints a single object if only one of the fields exists, and an array otherwise.
+pub(crate) fn parse_fields(stdout: &[u8]) -> Result<OpLogin, KeyringError> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Fields {
+        Many(Vec<OpField>),
+        One(OpField),
+    }
+
+    let fields = match serde_json::from_slice(stdout).map_err(|err| malformed(&err))? {
+        Fields::Many(fields) => fields,
+        Fields::One(field) => vec![field],
+    };
+    Ok(OpLogin::from_fields(fields))
+}
+
+/// Parse the output of `op item get --format json`, returning the ID and the login of the item.
+pub(crate) fn parse_item(stdout: &[u8]) -> Result<(String, OpLogin), KeyringError> {
+    let item: OpItem = serde_json::from_slice(stdout).map_err(|err| malformed(&err))?;
+    Ok((item.id, OpLogin::from_fields(item.fields)))
+}
+
+fn malformed(err: &serde_json::Error) -> KeyringError {
+    KeyringError::MalformedCredentials(format!("`op` returned malformed JSON: {err}"))
+}
+
+/// The JSON template of a login item, passed to `op item create` on stdin so that the password
+/// is not visible in the process arguments.
+///
+/// The template is serialized from borrowed fields into a buffer that is large enough for any
+/// escaping, so that the only copy of the password is the returned [`SecretString`].
+pub(crate) fn login_item(title: &str, username: &str, password: &str) -> SecretString {
+    #[derive(Serialize)]
+    struct LoginItem<'a> {
+        title: &'a str,
+        category: &'static str,
+        fields: [LoginField<'a>; 2],
+    }
+
+    #[derive(Serialize)]
+    struct LoginField<'a> {
+        id: &'static str,
+        #[serde(rename = "type")]
+        kind: &'static str,
+        purpose: &'static str,
+        label: &'static str,
+        value: &'a str,
+    }
+
+    let item = LoginItem {
+        title,
+        category: "LOGIN",
+        fields: [
+            LoginField {
+                id: "username",
+                kind: "STRING",
+                purpose: "USERNAME",
+                label: "username",
+                value: username,
+            },
+            LoginField {
+                id: "password",
+                kind: "CONCEALED",
+                purpose: "PASSWORD",
+                label: "password",
+                value: password,
+            },
+        ],
+    };
+    // An escaped character takes at most six bytes, as in `\u001f`.
+    let mut buffer = Vec::with_capacity(256 + 6 * (title.len() + username.len() + password.len()));
+    serde_json::to_writer(&mut buffer, &item).expect("A login item should serialize to JSON");
+    SecretString::new(String::from_utf8(buffer).expect("JSON should be valid UTF-8"))
+}
+
+/// Classify a failure of `op` from its stderr.
+///
+/// A missing item is not an error. The stderr of `op` can include hints about session tokens, so
+/// it's never passed on in full: only the first line of an unrecognized error is kept.
+pub(crate) fn check_failure(stderr: &str) -> Result<(), KeyringError> {
+    let lower = stderr.to_lowercase();
+    if lower.contains("isn't an item") || lower.contains("no item found") {
+        return Ok(());
+    }
+    if lower.contains("not currently signed in")
+        || lower.contains("not signed in")
+        || lower.contains("no accounts configured")
+        || lower.contains("authorization prompt dismissed")
+        || lower.contains("session expired")
+    {
+        return Err(KeyringError::NotSignedIn);
+    }
+    Err(KeyringError::BackendError(summarize(stderr)))
+}
+
+/// The first line of the stderr of `op`, without the `[ERROR] <timestamp>` prefix.
+pub(crate) fn summarize(stderr: &str) -> String {
+    let line = stderr.trim().lines().next().unwrap_or_default();
+    let message = line
+        .strip_prefix("[ERROR] ")
+        .map_or(line, |rest| {
+            // Skip the date and time, e.g., `2024/01/02 03:04:05`.
+            rest.splitn(3, ' ').nth(2).unwrap_or(rest)
+        })
+        .trim();
+    if message.contains("OP_SESSION") {
+        "`op` failed".to_string()
+    } else {
+        message.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn item_title() {
+        let config = OnePasswordConfig::default();
+        assert_eq!(
+            config
+                .item_title("https://example.com", Some("user"))
+                .as_deref(),
+            Some("https://example.com")
+        );
+
+        let config = OnePasswordConfig {
+            vault: None,
+            item_template: "uv: {username}@{service}".to_string(),
+        };
+        assert_eq!(
+            config
+                .item_title("https://example.com", Some("user"))
+                .as_deref(),
+            Some("uv: user@https://example.com")
+        );
+        // The title can't be determined without the username.
+        assert_eq!(config.item_title("https://example.com", None), None);
+    }
+
+    #[test]
+    fn vault_args() {
+        let config = OnePasswordConfig {
+            vault: Some("Engineering".to_string()),
+            ..OnePasswordConfig::default()
+        };
+        assert_eq!(
+            config.get_fields_args("https://example.com"),
+            [
+                "item",
+                "get",
+                "https://example.com",
+                "--fields",
+                "username,password",
+                "--format",
+                "json",
+                "--vault",
+                "Engineering"
+            ]
+        );
+        assert_eq!(
+            OnePasswordConfig::default().delete_args("abc123"),
+            ["item", "delete", "abc123"]
+        );
+    }
+
+    #[test]
+    fn fields() {
+        let stdout = br#"[
+            {"id": "username", "type": "STRING", "purpose": "USERNAME", "label": "username", "value": "user", "reference": "op://Private/example/username"},
+            {"id": "password", "type": "CONCEALED", "purpose": "PASSWORD", "label": "password", "value": "hunter2", "reference": "op://Private/example/password"}
+        ]"#;
+        assert_eq!(
+            parse_fields(stdout).unwrap(),
+            OpLogin {
+                username: Some("user".to_string()),
+                password: Some("hunter2".to_string()),
+            }
+        );
+
+        // An item with only a password is printed as a single object.
+        let stdout = br#"{"id": "password", "type": "CONCEALED", "purpose": "PASSWORD", "label": "password", "value": "hunter2"}"#;
+        assert_eq!(
+            parse_fields(stdout).unwrap(),
+            OpLogin {
+                username: None,
+                password: Some("hunter2".to_string()),
+            }
+        );
+
+        assert!(matches!(
+            parse_fields(b"Password: hunter2"),
+            Err(KeyringError::MalformedCredentials(_))
+        ));
+    }
+
+    #[test]
+    fn item() {
+        let stdout = br#"{
+            "id": "abc123",
+            "title": "https://example.com",
+            "category": "LOGIN",
+            "fields": [
+                {"id": "username", "type": "STRING", "purpose": "USERNAME", "label": "username", "value": "user"},
+                {"id": "password", "type": "CONCEALED", "purpose": "PASSWORD", "label": "password", "value": "hunter2"},
+                {"id": "notesPlain", "type": "STRING", "purpose": "NOTES", "label": "notesPlain"}
+            ]
+        }"#;
+        let (id, login) = parse_item(stdout).unwrap();
+        assert_eq!(id, "abc123");
+        assert_eq!(login.username.as_deref(), Some("user"));
+    }
+
+    #[test]
+    fn login_item_template() {
+        let item: serde_json::Value =
+            serde_json::from_str(login_item("https://example.com", "user", "hun\"ter2").expose())
+                .unwrap();
+        assert_eq!(item["title"], "https://example.com");
+        assert_eq!(item["fields"][1]["value"], "hun\"ter2");
+    }
+
+    #[test]
+    fn failures() {
+        assert_eq!(
+            check_failure(
+                "[ERROR] 2024/01/02 03:04:05 \"https://example.com\" isn't an item. Specify the item with its UUID, name, or domain.\n"
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            check_failure(
+                "[ERROR] 2024/01/02 03:04:05 You are not currently signed in. Please run `op signin --help` for instructions\n"
+            ),
+            Err(KeyringError::NotSignedIn)
+        );
+
+        // Only the first line of other errors is kept.
+        assert_eq!(
+            check_failure(
+                "[ERROR] 2024/01/02 03:04:05 vault \"Engineering\" not found\nTo reuse the session, run: export OP_SESSION_abc=secret\n"
+            ),
+            Err(KeyringError::BackendError(
+                "vault \"Engineering\" not found".to_string()
+            ))
+        );
+        assert_eq!(
+            summarize("export OP_SESSION_abc=secret"),
+            "`op` failed".to_string()
+        );
+    }
+}
@@ -0,0 +1,252 @@
+use sha2::{Digest, Sha256};
+use zeroize::Zeroize;
+
+/// A secret, e.g., a password or a token, that is overwritten in memory when it's dropped.
+///
+/// The secret is never shown by [`Debug`] or [`Display`](std::fmt::Display); it can only be read
+/// with [`SecretString::expose`], where it's sent, e.g., in an HTTP header or to the stdin of a
+/// keyring process.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct SecretString(String);
+
+impl SecretString {
+    pub fn new(secret: String) -> Self {
+        Self(secret)
+    }
+
+    /// The secret itself.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// A fingerprint of the secret, see [`fingerprint`].
+    pub fn fingerprint(&self) -> String {
+        fingerprint(&self.0)
+    }
+
+    /// Remove the ASCII whitespace around the secret, and find the characters within it that were
+    /// likely copied along with it by accident, e.g., a zero-width space from a web page.
+    ///
+    /// Only the kinds of characters are reported, never the secret itself.
+    #[must_use]
+    pub fn sanitize(self) -> (Self, SecretIssues) {
+        let trimmed = self.0.trim_matches(|c: char| c.is_ascii_whitespace());
+        let mut issues = SecretIssues {
+            trimmed: trimmed.len() != self.0.len(),
+            suspicious: Vec::new(),
+        };
+        for c in trimmed.chars() {
+            if let Some(kind) = SuspiciousChar::of(c) {
+                if !issues.suspicious.contains(&kind) {
+                    issues.suspicious.push(kind);
+                }
+            }
+        }
+        issues.suspicious.sort_unstable();
+        if !issues.trimmed {
+            return (self, issues);
+        }
+        (Self::new(trimmed.to_string()), issues)
+    }
+}
+
+/// A fingerprint of a secret, to tell secrets apart in messages without revealing them: the first
+/// eight hex digits of its SHA-256 digest, as in `sha256:2c26b46b`.
+pub fn fingerprint(secret: &str) -> String {
+    let digest = Sha256::digest(secret.as_bytes());
+    format!("sha256:{}", hex::encode(&digest[..4]))
+}
+
+/// The copy-paste artifacts that [`SecretString::sanitize`] found in a secret.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SecretIssues {
+    /// Whether whitespace around the secret was removed.
+    pub trimmed: bool,
+    /// The kinds of suspicious characters within the secret, in order.
+    pub suspicious: Vec<SuspiciousChar>,
+}
+
+/// A kind of character that is rarely part of a secret, but often pasted along with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SuspiciousChar {
+    /// A line feed or carriage return, e.g., from copying several lines.
+    LineBreak,
+    /// An invisible character, e.g., a zero-width space (U+200B) or a byte order mark (U+FEFF).
+    ZeroWidth,
+    /// A space that looks like an ASCII space, e.g., a no-break space (U+00A0).
+    NonBreakingSpace,
+}
+
+impl SuspiciousChar {
+    fn of(c: char) -> Option<Self> {
+        match c {
+            '\n' | '\r' => Some(Self::LineBreak),
+            '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{2060}' | '\u{FEFF}' => Some(Self::ZeroWidth),
+            '\u{00A0}' | '\u{2007}' | '\u{202F}' => Some(Self::NonBreakingSpace),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for SuspiciousChar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LineBreak => f.write_str("a line break"),
+            Self::ZeroWidth => f.write_str("a zero-width character"),
+            Self::NonBreakingSpace => f.write_str("a non-breaking space"),
+        }
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(secret: String) -> Self {
+        Self::new(secret)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(secret: &str) -> Self {
+        Self::new(secret.to_string())
+    }
+}
+
+impl Drop for SecretString {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl std::fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("****")
+    }
+}
+
+impl std::fmt::Display for SecretString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("****")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacted() {
+        let secret = SecretString::from("hunter2");
+        assert_eq!(format!("{secret:?}"), "****");
+        assert_eq!(secret.to_string(), "****");
+        assert_eq!(format!("{:?}", Some(&secret)), "Some(****)");
+        assert_eq!(secret.expose(), "hunter2");
+    }
+
+    #[test]
+    fn fingerprints() {
+        assert_eq!(SecretString::from("foo").fingerprint(), "sha256:2c26b46b");
+        assert_eq!(
+            SecretString::from("hunter2").fingerprint(),
+            "sha256:f52fbd32"
+        );
+    }
+
+    /// Sanitize the given secret, returning the result and the issues found.
+    fn sanitize(secret: &str) -> (String, bool, Vec<SuspiciousChar>) {
+        let (secret, issues) = SecretString::from(secret).sanitize();
+        (
+            secret.expose().to_string(),
+            issues.trimmed,
+            issues.suspicious,
+        )
+    }
+
+    #[test]
+    fn sanitize_clean() {
+        for secret in [
+            "hunter2",
+            "correct horse battery staple",
+            "pässwörd",
+            "tab\tinside",
+            "",
+        ] {
+            assert_eq!(
+                sanitize(secret),
+                (secret.to_string(), false, vec![]),
+                "{secret:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn sanitize_trims_ascii_whitespace() {
+        for secret in [
+            "hunter2\n",
+            "hunter2\r\n",
+            " hunter2",
+            "\thunter2 \n\n",
+            "\n\r hunter2\x0c",
+        ] {
+            assert_eq!(
+                sanitize(secret),
+                ("hunter2".to_string(), true, vec![]),
+                "{secret:?}"
+            );
+        }
+        // A secret of only whitespace is trimmed to nothing.
+        assert_eq!(sanitize(" \n"), (String::new(), true, vec![]));
+    }
+
+    #[test]
+    fn sanitize_reports_suspicious() {
+        for (secret, suspicious) in [
+            ("hunter\n2", vec![SuspiciousChar::LineBreak]),
+            ("hunter\r2", vec![SuspiciousChar::LineBreak]),
+            ("hunter2\u{200B}", vec![SuspiciousChar::ZeroWidth]),
+            ("\u{FEFF}hunter2", vec![SuspiciousChar::ZeroWidth]),
+            ("hun\u{200D}ter\u{2060}2", vec![SuspiciousChar::ZeroWidth]),
+            ("hunter\u{00A0}2", vec![SuspiciousChar::NonBreakingSpace]),
+            ("hunter\u{202F}2", vec![SuspiciousChar::NonBreakingSpace]),
+            (
+                "\u{00A0}line\none\u{200B}\nline two",
+                vec![
+                    SuspiciousChar::LineBreak,
+                    SuspiciousChar::ZeroWidth,
+                    SuspiciousChar::NonBreakingSpace,
+                ],
+            ),
+        ] {
+            assert_eq!(
+                sanitize(secret),
+                (secret.to_string(), false, suspicious),
+                "{secret:?}"
+            );
+        }
+
+        // Invisible characters aren't trimmed, but the ASCII whitespace outside them is.
+        assert_eq!(
+            sanitize(" \u{200B}hunter2\u{00A0}\n"),
+            (
+                "\u{200B}hunter2\u{00A0}".to_string(),
+                true,
+                vec![SuspiciousChar::ZeroWidth, SuspiciousChar::NonBreakingSpace]
+            )
+        );
+    }
+
+    #[test]
+    fn suspicious_char_display() {
+        assert_eq!(SuspiciousChar::LineBreak.to_string(), "a line break");
+        assert_eq!(
+            SuspiciousChar::ZeroWidth.to_string(),
+            "a zero-width character"
+        );
+        assert_eq!(
+            SuspiciousChar::NonBreakingSpace.to_string(),
+            "a non-breaking space"
+        );
+    }
+}
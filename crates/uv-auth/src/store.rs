@@ -0,0 +1,441 @@
+use std::fmt::Debug;
+
+use tracing::{debug, trace};
+
+use crate::{Credentials, KeyringError};
+
+/// A store of credentials, addressed by a service name and a username, e.g., a keyring backend.
+///
+/// Every [`KeyringProvider`](crate::KeyringProvider) is a store. Stores are composed with a
+/// [`ChainedStore`], e.g., to try the credential store of the operating system before the
+/// `keyring` command.
+#[async_trait::async_trait]
+pub trait CredentialStore: Debug + Send + Sync {
+    /// The kind of store, e.g., `subprocess`, for messages and tracing.
+    fn kind(&self) -> &'static str;
+
+    /// Fetch the credentials for the given service name and username, if any.
+    ///
+    /// Without a username, the store is asked for both the username and the password; stores that
+    /// can't be searched by service name return `None`.
+    async fn fetch(
+        &self,
+        service_name: &str,
+        username: Option<&str>,
+    ) -> Result<Option<Credentials>, KeyringError>;
+
+    /// Store a password for the given service name and username, replacing any existing entry.
+    async fn set(
+        &mut self,
+        service_name: &str,
+        username: &str,
+        password: &str,
+    ) -> Result<(), StoreError>;
+
+    /// Remove the entry for the given service name and username.
+    ///
+    /// Returns whether an entry was removed.
+    async fn unset(&mut self, service_name: &str, username: &str) -> Result<bool, StoreError>;
+}
+
+/// An error of a [`CredentialStore`] when storing or removing credentials.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum StoreError {
+    /// The store is read-only, e.g., a secrets engine that uv only reads from.
+    #[error("Storing and removing credentials is not supported by the {0} store")]
+    Unsupported(&'static str),
+    /// The store failed to store or remove the credentials, e.g., the `keyring` command exited
+    /// with an error, which was reported already.
+    #[error("The {0} store failed to store or remove the credentials")]
+    Failed(&'static str),
+}
+
+/// A chain of [`CredentialStore`]s, tried in order.
+///
+/// Credentials are fetched from the first store that has them, and stored in the first store that
+/// supports storing them. They're removed from every store that supports removing them, so that an
+/// entry in a later store doesn't take over.
+#[derive(Debug, Default)]
+pub struct ChainedStore {
+    stores: Vec<Box<dyn CredentialStore>>,
+}
+
+impl ChainedStore {
+    /// Create a chain of the given stores, in order of precedence.
+    pub fn new(stores: Vec<Box<dyn CredentialStore>>) -> Self {
+        Self { stores }
+    }
+
+    /// The kinds of the stores in the chain, in order.
+    pub fn kinds(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.stores.iter().map(|store| store.kind())
+    }
+}
+
+#[async_trait::async_trait]
+impl CredentialStore for ChainedStore {
+    fn kind(&self) -> &'static str {
+        "chained"
+    }
+
+    /// Fetch the credentials from the first store that has them.
+    ///
+    /// A store that fails is skipped; its error is only returned if no later store has the
+    /// credentials either.
+    async fn fetch(
+        &self,
+        service_name: &str,
+        username: Option<&str>,
+    ) -> Result<Option<Credentials>, KeyringError> {
+        let mut error = None;
+        for store in &self.stores {
+            match store.fetch(service_name, username).await {
+                Ok(Some(credentials)) => {
+                    trace!(
+                        "Found credentials for {service_name} in the {} store",
+                        store.kind()
+                    );
+                    return Ok(Some(credentials));
+                }
+                Ok(None) => {
+                    trace!(
+                        "No credentials for {service_name} in the {} store",
+                        store.kind()
+                    );
+                }
+                Err(err) => {
+                    debug!(
+                        "Failed to fetch credentials for {service_name} from the {} store: {err}",
+                        store.kind()
+                    );
+                    error.get_or_insert(err);
+                }
+            }
+        }
+        error.map_or(Ok(None), Err)
+    }
+
+    /// Store the password in the first store that supports storing it.
+    async fn set(
+        &mut self,
+        service_name: &str,
+        username: &str,
+        password: &str,
+    ) -> Result<(), StoreError> {
+        for store in &mut self.stores {
+            match store.set(service_name, username, password).await {
+                Err(StoreError::Unsupported(kind)) => {
+                    trace!("Skipping the {kind} store, which can't store credentials");
+                }
+                result => return result,
+            }
+        }
+        Err(StoreError::Unsupported(self.kind()))
+    }
+
+    /// Remove the entry from every store that supports removing it.
+    async fn unset(&mut self, service_name: &str, username: &str) -> Result<bool, StoreError> {
+        let mut removed = None;
+        let mut error = None;
+        for store in &mut self.stores {
+            match store.unset(service_name, username).await {
+                Ok(found) => removed = Some(removed.unwrap_or(false) || found),
+                Err(StoreError::Unsupported(kind)) => {
+                    trace!("Skipping the {kind} store, which can't remove credentials");
+                }
+                Err(err) => {
+                    error.get_or_insert(err);
+                }
+            }
+        }
+        match (removed, error) {
+            (Some(removed), _) => Ok(removed),
+            (None, Some(err)) => Err(err),
+            (None, None) => Err(StoreError::Unsupported(self.kind())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use url::Url;
+
+    use super::*;
+    use crate::{HostPolicy, KeyringProvider, SecretString};
+
+    /// An in-memory store, which may be read-only or fail every lookup.
+    #[derive(Debug, Default)]
+    struct TestStore {
+        kind: &'static str,
+        entries: HashMap<(String, String), String>,
+        read_only: bool,
+        failing: bool,
+    }
+
+    impl TestStore {
+        fn new(kind: &'static str, entries: &[(&str, &str, &str)]) -> Self {
+            Self {
+                kind,
+                entries: entries
+                    .iter()
+                    .map(|(service, username, password)| {
+                        (
+                            (service.to_string(), username.to_string()),
+                            password.to_string(),
+                        )
+                    })
+                    .collect(),
+                ..Self::default()
+            }
+        }
+
+        fn read_only(mut self) -> Self {
+            self.read_only = true;
+            self
+        }
+
+        fn failing(mut self) -> Self {
+            self.failing = true;
+            self
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl CredentialStore for TestStore {
+        fn kind(&self) -> &'static str {
+            self.kind
+        }
+
+        async fn fetch(
+            &self,
+            service_name: &str,
+            username: Option<&str>,
+        ) -> Result<Option<Credentials>, KeyringError> {
+            if self.failing {
+                return Err(KeyringError::BackendError(format!("{} failed", self.kind)));
+            }
+            Ok(self
+                .entries
+                .iter()
+                .find(|((service, user), _)| {
+                    service == service_name && username.is_none_or(|username| username == user)
+                })
+                .map(|((_, username), password)| {
+                    Credentials::new(Some(username.clone()), Some(password.clone()))
+                }))
+        }
+
+        async fn set(
+            &mut self,
+            service_name: &str,
+            username: &str,
+            password: &str,
+        ) -> Result<(), StoreError> {
+            if self.read_only {
+                return Err(StoreError::Unsupported(self.kind));
+            }
+            self.entries.insert(
+                (service_name.to_string(), username.to_string()),
+                password.to_string(),
+            );
+            Ok(())
+        }
+
+        async fn unset(&mut self, service_name: &str, username: &str) -> Result<bool, StoreError> {
+            if self.read_only {
+                return Err(StoreError::Unsupported(self.kind));
+            }
+            Ok(self
+                .entries
+                .remove(&(service_name.to_string(), username.to_string()))
+                .is_some())
+        }
+    }
+
+    /// The password fetched from the store, if any.
+    async fn password(
+        store: &dyn CredentialStore,
+        service_name: &str,
+        username: Option<&str>,
+    ) -> Option<String> {
+        let credentials = store.fetch(service_name, username).await.unwrap()?;
+        Some(credentials.password()?.expose().to_string())
+    }
+
+    /// The first store with the credentials is used.
+    #[tokio::test]
+    async fn fetch_in_order() {
+        let chain = ChainedStore::new(vec![
+            Box::new(TestStore::new(
+                "first",
+                &[("https://example.com", "user", "first")],
+            )),
+            Box::new(TestStore::new("broken", &[]).failing()),
+            Box::new(TestStore::new(
+                "second",
+                &[
+                    ("https://example.com", "user", "second"),
+                    ("https://example.com", "other", "second-other"),
+                ],
+            )),
+        ]);
+        assert_eq!(
+            chain.kinds().collect::<Vec<_>>(),
+            ["first", "broken", "second"]
+        );
+
+        assert_eq!(
+            password(&chain, "https://example.com", Some("user"))
+                .await
+                .as_deref(),
+            Some("first")
+        );
+        // The failing store is skipped, since a later store has the credentials.
+        assert_eq!(
+            password(&chain, "https://example.com", Some("other"))
+                .await
+                .as_deref(),
+            Some("second-other")
+        );
+        // Without a username, the first store with an entry for the service is used.
+        assert_eq!(
+            password(&chain, "https://example.com", None)
+                .await
+                .as_deref(),
+            Some("first")
+        );
+    }
+
+    /// Without credentials in any store, nothing is found, unless a store failed.
+    #[tokio::test]
+    async fn fetch_all_miss() {
+        let chain = ChainedStore::new(vec![
+            Box::new(TestStore::new(
+                "first",
+                &[("https://other.example.com", "user", "first")],
+            )),
+            Box::new(TestStore::new("second", &[])),
+        ]);
+        assert_eq!(
+            chain
+                .fetch("https://example.com", Some("user"))
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            chain.fetch("https://example.com", None).await.unwrap(),
+            None
+        );
+
+        let chain = ChainedStore::default();
+        assert_eq!(
+            chain
+                .fetch("https://example.com", Some("user"))
+                .await
+                .unwrap(),
+            None
+        );
+
+        let chain = ChainedStore::new(vec![
+            Box::new(TestStore::new("first", &[])),
+            Box::new(TestStore::new("broken", &[]).failing()),
+        ]);
+        assert_eq!(
+            chain
+                .fetch("https://example.com", Some("user"))
+                .await
+                .unwrap_err(),
+            KeyringError::BackendError("broken failed".to_string())
+        );
+    }
+
+    /// Credentials are stored in the first writable store, and removed from every writable store.
+    #[tokio::test]
+    async fn write_routing() {
+        let mut chain = ChainedStore::new(vec![
+            Box::new(
+                TestStore::new("read-only", &[("https://example.com", "user", "read-only")])
+                    .read_only(),
+            ),
+            Box::new(TestStore::new("first", &[])),
+            Box::new(TestStore::new(
+                "second",
+                &[("https://example.com", "user", "second")],
+            )),
+        ]);
+
+        chain
+            .set("https://example.com", "other", "stored")
+            .await
+            .unwrap();
+        assert_eq!(
+            password(&*chain.stores[1], "https://example.com", Some("other"))
+                .await
+                .as_deref(),
+            Some("stored")
+        );
+        assert_eq!(
+            password(&*chain.stores[2], "https://example.com", Some("other")).await,
+            None
+        );
+
+        // The entries of the read-only store are kept.
+        assert!(chain.unset("https://example.com", "user").await.unwrap());
+        assert_eq!(
+            password(&*chain.stores[2], "https://example.com", Some("user")).await,
+            None
+        );
+        assert_eq!(
+            password(&chain, "https://example.com", Some("user"))
+                .await
+                .as_deref(),
+            Some("read-only")
+        );
+        assert!(!chain.unset("https://example.com", "user").await.unwrap());
+
+        // Without a writable store, nothing can be stored.
+        let mut chain =
+            ChainedStore::new(vec![Box::new(TestStore::new("read-only", &[]).read_only())]);
+        assert_eq!(
+            chain.set("https://example.com", "user", "password").await,
+            Err(StoreError::Unsupported("chained"))
+        );
+        assert_eq!(
+            chain.unset("https://example.com", "user").await,
+            Err(StoreError::Unsupported("chained"))
+        );
+    }
+
+    /// A provider wraps a chain, and providers can be chained.
+    #[tokio::test]
+    async fn provider_chain() {
+        let url = Url::parse("https://example.com/simple").unwrap();
+        let mut provider = KeyringProvider::chained(ChainedStore::new(vec![
+            Box::new(
+                TestStore::new("read-only", &[("https://example.com", "user", "read-only")])
+                    .read_only(),
+            ),
+            Box::new(KeyringProvider::empty().uncached()),
+        ]))
+        .with_host_policy(HostPolicy::default())
+        .with_compat(Vec::new());
+
+        let credentials = provider.fetch(&url, "user").await.unwrap();
+        assert_eq!(
+            credentials.password().map(SecretString::expose),
+            Some("read-only")
+        );
+
+        assert!(provider.set(&url, "other", "stored").await);
+        let credentials = provider.fetch(&url, "other").await.unwrap();
+        assert_eq!(
+            credentials.password().map(SecretString::expose),
+            Some("stored")
+        );
+        assert!(provider.unset(&url, "other").await);
+        assert_eq!(provider.fetch(&url, "other").await, None);
+    }
+}
@@ -0,0 +1,111 @@
+//! Helpers for the tests of the crate.
+
+use std::sync::{Arc, Mutex};
+
+use http::StatusCode;
+use reqwest::{Client, Request, Response};
+
+use crate::middleware::Transport;
+use crate::{AuthMiddleware, Credentials};
+
+/// A [`Transport`] that serves requests in memory, recording the credentials sent with each.
+pub(crate) struct FakeTransport {
+    /// The credentials required by the server, or [`None`] if the server is public.
+    required: Option<Credentials>,
+    /// The status returned for requests without the required credentials.
+    challenge: StatusCode,
+    /// The credentials sent with each request, in order.
+    pub(crate) sent: Vec<Option<Credentials>>,
+    /// The `Authorization` header sent with each request, in order, e.g., for bearer tokens, which
+    /// aren't parsed into [`Credentials`].
+    pub(crate) authorization: Vec<Option<String>>,
+}
+
+impl FakeTransport {
+    /// A server that requires the given credentials, and responds with a 401 otherwise.
+    pub(crate) fn protected(username: &str, password: &str) -> Self {
+        Self {
+            required: Some(Credentials::new(
+                Some(username.to_string()),
+                Some(password.to_string()),
+            )),
+            challenge: StatusCode::UNAUTHORIZED,
+            sent: Vec::new(),
+            authorization: Vec::new(),
+        }
+    }
+
+    /// A server that does not require credentials.
+    pub(crate) fn public() -> Self {
+        Self {
+            required: None,
+            challenge: StatusCode::UNAUTHORIZED,
+            sent: Vec::new(),
+            authorization: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub(crate) fn with_challenge(mut self, challenge: StatusCode) -> Self {
+        self.challenge = challenge;
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl Transport for FakeTransport {
+    async fn send(&mut self, request: Request) -> reqwest_middleware::Result<Response> {
+        let header = request.headers().get(reqwest::header::AUTHORIZATION);
+        let credentials = header.and_then(Credentials::from_header_value);
+        let status = match self.required {
+            Some(ref required) if credentials.as_ref() != Some(required) => self.challenge,
+            _ => StatusCode::OK,
+        };
+        self.sent.push(credentials);
+        self.authorization.push(
+            header
+                .and_then(|header| header.to_str().ok())
+                .map(ToString::to_string),
+        );
+        Ok(Response::from(
+            http::Response::builder()
+                .status(status)
+                .body(Vec::new())
+                .unwrap(),
+        ))
+    }
+}
+
+/// Perform a `GET` request for the URL through the middleware, without a network.
+pub(crate) async fn fake_get(
+    middleware: &AuthMiddleware,
+    transport: &mut FakeTransport,
+    url: &str,
+) -> reqwest_middleware::Result<Response> {
+    // Build the request with `reqwest` so that credentials in the URL are moved to the
+    // headers, as they are for real requests.
+    let request = Client::new().get(url).build()?;
+    middleware.authenticate(request, transport).await
+}
+
+/// A writer that appends to a shared buffer, for capturing tracing output.
+#[derive(Clone, Default)]
+pub(crate) struct SharedWriter(Arc<Mutex<Vec<u8>>>);
+
+impl SharedWriter {
+    /// The output written so far.
+    pub(crate) fn output(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+impl std::io::Write for SharedWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,245 @@
+use reqwest::StatusCode;
+use serde::Deserialize;
+use url::Url;
+
+use crate::credentials::Credentials;
+use crate::keyring::KeyringError;
+
+/// The default mount of the KV v2 secrets engine.
+const DEFAULT_MOUNT: &str = "secret";
+
+/// The default path of the secret for a keyring service name, within the mount.
+const DEFAULT_PATH_TEMPLATE: &str = "uv/{service}";
+
+/// A read-only store of credentials in the KV v2 secrets engine of Vault.
+///
+/// Each keyring entry is a secret with `username` and `password` keys, at a path derived from the
+/// service name, see [`VaultStore::secret_path`].
+#[derive(Clone)]
+pub(crate) struct VaultStore {
+    client: reqwest::Client,
+    /// The address of the Vault server, e.g., `https://vault.example.com:8200`.
+    addr: Url,
+    token: String,
+    mount: String,
+    /// The path of the secret for an entry, with `{service}` and `{username}` placeholders.
+    path_template: String,
+}
+
+impl std::fmt::Debug for VaultStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Omit the token.
+        f.debug_struct("VaultStore")
+            .field("addr", &self.addr.as_str())
+            .field("mount", &self.mount)
+            .field("path_template", &self.path_template)
+            .finish_non_exhaustive()
+    }
+}
+
+impl VaultStore {
+    pub(crate) fn new(addr: Url, token: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            addr,
+            token,
+            mount: DEFAULT_MOUNT.to_string(),
+            path_template: DEFAULT_PATH_TEMPLATE.to_string(),
+        }
+    }
+
+    /// Read the server from `VAULT_ADDR` and the token from `VAULT_TOKEN`, and the mount and path
+    /// template from `UV_VAULT_MOUNT` and `UV_VAULT_PATH_TEMPLATE`.
+    ///
+    /// Returns [`None`] if `VAULT_ADDR` or `VAULT_TOKEN` is missing or invalid.
+    #[cfg(feature = "vault")]
+    pub(crate) fn from_env() -> Option<Self> {
+        use tracing::warn;
+        use uv_static::EnvVars;
+
+        let non_empty = |name| std::env::var(name).ok().filter(|value| !value.is_empty());
+        let addr = non_empty(EnvVars::VAULT_ADDR)?;
+        let addr = Url::parse(&addr)
+            .inspect_err(|err| warn!("Invalid `VAULT_ADDR` `{addr}`: {err}"))
+            .ok()?;
+        let mut store = Self::new(addr, non_empty(EnvVars::VAULT_TOKEN)?);
+        if let Some(mount) = non_empty(EnvVars::UV_VAULT_MOUNT) {
+            store.mount = mount;
+        }
+        if let Some(path_template) = non_empty(EnvVars::UV_VAULT_PATH_TEMPLATE) {
+            store.path_template = path_template;
+        }
+        Some(store)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_layout(mut self, mount: &str, path_template: &str) -> Self {
+        self.mount = mount.to_string();
+        self.path_template = path_template.to_string();
+        self
+    }
+
+    /// The path of the secret for the given service name and username, within the mount.
+    ///
+    /// In the template, `{service}` is replaced by the service name with the `://` after the
+    /// scheme replaced by `/` and without a trailing slash, e.g., `https/example.com/simple` for
+    /// `https://example.com/simple/`. Service names without a scheme, which previous versions of
+    /// uv used for `https`, are prefixed with `https/`.
+    ///
+    /// Returns [`None`] if the template refers to the username, but none is known.
+    pub(crate) fn secret_path(&self, service_name: &str, username: Option<&str>) -> Option<String> {
+        let service = match service_name.split_once("://") {
+            Some((scheme, rest)) => format!("{scheme}/{rest}"),
+            None => format!("https/{service_name}"),
+        };
+        let path = self
+            .path_template
+            .replace("{service}", service.trim_end_matches('/'));
+        if path.contains("{username}") {
+            Some(path.replace("{username}", username?))
+        } else {
+            Some(path)
+        }
+    }
+
+    /// The URL to read the secret at the given path from, with the KV v2 API.
+    fn secret_url(&self, path: &str) -> Url {
+        let mut url = self.addr.clone();
+        url.path_segments_mut()
+            .expect("`VAULT_ADDR` should be a base URL")
+            .pop_if_empty()
+            .push("v1")
+            .extend(self.mount.split('/').filter(|segment| !segment.is_empty()))
+            .push("data")
+            .extend(path.split('/').filter(|segment| !segment.is_empty()));
+        url
+    }
+
+    /// Read the credentials for the given service name.
+    ///
+    /// Returns [`None`] if there is no secret at the path for the service name, or if it has no
+    /// password.
+    pub(crate) async fn read(
+        &self,
+        service_name: &str,
+        username: Option<&str>,
+    ) -> Result<Option<Credentials>, KeyringError> {
+        let Some(path) = self.secret_path(service_name, username) else {
+            return Ok(None);
+        };
+        let response = self
+            .client
+            .get(self.secret_url(&path))
+            .header("X-Vault-Token", &self.token)
+            .send()
+            .await
+            .map_err(|err| KeyringError::BackendError(format!("Failed to reach Vault: {err}")))?;
+
+        match response.status() {
+            StatusCode::OK => {}
+            StatusCode::NOT_FOUND => return Ok(None),
+            StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED => {
+                return Err(KeyringError::VaultPermissionDenied(path));
+            }
+            status => {
+                return Err(KeyringError::BackendError(format!(
+                    "Vault returned {status} for the secret at `{path}`"
+                )));
+            }
+        }
+
+        let secret: KvResponse = response.json().await.map_err(|err| {
+            KeyringError::MalformedCredentials(format!(
+                "Vault returned an invalid response for the secret at `{path}`: {err}"
+            ))
+        })?;
+        let KvSecret {
+            username: found,
+            password,
+        } = secret.data.data;
+        let Some(password) = password else {
+            return Ok(None);
+        };
+        Ok(Some(Credentials::new(found, Some(password))))
+    }
+}
+
+/// The response of the KV v2 API to a read, with the secret in `data.data`.
+#[derive(Deserialize)]
+struct KvResponse {
+    data: KvData,
+}
+
+#[derive(Deserialize)]
+struct KvData {
+    data: KvSecret,
+}
+
+#[derive(Deserialize)]
+struct KvSecret {
+    #[serde(default)]
+    username: Option<String>,
+    #[serde(default)]
+    password: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> VaultStore {
+        VaultStore::new(
+            Url::parse("https://vault.example.com:8200").unwrap(),
+            "token".to_string(),
+        )
+    }
+
+    #[test]
+    fn secret_path() {
+        let store = store();
+        assert_eq!(
+            store
+                .secret_path("https://example.com/simple/", Some("user"))
+                .as_deref(),
+            Some("uv/https/example.com/simple")
+        );
+        assert_eq!(
+            store.secret_path("http://localhost:8080", None).as_deref(),
+            Some("uv/http/localhost:8080")
+        );
+        // Service names of previous versions of uv are only used for `https`.
+        assert_eq!(
+            store.secret_path("example.com", None).as_deref(),
+            Some("uv/https/example.com")
+        );
+
+        let store = store.with_layout("kv", "registries/{service}/{username}");
+        assert_eq!(
+            store
+                .secret_path("https://example.com", Some("ci"))
+                .as_deref(),
+            Some("registries/https/example.com/ci")
+        );
+        assert_eq!(store.secret_path("https://example.com", None), None);
+    }
+
+    #[test]
+    fn secret_url() {
+        let store = store().with_layout("teams/kv", "uv/{service}");
+        assert_eq!(
+            store
+                .secret_url("uv/https/example.com:8080/simple")
+                .as_str(),
+            "https://vault.example.com:8200/v1/teams/kv/data/uv/https/example.com:8080/simple"
+        );
+    }
+
+    #[test]
+    fn debug_omits_token() {
+        let store = VaultStore::new(
+            Url::parse("https://vault.example.com").unwrap(),
+            "hvs.secret".to_string(),
+        );
+        assert!(!format!("{store:?}").contains("hvs.secret"));
+    }
+}
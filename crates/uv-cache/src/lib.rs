@@ -38,6 +38,10 @@ mod wheel;
 /// Must be kept in-sync with the version in [`CacheBucket::to_str`].
 pub const ARCHIVE_VERSION: u8 = 0;
 
+/// The name of the log at the root of the cache that records which credentials fetched its
+/// entries, see [`Cache::provenance_log`].
+const PROVENANCE_LOG: &str = "provenance.jsonl";
+
 /// A [`CacheEntry`] which may or may not exist yet.
 #[derive(Debug, Clone)]
 pub struct CacheEntry(PathBuf);
@@ -182,6 +186,12 @@ impl Cache {
         &self.root
     }
 
+    /// Return the path of the log that records which credentials fetched the entries of the
+    /// cache, if enabled with `UV_AUTH_PROVENANCE`.
+    pub fn provenance_log(&self) -> PathBuf {
+        self.root.join(PROVENANCE_LOG)
+    }
+
     /// Return the [`Refresh`] policy for the cache.
     pub fn refresh(&self) -> &Refresh {
         &self.refresh
@@ -399,6 +409,7 @@ impl Cache {
             if entry.file_name() == "CACHEDIR.TAG"
                 || entry.file_name() == ".gitignore"
                 || entry.file_name() == ".git"
+                || entry.file_name() == PROVENANCE_LOG
             {
                 continue;
             }
@@ -44,6 +44,40 @@ pub enum PythonListFormat {
     Json,
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum IndexCredentialsFormat {
+    /// Plain text (for humans).
+    #[default]
+    Text,
+    /// JSON (for computers).
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum CredentialHelperKind {
+    /// Request authorization tokens for AWS CodeArtifact with the AWS CLI.
+    AwsCodeartifact,
+    /// Request OAuth access tokens for Google Artifact Registry with `gcloud`.
+    Gcloud,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum IndexAuthScheme {
+    /// Send a username and password with HTTP Basic Authentication.
+    Basic,
+    /// Send a token in an `Authorization: Bearer` header.
+    Bearer,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum IndexCredentialsScope {
+    /// Store the credentials for the host of the index, to use them for all indexes on the host.
+    Host,
+    /// Store the credentials for the URL of the index, to use them for the index and the URLs
+    /// below it only.
+    Url,
+}
+
 #[derive(Debug, Default, Clone, clap::ValueEnum)]
 pub enum ListFormat {
     /// Display the list of packages in a human-readable table.
@@ -491,6 +525,8 @@ pub enum Commands {
     Help(HelpArgs),
     /// Manage uv's indexes
     Index(IndexNamespace),
+    /// Log in to and out of the indexes configured in your pyproject.toml
+    Auth(AuthNamespace),
 }
 
 #[derive(Args, Debug)]
@@ -580,6 +616,14 @@ pub struct PruneArgs {
     /// that were built from source.
     #[arg(long)]
     pub ci: bool,
+
+    /// Remove the cache entries that were fetched with the credentials of the given fingerprint,
+    /// e.g., after the credentials were compromised.
+    ///
+    /// The credentials are only recorded for the entries that were fetched with
+    /// `UV_AUTH_PROVENANCE=1`. Use `uv auth provenance` to show their fingerprints.
+    #[arg(long, value_name = "FINGERPRINT")]
+    pub credential: Option<String>,
 }
 
 #[derive(Args)]
@@ -1828,8 +1872,9 @@ pub struct PipUninstallArgs {
 
     /// Attempt to use `keyring` for authentication for remote requirements files.
     ///
-    /// At present, only `--keyring-provider subprocess` is supported, which configures uv to use
-    /// the `keyring` CLI to handle authentication.
+    /// `--keyring-provider subprocess` configures uv to use the `keyring` CLI to handle
+    /// authentication, and `--keyring-provider native` configures uv to use the credential store of
+    /// the operating system directly (if uv was built with the `native-keyring` feature).
     ///
     /// Defaults to `disabled`.
     #[arg(long, value_enum, env = EnvVars::UV_KEYRING_PROVIDER)]
@@ -2434,8 +2479,9 @@ pub struct VenvArgs {
 
     /// Attempt to use `keyring` for authentication for index URLs.
     ///
-    /// At present, only `--keyring-provider subprocess` is supported, which configures uv to use
-    /// the `keyring` CLI to handle authentication.
+    /// `--keyring-provider subprocess` configures uv to use the `keyring` CLI to handle
+    /// authentication, and `--keyring-provider native` configures uv to use the credential store of
+    /// the operating system directly (if uv was built with the `native-keyring` feature).
     ///
     /// Defaults to `disabled`.
     #[arg(long, value_enum, env = EnvVars::UV_KEYRING_PROVIDER)]
@@ -4203,8 +4249,9 @@ pub struct ToolUpgradeArgs {
 
     /// Attempt to use `keyring` for authentication for index URLs.
     ///
-    /// At present, only `--keyring-provider subprocess` is supported, which configures uv to use
-    /// the `keyring` CLI to handle authentication.
+    /// `--keyring-provider subprocess` configures uv to use the `keyring` CLI to handle
+    /// authentication, and `--keyring-provider native` configures uv to use the credential store of
+    /// the operating system directly (if uv was built with the `native-keyring` feature).
     ///
     /// Defaults to `disabled`.
     #[arg(
@@ -4885,8 +4932,9 @@ pub struct InstallerArgs {
 
     /// Attempt to use `keyring` for authentication for index URLs.
     ///
-    /// At present, only `--keyring-provider subprocess` is supported, which configures uv to use
-    /// the `keyring` CLI to handle authentication.
+    /// `--keyring-provider subprocess` configures uv to use the `keyring` CLI to handle
+    /// authentication, and `--keyring-provider native` configures uv to use the credential store of
+    /// the operating system directly (if uv was built with the `native-keyring` feature).
     ///
     /// Defaults to `disabled`.
     #[arg(
@@ -5027,8 +5075,9 @@ pub struct ResolverArgs {
 
     /// Attempt to use `keyring` for authentication for index URLs.
     ///
-    /// At present, only `--keyring-provider subprocess` is supported, which configures uv to use
-    /// the `keyring` CLI to handle authentication.
+    /// `--keyring-provider subprocess` configures uv to use the `keyring` CLI to handle
+    /// authentication, and `--keyring-provider native` configures uv to use the credential store of
+    /// the operating system directly (if uv was built with the `native-keyring` feature).
     ///
     /// Defaults to `disabled`.
     #[arg(
@@ -5217,8 +5266,9 @@ pub struct ResolverInstallerArgs {
 
     /// Attempt to use `keyring` for authentication for index URLs.
     ///
-    /// At present, only `--keyring-provider subprocess` is supported, which configures uv to use
-    /// the `keyring` CLI to handle authentication.
+    /// `--keyring-provider subprocess` configures uv to use the `keyring` CLI to handle
+    /// authentication, and `--keyring-provider native` configures uv to use the credential store of
+    /// the operating system directly (if uv was built with the `native-keyring` feature).
     ///
     /// Defaults to `disabled`.
     #[arg(
@@ -5388,8 +5438,9 @@ pub struct FetchArgs {
 
     /// Attempt to use `keyring` for authentication for index URLs.
     ///
-    /// At present, only `--keyring-provider subprocess` is supported, which configures uv to use
-    /// the `keyring` CLI to handle authentication.
+    /// `--keyring-provider subprocess` configures uv to use the `keyring` CLI to handle
+    /// authentication, and `--keyring-provider native` configures uv to use the credential store of
+    /// the operating system directly (if uv was built with the `native-keyring` feature).
     ///
     /// Defaults to `disabled`.
     #[arg(
@@ -5506,8 +5557,9 @@ pub struct PublishArgs {
 
     /// Attempt to use `keyring` for authentication for remote requirements files.
     ///
-    /// At present, only `--keyring-provider subprocess` is supported, which configures uv to use
-    /// the `keyring` CLI to handle authentication.
+    /// `--keyring-provider subprocess` configures uv to use the `keyring` CLI to handle
+    /// authentication, and `--keyring-provider native` configures uv to use the credential store of
+    /// the operating system directly (if uv was built with the `native-keyring` feature).
     ///
     /// Defaults to `disabled`.
     #[arg(long, value_enum, env = EnvVars::UV_KEYRING_PROVIDER)]
@@ -5577,42 +5629,46 @@ pub enum BuildBackendCommand {
 pub struct IndexNamespace {
     #[command(subcommand)]
     pub command: IndexCommand,
+
+    /// The path to the auth config, which records the usernames and settings of the credentials
+    /// for each index.
+    ///
+    /// Defaults to `auth.toml` in the uv state directory, e.g., `$XDG_DATA_HOME/uv` or
+    /// `$HOME/.local/share/uv` on macOS and Linux. The parent directories are created as needed.
+    #[arg(global = true, long, env = EnvVars::UV_AUTH_CONFIG_FILE, value_name = "PATH")]
+    pub auth_config: Option<PathBuf>,
 }
 
 #[derive(Subcommand)]
 pub enum IndexCommand {
-    // /// Set a new index. This will be added to your pyproject.toml
-    // #[command(
-    //     after_help = "Use `uv help index add` for more details.",
-    //     after_long_help = ""
-    // )]
-    // Set(IndexSourceArgs),
-    // /// List all indexes set in your pyproject.toml
-    // #[command(
-    //     after_help = "Use `uv help index list` for more details.",
-    //     after_long_help = ""
-    // )]
-    // List(IndexSourceArgs),
-    // /// Unset an existing index. This will be removed from your pyproject.toml
-    // #[command(
-    //     after_help = "Use `uv help index delete` for more details.",
-    //     after_long_help = ""
-    // )]
-    // Unset(IndexSourceArgs),
     /// Manage credentials for the indexes configured in your pyproject.toml
     #[command(subcommand)]
     Credentials(IndexCredentialsCommand),
+    /// Print the names of the indexes configured in your pyproject.toml, for shell completion
+    #[command(hide = true)]
+    Names,
 }
 
 #[derive(Subcommand)]
 pub enum IndexCredentialsCommand {
     /// Set credentials for an index
+    ///
+    /// Without `--name`, `--url`, or `--proxy`, the index is selected from the configured named
+    /// indexes: a single index is used directly, and with several, you are prompted to select
+    /// one if the terminal is interactive.
     #[command(
         after_help = "Use `uv help index credentials set` for more details.",
         after_long_help = ""
     )]
     Set(IndexSetCredentialsArgs),
 
+    /// Replace the password for an index, keeping its username
+    #[command(
+        after_help = "Use `uv help index credentials rotate` for more details.",
+        after_long_help = ""
+    )]
+    Rotate(IndexRotateCredentialsArgs),
+
     /// List credentials for each index (Only username is shown).
     #[command(
         after_help = "Use `uv help index credentials list` for more details.",
@@ -5626,21 +5682,94 @@ pub enum IndexCredentialsCommand {
         after_long_help = ""
     )]
     Unset(IndexUnsetCredentialsArgs),
+
+    /// Check that the stored credentials are accepted by each index
+    #[command(
+        after_help = "Use `uv help index credentials check` for more details.",
+        after_long_help = ""
+    )]
+    Check(IndexCheckCredentialsArgs),
+
+    /// Remove credentials whose index is no longer configured
+    #[command(
+        after_help = "Use `uv help index credentials prune` for more details.",
+        after_long_help = ""
+    )]
+    Prune(IndexPruneCredentialsArgs),
+
+    /// Write the auth config entries as JSON, without passwords
+    #[command(
+        after_help = "Use `uv help index credentials export` for more details.",
+        after_long_help = ""
+    )]
+    Export,
+
+    /// Import the auth config entries written by `uv index credentials export`
+    #[command(
+        after_help = "Use `uv help index credentials import` for more details.",
+        after_long_help = ""
+    )]
+    Import(IndexImportCredentialsArgs),
 }
 
+/// Arguments for selecting an index by name, or a URL.
+///
+/// Commands require one of them through the `selector` group, which `uv index credentials unset`
+/// extends with `--all`. `uv index credentials set` doesn't require one, and selects from the
+/// configured indexes instead.
 #[derive(Args)]
-pub struct IndexSourceArgs {
+pub struct IndexSelectorArgs {
     /// The name of the index
+    #[arg(long, conflicts_with = "url")]
+    pub name: Option<String>,
+
+    /// The URL to use the credentials for, instead of a named index.
+    ///
+    /// Use this for direct URL dependencies and `--find-links` locations that aren't declared as
+    /// indexes. The URL must not contain a password. The password is stored in the keyring for
+    /// the host of the URL.
     #[arg(long)]
-    pub name: String,
+    pub url: Option<String>,
+
+    /// The URL of the default package index, which the reserved name `pypi` refers to (by
+    /// default: <https://pypi.org/simple>).
+    ///
+    /// Use this to manage the credentials of a private mirror of PyPI without declaring it as a
+    /// named index.
+    #[arg(long, env = EnvVars::UV_DEFAULT_INDEX, value_parser = parse_default_index)]
+    pub default_index: Option<Maybe<Index>>,
 }
 
+/// Arguments for accessing the keyring, shared by the `uv index credentials` subcommands.
 #[derive(Args)]
-pub struct IndexSetCredentialsArgs {
-    /// The name of the index
-    #[arg(long)]
-    pub name: String,
+pub struct KeyringArgs {
+    /// Attempt to use `keyring` for authentication for remote requirements files.
+    ///
+    /// `--keyring-provider subprocess` configures uv to use the `keyring` CLI to handle
+    /// authentication, and `--keyring-provider native` configures uv to use the credential store of
+    /// the operating system directly (if uv was built with the `native-keyring` feature).
+    ///
+    /// The provider used to set the credentials for an index is recorded, and used for the index
+    /// unless `--keyring-provider` is provided.
+    ///
+    /// Defaults to `disabled`.
+    #[arg(long, value_enum, env = EnvVars::UV_KEYRING_PROVIDER)]
+    pub keyring_provider: Option<KeyringProviderType>,
+
+    /// The maximum number of seconds to wait for the `keyring` command to respond.
+    ///
+    /// If the command does not respond in time, e.g., because it is waiting for the keychain to be
+    /// unlocked, it is terminated.
+    ///
+    /// Defaults to 15 seconds.
+    #[arg(long, value_name = "SECONDS")]
+    pub keyring_timeout: Option<u64>,
+}
 
+/// Arguments for providing the credentials for an index, shared by `uv index credentials set`
+/// and `uv auth login`.
+#[derive(Args)]
+pub struct CredentialsArgs {
     /// The username that should be used for the index
     #[arg(long, required(false))]
     pub username: Option<String>,
@@ -5649,44 +5778,817 @@ pub struct IndexSetCredentialsArgs {
     #[arg(long, required(false))]
     pub password: Option<String>,
 
-    /// Attempt to use `keyring` for authentication for remote requirements files.
+    /// Read the password for the index from stdin.
     ///
-    /// At present, only `--keyring-provider subprocess` is supported, which configures uv to
-    /// use the `keyring` CLI to handle authentication.
+    /// The entire input is used as the password, excluding a single trailing newline.
+    #[arg(long, conflicts_with = "password", conflicts_with = "token")]
+    pub password_stdin: bool,
+
+    /// The API token that should be used for the index.
     ///
-    /// Defaults to `disabled`.
-    #[arg(long, value_enum, env = EnvVars::UV_KEYRING_PROVIDER)]
-    pub keyring_provider: Option<KeyringProviderType>,
+    /// Using a token is equivalent to passing `__token__` as `--username` and the token as
+    /// `--password`. If `--token` is passed without a value, the token is read from user input.
+    #[arg(
+        long,
+        num_args = 0..=1,
+        conflicts_with = "username",
+        conflicts_with = "password"
+    )]
+    pub token: Option<Option<String>>,
+}
+
+/// Arguments for selecting the output format, shared by the `uv index credentials` and `uv auth`
+/// subcommands that report a result.
+#[derive(Args)]
+pub struct OutputFormatArgs {
+    /// Select the output format.
+    ///
+    /// With `json`, the result is written to stdout as JSON, e.g., the index, URL, username, and
+    /// keyring service of the credentials, and if the command fails, an object with an
+    /// `error_code`. Prompts and other messages are written to stderr. Passwords are never
+    /// included, unless requested with `--show-password`.
+    #[arg(long, alias = "format", value_enum, default_value_t = IndexCredentialsFormat::default())]
+    pub output_format: IndexCredentialsFormat,
+}
+
+/// Arguments for confirming changes, shared by the `uv index credentials` and `uv auth`
+/// subcommands that change stored credentials.
+#[derive(Args)]
+pub struct ConfirmationArgs {
+    /// Print what would be changed, without changing it.
+    ///
+    /// The index and the username are resolved as usual, but no password is prompted for, and
+    /// neither the keyring nor the auth config is changed.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Make the changes without asking for confirmation.
+    ///
+    /// Without this flag, uv asks for confirmation before replacing or removing credentials, or
+    /// storing a password with characters that were likely copied along with it, e.g., a
+    /// zero-width space, and fails if it cannot prompt, e.g., in a non-interactive session.
+    /// Commands that never ask, e.g., `uv index credentials unset`, accept the flag as well.
+    #[arg(long, alias = "force", conflicts_with = "dry_run")]
+    pub yes: bool,
+}
+
+/// Arguments for configuring a credential helper for an index, in place of stored credentials.
+#[derive(Args)]
+pub struct CredentialHelperArgs {
+    /// Issue short-lived credentials for the index with a credential helper, instead of storing a
+    /// password in the keyring.
+    ///
+    /// With `aws-codeartifact`, uv runs `aws codeartifact get-authorization-token` for the
+    /// `--domain` and `--domain-owner` when the index requires authentication, and authenticates
+    /// with the username `aws` and the token. The token is kept in memory until it expires.
+    ///
+    /// With `gcloud`, uv runs `gcloud auth print-access-token`, or `gcloud auth
+    /// application-default print-access-token` with `--adc`, and authenticates with the username
+    /// `oauth2accesstoken` and the token.
+    ///
+    /// Only the auth config is updated; the keyring is not used.
+    #[arg(
+        long,
+        value_enum,
+        conflicts_with_all = [
+            "url",
+            "username",
+            "password",
+            "password_stdin",
+            "token",
+            "output_format",
+        ]
+    )]
+    pub helper: Option<CredentialHelperKind>,
+
+    /// The name of the CodeArtifact domain, for `--helper aws-codeartifact`.
+    #[arg(
+        long,
+        requires = "helper",
+        required_if_eq("helper", "aws-codeartifact")
+    )]
+    pub domain: Option<String>,
+
+    /// The AWS account ID that owns the CodeArtifact domain, for `--helper aws-codeartifact`.
+    #[arg(
+        long,
+        requires = "helper",
+        required_if_eq("helper", "aws-codeartifact")
+    )]
+    pub domain_owner: Option<String>,
+
+    /// The AWS region of the CodeArtifact domain, for `--helper aws-codeartifact`.
+    ///
+    /// Defaults to the region configured for the AWS CLI.
+    #[arg(long, requires = "helper")]
+    pub region: Option<String>,
+
+    /// Use the application default credentials instead of the active account, for `--helper
+    /// gcloud`.
+    #[arg(long, requires = "helper")]
+    pub adc: bool,
+}
+
+#[derive(Args)]
+#[command(group = clap::ArgGroup::new("selector").args(["name", "url", "proxy"]))]
+pub struct IndexSetCredentialsArgs {
+    #[command(flatten)]
+    pub index: IndexSelectorArgs,
+
+    #[command(flatten)]
+    pub credentials: CredentialsArgs,
+
+    /// Store a personal access token for an Azure Artifacts feed.
+    ///
+    /// Azure Artifacts accepts any username with a personal access token, so the username
+    /// defaults to `azure`. The token is read from `--password`, stdin with `--password-stdin`,
+    /// or user input. Whitespace around the token, which usually comes from copying it, is
+    /// removed.
+    #[arg(long, conflicts_with_all = ["username", "token", "helper"])]
+    pub azure: bool,
+
+    /// Request Entra ID access tokens for the Azure Artifacts feed with the Azure CLI, instead of
+    /// storing a personal access token.
+    ///
+    /// uv runs `az account get-access-token` when the index requires authentication, and keeps the
+    /// token in memory until it expires. Only the auth config is updated; the keyring is not used.
+    #[arg(
+        long,
+        requires = "azure",
+        conflicts_with_all = ["url", "password", "password_stdin", "output_format"]
+    )]
+    pub from_az_cli: bool,
+
+    #[command(flatten)]
+    pub helper: CredentialHelperArgs,
+
+    /// The HTTP authentication scheme to send the credentials with.
+    ///
+    /// With `bearer`, only a token is stored, read from `--password`, stdin with
+    /// `--password-stdin`, or user input. The token is sent in an `Authorization: Bearer` header,
+    /// and stored in the keyring under the username `__bearer__`.
+    ///
+    /// Defaults to `basic`.
+    #[arg(long, value_enum, conflicts_with_all = ["username", "token", "azure", "helper"])]
+    pub scheme: Option<IndexAuthScheme>,
+
+    /// Where to store the credentials in the keyring.
+    ///
+    /// With `host`, the credentials are stored for the host of the index, e.g.,
+    /// `https://example.com`, and used for all indexes on the host without credentials of their
+    /// own. With `url`, they're stored for the URL of the index, e.g.,
+    /// `https://example.com/team-a/simple`, and only used for the index and the URLs below it, to
+    /// use different credentials for several indexes on the same host.
+    ///
+    /// Defaults to `host`.
+    #[arg(long, value_enum, conflicts_with_all = ["helper", "from_az_cli"])]
+    pub scope: Option<IndexCredentialsScope>,
+
+    /// A PEM file with a client certificate to present to the index, for mutual TLS.
+    ///
+    /// The certificate is used for all requests to the host of the index, in place of
+    /// `SSL_CLIENT_CERT`. If the file doesn't include the private key of the certificate, provide
+    /// it with `--client-key`. Only the paths are recorded in the auth config, not the contents
+    /// of the files.
+    #[arg(long, value_name = "PATH", conflicts_with = "helper")]
+    pub client_cert: Option<PathBuf>,
+
+    /// A PEM file with the private key of the client certificate.
+    #[arg(long, value_name = "PATH", requires = "client_cert")]
+    pub client_key: Option<PathBuf>,
+
+    /// The URL of an HTTP proxy to store the credentials for, instead of an index.
+    ///
+    /// The password is stored in the keyring apart from the credentials of any index on the same
+    /// host, and is sent to the proxy when it is configured with `HTTP_PROXY`, `HTTPS_PROXY`, or
+    /// `ALL_PROXY`. The URL must not contain a password.
+    #[arg(
+        long,
+        value_name = "PROXY_URL",
+        conflicts_with_all = [
+            "token",
+            "azure",
+            "helper",
+            "scheme",
+            "scope",
+            "client_cert",
+            "output_format",
+        ]
+    )]
+    pub proxy: Option<String>,
+
+    /// Add the entry to the auth config of the project, rather than that of the user.
+    ///
+    /// The project auth config is `.uv/auth.toml` at the workspace root. Its entries take
+    /// precedence over the entries of the user for the same index, e.g., to use a service account
+    /// of the project.
+    ///
+    /// The flag isn't named `--project`, since that's the global option that selects the project
+    /// directory, e.g., `--project ../app --local` adds the entry to the auth config of `../app`.
+    #[arg(long, conflicts_with_all = ["helper", "from_az_cli", "proxy"])]
+    pub local: bool,
+
+    #[command(flatten)]
+    pub confirmation: ConfirmationArgs,
+
+    /// Write the credentials to the keyring and the auth config even if they're already up to
+    /// date.
+    ///
+    /// By default, if the keyring already has the password for the username and the auth config
+    /// already has the entry, neither is written, e.g., to avoid a prompt to authorize access to
+    /// the keyring.
+    #[arg(long, conflicts_with_all = ["helper", "from_az_cli", "dry_run"])]
+    pub force_write: bool,
+
+    #[command(flatten)]
+    pub output: OutputFormatArgs,
+
+    #[command(flatten)]
+    pub keyring: KeyringArgs,
+}
+
+#[derive(Args)]
+#[command(group = clap::ArgGroup::new("selector").args(["name", "url"]).required(true))]
+pub struct IndexRotateCredentialsArgs {
+    #[command(flatten)]
+    pub index: IndexSelectorArgs,
+
+    /// The username of the credentials to rotate
+    ///
+    /// Defaults to the username stored for the index, if it is the only one.
+    #[arg(long, required(false))]
+    pub username: Option<String>,
+
+    /// The new password that should be used for the index
+    #[arg(long, required(false))]
+    pub password: Option<String>,
+
+    /// Read the new password for the index from stdin.
+    ///
+    /// The entire input is used as the password, excluding a single trailing newline.
+    #[arg(long, conflicts_with = "password")]
+    pub password_stdin: bool,
+
+    #[command(flatten)]
+    pub confirmation: ConfirmationArgs,
+
+    #[command(flatten)]
+    pub keyring: KeyringArgs,
 }
 
 #[derive(Args)]
 pub struct IndexListCredentialsArgs {
-    /// Attempt to use `keyring` for authentication for remote requirements files.
+    #[command(flatten)]
+    pub output: OutputFormatArgs,
+
+    /// Exit with a non-zero status if any index is missing credentials.
     ///
-    /// At present, only `--keyring-provider subprocess` is supported, which configures uv to
-    /// use the `keyring` CLI to handle authentication.
+    /// Exits with status 1 if any index, or URL with an entry in the auth config, has no
+    /// credentials, e.g., because it has no entry in the auth config or the keyring has no
+    /// password for it, and with status 2 if the keyring could not be queried.
+    #[arg(long)]
+    pub strict: bool,
+
+    /// The name of an index that isn't required to have credentials with `--strict`, e.g., a
+    /// public index.
     ///
-    /// Defaults to `disabled`.
-    #[arg(long, value_enum, env = EnvVars::UV_KEYRING_PROVIDER)]
-    pub keyring_provider: Option<KeyringProviderType>,
+    /// May be provided multiple times.
+    #[arg(long, value_name = "NAME", requires = "strict")]
+    pub ignore: Vec<String>,
+
+    /// List only the credentials of the index with the given name.
+    ///
+    /// May be provided multiple times. Only the selected indexes are looked up, and checked with
+    /// `--strict`; URLs with an entry in the auth config aren't listed.
+    #[arg(long, value_name = "NAME", conflicts_with = "verify_config")]
+    pub index: Vec<String>,
+
+    /// Validate the auth config, without listing the credentials or accessing the keyring.
+    ///
+    /// Checks the auth config of the user, and that of the project, if any. Exits with a non-zero
+    /// status if either can't be loaded, naming the offending line.
+    #[arg(long, conflicts_with = "strict")]
+    pub verify_config: bool,
+
+    /// List the usernames in the auth config, without looking up the credentials.
+    ///
+    /// Neither the keyring nor any other source of credentials is queried, e.g., to avoid a prompt
+    /// to unlock the keyring. With `--output-format json`, `has_credentials` is `null`.
+    #[arg(long, conflicts_with_all = ["strict", "verify_config"])]
+    pub no_verify: bool,
+
+    /// Show the password of each index and URL, e.g., to check which token is used.
+    ///
+    /// Passwords are shown in full only if the output is a terminal; otherwise, only their last
+    /// four characters are shown. Can't be used with `--output-format json` without `--yes-really`.
+    #[arg(long, conflicts_with_all = ["verify_config", "no_verify"])]
+    pub show_password: bool,
+
+    /// Show the passwords in full even if the output isn't a terminal, or with `--output-format
+    /// json`.
+    #[arg(long, requires = "show_password")]
+    pub yes_really: bool,
+
+    /// The URL of the default package index, which is listed as `pypi` (by default:
+    /// <https://pypi.org/simple>).
+    ///
+    /// PyPI itself is only listed if credentials were set for it.
+    #[arg(long, env = EnvVars::UV_DEFAULT_INDEX, value_parser = parse_default_index)]
+    pub default_index: Option<Maybe<Index>>,
+
+    #[command(flatten)]
+    pub keyring: KeyringArgs,
 }
 
 #[derive(Args)]
-pub struct IndexUnsetCredentialsArgs {
-    /// The name of the index
+pub struct IndexCheckCredentialsArgs {
+    /// The name of the index to check.
+    ///
+    /// If not provided, all indexes with configured credentials are checked.
+    #[arg(long)]
+    pub name: Option<String>,
+
+    #[command(flatten)]
+    pub output: OutputFormatArgs,
+
+    #[command(flatten)]
+    pub keyring: KeyringArgs,
+}
+
+#[derive(Args)]
+pub struct IndexPruneCredentialsArgs {
+    #[command(flatten)]
+    pub confirmation: ConfirmationArgs,
+
+    #[command(flatten)]
+    pub keyring: KeyringArgs,
+}
+
+#[derive(Args)]
+pub struct IndexImportCredentialsArgs {
+    /// The file written by `uv index credentials export`.
+    ///
+    /// Reads from stdin if omitted or `-`.
+    pub file: Option<PathBuf>,
+
+    /// Replace the existing entries whose settings differ from the imported ones.
+    ///
+    /// Without this flag, such entries are kept, with a warning.
+    #[arg(long)]
+    pub overwrite: bool,
+
+    /// Don't prompt for the passwords of the imported entries.
+    ///
+    /// Without this flag, uv prompts for the password of each username of the entries that were
+    /// added or replaced, and stores it in the keyring. Entries with a credential helper are
+    /// skipped either way.
     #[arg(long)]
-    pub name: String,
+    pub skip_passwords: bool,
+
+    #[command(flatten)]
+    pub keyring: KeyringArgs,
+}
+
+#[derive(Args)]
+#[command(group = clap::ArgGroup::new("selector").args(["name", "url", "all"]).required(true))]
+pub struct IndexUnsetCredentialsArgs {
+    #[command(flatten)]
+    pub index: IndexSelectorArgs,
 
     /// The username that should be used for the index
-    #[arg(long, required(false))]
+    ///
+    /// Defaults to the username stored for the index when its credentials were set, if it is the
+    /// only one. Only this username is removed from the auth config.
+    #[arg(long, required(false), conflicts_with = "all")]
     pub username: Option<String>,
 
-    /// Attempt to use `keyring` for authentication for remote requirements files.
+    /// Unset the credentials for every entry in the auth config, and remove the entries.
     ///
-    /// At present, only `--keyring-provider subprocess` is supported, which configures uv to
-    /// use the `keyring` CLI to handle authentication.
+    /// Entries for hosts that no configured index uses are skipped if they were added by a
+    /// previous version of uv, since the URL their credentials are stored for is unknown.
+    #[arg(long, conflicts_with = "output_format")]
+    pub all: bool,
+
+    /// Remove the username from the entries of both the user and the project auth config.
     ///
-    /// Defaults to `disabled`.
-    #[arg(long, value_enum, env = EnvVars::UV_KEYRING_PROVIDER)]
-    pub keyring_provider: Option<KeyringProviderType>,
+    /// By default, only the auth config whose entry is used for the index is changed.
+    #[arg(long, conflicts_with = "all")]
+    pub all_layers: bool,
+
+    #[command(flatten)]
+    pub confirmation: ConfirmationArgs,
+
+    /// Exit with a non-zero status if no credentials were stored for the username.
+    ///
+    /// By default, the command succeeds whether or not the keyring had a password, or the auth
+    /// config an entry, to remove. Use `--quiet` to hide the summary of what was removed.
+    #[arg(long, conflicts_with_all = ["all", "dry_run"])]
+    pub strict: bool,
+
+    #[command(flatten)]
+    pub output: OutputFormatArgs,
+
+    #[command(flatten)]
+    pub keyring: KeyringArgs,
+}
+
+#[derive(Args)]
+pub struct AuthNamespace {
+    #[command(subcommand)]
+    pub command: AuthCommand,
+}
+
+#[derive(Subcommand)]
+pub enum AuthCommand {
+    /// Store credentials for an index, like `uv index credentials set`
+    #[command(
+        after_help = "Use `uv help auth login` for more details.",
+        after_long_help = ""
+    )]
+    Login(AuthLoginArgs),
+
+    /// Remove the credentials for an index, like `uv index credentials unset`
+    #[command(
+        after_help = "Use `uv help auth logout` for more details.",
+        after_long_help = ""
+    )]
+    Logout(AuthLogoutArgs),
+
+    /// Show the credentials for each index, like `uv index credentials list`
+    #[command(
+        after_help = "Use `uv help auth status` for more details.",
+        after_long_help = ""
+    )]
+    Status(IndexListCredentialsArgs),
+
+    /// Set up the credentials for each configured index, e.g., after cloning a project
+    ///
+    /// Indexes that accept their stored credentials, or don't require any, are skipped, so the
+    /// setup continues where it left off when run again. Without a terminal to prompt in, uv
+    /// fails with the indexes that still need credentials.
+    #[command(
+        after_help = "Use `uv help auth setup` for more details.",
+        after_long_help = ""
+    )]
+    Setup(AuthSetupArgs),
+
+    /// Show which credentials fetched the cached distributions of a package
+    ///
+    /// Credentials are only recorded for the cache entries that were fetched with
+    /// `UV_AUTH_PROVENANCE=1`. Only the realm, the name of the index, the kind of source, and a
+    /// fingerprint of the password are recorded. Use `uv cache prune --credential` to remove the
+    /// entries that were fetched with a set of credentials.
+    #[command(
+        after_help = "Use `uv help auth provenance` for more details.",
+        after_long_help = ""
+    )]
+    Provenance(AuthProvenanceArgs),
+}
+
+#[derive(Args)]
+pub struct AuthLoginArgs {
+    /// The name of the index to log in to, or a URL to use the credentials for.
+    ///
+    /// Defaults to the only index configured in your pyproject.toml, other than PyPI. If several
+    /// are configured, uv asks which one to use.
+    pub index: Option<String>,
+
+    #[command(flatten)]
+    pub credentials: CredentialsArgs,
+
+    #[command(flatten)]
+    pub confirmation: ConfirmationArgs,
+
+    #[command(flatten)]
+    pub output: OutputFormatArgs,
+
+    #[command(flatten)]
+    pub keyring: KeyringArgs,
+}
+
+#[derive(Args)]
+pub struct AuthLogoutArgs {
+    /// The name of the index to log out of, or a URL the credentials are used for.
+    ///
+    /// Defaults to the only index configured in your pyproject.toml, other than PyPI. If several
+    /// are configured, uv asks which one to use.
+    pub index: Option<String>,
+
+    /// The username to log out
+    ///
+    /// Defaults to the username stored for the index when its credentials were set, if it is the
+    /// only one.
+    #[arg(long)]
+    pub username: Option<String>,
+
+    #[command(flatten)]
+    pub confirmation: ConfirmationArgs,
+
+    #[command(flatten)]
+    pub output: OutputFormatArgs,
+
+    #[command(flatten)]
+    pub keyring: KeyringArgs,
+}
+
+#[derive(Args)]
+pub struct AuthSetupArgs {
+    #[command(flatten)]
+    pub keyring: KeyringArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct AuthProvenanceArgs {
+    /// The package to show the credentials for.
+    pub package: PackageName,
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::error::ErrorKind;
+    use clap::{Arg, Args, Command, CommandFactory, Parser};
+
+    use super::{
+        Cli, ConfirmationArgs, CredentialsArgs, IndexSelectorArgs, KeyringArgs, OutputFormatArgs,
+    };
+
+    /// Return the long flags defined by a set of shared arguments.
+    fn long_flags<T: Args>() -> Vec<String> {
+        T::augment_args(Command::new("shared"))
+            .get_arguments()
+            .filter_map(Arg::get_long)
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    /// Check that a command defines the long flags of each of the given shared arguments exactly
+    /// once, and that none of its long flags or their aliases, hidden or not, is defined twice.
+    fn assert_shared_args(command: &Command, path: &str, shared: &[Vec<String>]) {
+        let flags = command
+            .get_arguments()
+            .flat_map(|arg| {
+                arg.get_long()
+                    .into_iter()
+                    .chain(arg.get_all_aliases().into_iter().flatten())
+            })
+            .collect::<Vec<_>>();
+
+        for flag in shared.iter().flatten() {
+            assert_eq!(
+                flags.iter().filter(|long| **long == flag.as_str()).count(),
+                1,
+                "`uv {path}` should define `--{flag}` exactly once"
+            );
+        }
+        for (i, flag) in flags.iter().enumerate() {
+            assert!(
+                !flags[..i].contains(flag),
+                "`uv {path}` defines `--{flag}` more than once"
+            );
+        }
+    }
+
+    /// Every `uv index credentials` subcommand exposes the shared keyring arguments, those that
+    /// act on a single index expose the shared index selector, those that change credentials the
+    /// confirmation arguments, and those that report a result the output format, with no
+    /// subcommand redefining any of their flags.
+    #[test]
+    fn index_credentials_shared_args() {
+        let uv = Cli::command();
+        uv.clone().debug_assert();
+
+        let credentials = uv
+            .find_subcommand("index")
+            .and_then(|index| index.find_subcommand("credentials"))
+            .expect("`uv index credentials` should exist");
+
+        let keyring = long_flags::<KeyringArgs>();
+        let selector = long_flags::<IndexSelectorArgs>();
+        let confirmation = long_flags::<ConfirmationArgs>();
+        let output = long_flags::<OutputFormatArgs>();
+
+        for subcommand in credentials.get_subcommands() {
+            let name = subcommand.get_name();
+            let shared = match name {
+                "set" | "unset" => vec![
+                    keyring.clone(),
+                    selector.clone(),
+                    confirmation.clone(),
+                    output.clone(),
+                ],
+                "rotate" => vec![keyring.clone(), selector.clone(), confirmation.clone()],
+                "list" | "check" => vec![keyring.clone(), output.clone()],
+                "prune" => vec![keyring.clone(), confirmation.clone()],
+                "import" => vec![keyring.clone()],
+                "export" => vec![],
+                _ => panic!("`uv index credentials {name}` should be listed in this test"),
+            };
+            assert_shared_args(subcommand, &format!("index credentials {name}"), &shared);
+        }
+
+        let parse = |args: &[&str]| {
+            Cli::try_parse_from(["uv", "index", "credentials"].iter().chain(args)).map(drop)
+        };
+        assert!(parse(&[
+            "set",
+            "--name",
+            "internal",
+            "--yes",
+            "--output-format",
+            "json"
+        ])
+        .is_ok());
+        assert!(parse(&["unset", "--name", "internal", "--dry-run"]).is_ok());
+        assert!(parse(&["prune", "--dry-run"]).is_ok());
+        assert!(parse(&["check", "--output-format", "json"]).is_ok());
+        // The previous spellings are kept as hidden aliases.
+        assert!(parse(&["set", "--name", "internal", "--force", "--format", "json"]).is_ok());
+        assert!(parse(&["list", "--format", "json"]).is_ok());
+        for args in [
+            &["prune", "--yes", "--dry-run"][..],
+            &["set", "--name", "internal", "--force", "--dry-run"],
+            &["set", "--proxy", "http://proxy", "--output-format", "json"],
+            &["unset", "--all", "--output-format", "json"],
+        ] {
+            assert_eq!(
+                parse(args).unwrap_err().kind(),
+                ErrorKind::ArgumentConflict,
+                "{args:?}"
+            );
+        }
+    }
+
+    /// `uv auth login` takes the same credential flags as `uv index credentials set`, and every
+    /// `uv auth` subcommand takes the shared arguments of the `uv index credentials` subcommand it
+    /// corresponds to.
+    #[test]
+    fn auth_shared_args() {
+        let uv = Cli::command();
+        let auth = uv.find_subcommand("auth").expect("`uv auth` should exist");
+
+        let credentials = long_flags::<CredentialsArgs>();
+        let keyring = long_flags::<KeyringArgs>();
+        let confirmation = long_flags::<ConfirmationArgs>();
+        let output = long_flags::<OutputFormatArgs>();
+
+        for subcommand in auth.get_subcommands() {
+            let name = subcommand.get_name();
+            let shared = match name {
+                "login" => vec![
+                    credentials.clone(),
+                    keyring.clone(),
+                    confirmation.clone(),
+                    output.clone(),
+                ],
+                "logout" => vec![keyring.clone(), confirmation.clone(), output.clone()],
+                "status" => vec![keyring.clone(), output.clone()],
+                "setup" => vec![keyring.clone()],
+                "provenance" => vec![],
+                _ => panic!("`uv auth {name}` should be listed in this test"),
+            };
+            assert_shared_args(subcommand, &format!("auth {name}"), &shared);
+        }
+        let set = uv
+            .find_subcommand("index")
+            .and_then(|index| index.find_subcommand("credentials"))
+            .and_then(|credentials| credentials.find_subcommand("set"))
+            .expect("`uv index credentials set` should exist");
+        assert_shared_args(set, "index credentials set", &[credentials]);
+
+        let parse =
+            |args: &[&str]| Cli::try_parse_from(["uv", "auth"].iter().chain(args)).map(drop);
+        assert!(parse(&["login"]).is_ok());
+        assert!(parse(&["login", "internal", "--token", "secret"]).is_ok());
+        assert!(parse(&["login", "internal", "--force"]).is_ok());
+        assert!(parse(&["login", "internal", "--dry-run", "--output-format", "json"]).is_ok());
+        assert!(parse(&["logout", "internal", "--username", "user"]).is_ok());
+        assert!(parse(&["logout", "internal", "--dry-run"]).is_ok());
+        assert!(parse(&["status", "--output-format", "json"]).is_ok());
+        assert!(parse(&["status", "--format", "json"]).is_ok());
+        assert!(parse(&["setup", "--keyring-provider", "subprocess"]).is_ok());
+        assert!(parse(&["provenance", "anyio"]).is_ok());
+        assert_eq!(
+            parse(&["provenance"]).unwrap_err().kind(),
+            ErrorKind::MissingRequiredArgument
+        );
+        let err = parse(&["login", "--token", "secret", "--username", "user"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ArgumentConflict);
+        let err = parse(&["login", "--yes", "--dry-run"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ArgumentConflict);
+    }
+
+    /// `--azure` defaults the username, and `--from-az-cli` replaces the stored token.
+    #[test]
+    fn index_credentials_set_azure() {
+        let parse = |args: &[&str]| {
+            Cli::try_parse_from(
+                ["uv", "index", "credentials", "set", "--name", "feed"]
+                    .iter()
+                    .chain(args),
+            )
+            .map(drop)
+        };
+
+        assert!(parse(&["--azure"]).is_ok());
+        assert!(parse(&["--azure", "--password-stdin"]).is_ok());
+        assert!(parse(&["--azure", "--from-az-cli"]).is_ok());
+        for args in [
+            &["--azure", "--username", "user"][..],
+            &["--azure", "--from-az-cli", "--password", "secret"],
+            &["--azure", "--helper", "gcloud"],
+        ] {
+            assert_eq!(parse(args).unwrap_err().kind(), ErrorKind::ArgumentConflict);
+        }
+        assert_eq!(
+            parse(&["--from-az-cli"]).unwrap_err().kind(),
+            ErrorKind::MissingRequiredArgument
+        );
+    }
+
+    /// `--ignore` only applies to `--strict`.
+    #[test]
+    fn index_credentials_list_strict() {
+        let parse = |args: &[&str]| {
+            Cli::try_parse_from(["uv", "index", "credentials", "list"].iter().chain(args)).map(drop)
+        };
+
+        assert!(parse(&["--strict"]).is_ok());
+        assert!(parse(&["--strict", "--ignore", "pypi", "--ignore", "mirror"]).is_ok());
+        assert_eq!(
+            parse(&["--ignore", "pypi"]).unwrap_err().kind(),
+            ErrorKind::MissingRequiredArgument
+        );
+    }
+
+    /// `--strict` applies to a single index, with the global `--quiet`.
+    #[test]
+    fn index_credentials_unset_strict() {
+        let parse = |args: &[&str]| {
+            Cli::try_parse_from(["uv", "index", "credentials", "unset"].iter().chain(args))
+                .map(drop)
+        };
+
+        assert!(parse(&["--name", "internal", "--strict", "--quiet"]).is_ok());
+        for args in [
+            &["--all", "--strict"][..],
+            &["--name", "internal", "--dry-run", "--strict"],
+        ] {
+            assert_eq!(parse(args).unwrap_err().kind(), ErrorKind::ArgumentConflict);
+        }
+    }
+
+    /// An index is selected either by name or by URL, but not both.
+    #[test]
+    fn index_credentials_name_or_url() {
+        let parse = |args: &[&str]| {
+            Cli::try_parse_from(["uv", "index", "credentials"].iter().chain(args)).map(drop)
+        };
+
+        // Without either, `set` selects from the configured indexes.
+        assert!(parse(&["set"]).is_ok());
+
+        for subcommand in ["set", "rotate", "unset"] {
+            assert!(parse(&[subcommand, "--name", "internal"]).is_ok());
+            assert!(parse(&[subcommand, "--url", "https://example.com/pkg.whl"]).is_ok());
+
+            let err = parse(&[
+                subcommand,
+                "--name",
+                "internal",
+                "--url",
+                "https://example.com/pkg.whl",
+            ])
+            .unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::ArgumentConflict);
+
+            if subcommand != "set" {
+                let err = parse(&[subcommand]).unwrap_err();
+                assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+            }
+        }
+    }
+
+    /// `--all` replaces the index selector of `uv index credentials unset`.
+    #[test]
+    fn index_credentials_unset_all() {
+        let parse = |args: &[&str]| {
+            Cli::try_parse_from(["uv", "index", "credentials", "unset"].iter().chain(args))
+                .map(drop)
+        };
+
+        assert!(parse(&["--all"]).is_ok());
+        assert!(parse(&["--all", "--dry-run"]).is_ok());
+
+        for args in [
+            &["--all", "--name", "internal"][..],
+            &["--all", "--username", "user"],
+            &["--name", "internal", "--dry-run"],
+        ] {
+            let err = parse(args).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::ArgumentConflict, "{args:?}");
+        }
+
+        let err = parse(&["--dry-run"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
 }
@@ -10,6 +10,7 @@ use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info_span, instrument, trace, warn, Instrument};
 
+use uv_auth::CredentialProvenance;
 use uv_cache::{CacheEntry, Freshness};
 use uv_fs::write_atomic;
 
@@ -406,6 +407,13 @@ impl CachedClient {
         CallbackReturn: Future<Output = Result<Payload, CallBackError>>,
     {
         let new_cache = info_span!("new_cache", file = %cache_entry.path().display());
+        // Keep the provenance of the credentials the response was fetched with, if any, to record
+        // it for the cache entry.
+        let provenance = response
+            .extensions()
+            .get::<CredentialProvenance>()
+            .cloned()
+            .map(|provenance| (response.url().clone(), provenance));
         let data = response_callback(response)
             .boxed_local()
             .await
@@ -422,6 +430,9 @@ impl CachedClient {
             write_atomic(cache_entry.path(), data_with_cache_policy_bytes)
                 .await
                 .map_err(ErrorKind::CacheWrite)?;
+            if let Some((url, provenance)) = provenance {
+                uv_auth::record_provenance(&url, cache_entry.path(), &provenance);
+            }
             Ok(data.into_target())
         }
         .instrument(new_cache)
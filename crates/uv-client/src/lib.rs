@@ -11,6 +11,7 @@ pub use registry_client::{
     VersionFiles,
 };
 pub use rkyvutil::{Deserializer, OwnedArchive, Serializer, Validator};
+pub use tls::{read_client_identity, CertificateError};
 
 mod base_client;
 mod cached_client;
@@ -20,6 +21,7 @@ mod html;
 mod httpcache;
 mod linehaul;
 mod middleware;
+mod proxy;
 mod registry_client;
 mod remote_metadata;
 mod rkyvutil;
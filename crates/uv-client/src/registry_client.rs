@@ -2,6 +2,7 @@ use std::collections::BTreeMap;
 use std::fmt::Debug;
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::sync::Arc;
 use std::time::Duration;
 
 use async_http_range_reader::AsyncHttpRangeReader;
@@ -20,6 +21,7 @@ use crate::html::SimpleHtml;
 use crate::remote_metadata::wheel_metadata_from_remote_zip;
 use crate::rkyvutil::OwnedArchive;
 use crate::{BaseClient, CachedClient, CachedClientError, Error, ErrorKind};
+use uv_auth::KeyringMetrics;
 use uv_cache::{Cache, CacheBucket, CacheEntry, WheelCache};
 use uv_configuration::KeyringProviderType;
 use uv_configuration::{IndexStrategy, TrustedHost};
@@ -74,6 +76,18 @@ impl<'a> RegistryClientBuilder<'a> {
         self
     }
 
+    #[must_use]
+    pub fn keyring_from_cli(mut self, keyring_from_cli: bool) -> Self {
+        self.base_client_builder = self.base_client_builder.keyring_from_cli(keyring_from_cli);
+        self
+    }
+
+    #[must_use]
+    pub fn keyring_metrics(mut self, keyring_metrics: Arc<KeyringMetrics>) -> Self {
+        self.base_client_builder = self.base_client_builder.keyring_metrics(keyring_metrics);
+        self
+    }
+
     #[must_use]
     pub fn allow_insecure_host(mut self, allow_insecure_host: Vec<TrustedHost>) -> Self {
         self.base_client_builder = self
@@ -132,7 +146,10 @@ impl<'a> RegistryClientBuilder<'a> {
 
     pub fn build(self) -> RegistryClient {
         // Build a base client
-        let builder = self.base_client_builder;
+        let builder = self
+            .base_client_builder
+            .username_discovery(defined_index_urls(&self.index_urls))
+            .index_names(named_index_urls(&self.index_urls));
 
         let client = builder.build();
 
@@ -155,7 +172,11 @@ impl<'a> RegistryClientBuilder<'a> {
     /// Share the underlying client between two different middleware configurations.
     pub fn wrap_existing(self, existing: &BaseClient) -> RegistryClient {
         // Wrap in any relevant middleware and handle connectivity.
-        let client = self.base_client_builder.wrap_existing(existing);
+        let client = self
+            .base_client_builder
+            .username_discovery(defined_index_urls(&self.index_urls))
+            .index_names(named_index_urls(&self.index_urls))
+            .wrap_existing(existing);
 
         let timeout = client.timeout();
         let connectivity = client.connectivity();
@@ -187,6 +208,25 @@ impl<'a> TryFrom<BaseClientBuilder<'a>> for RegistryClientBuilder<'a> {
     }
 }
 
+/// The URLs of the indexes defined by the user, for which a username is discovered in the keyring
+/// when none is known.
+///
+/// `PyPI` is only included if it was configured explicitly.
+fn defined_index_urls(index_urls: &IndexUrls) -> Vec<Url> {
+    index_urls
+        .defined_indexes()
+        .map(|index| index.url().url().clone())
+        .collect()
+}
+
+/// The URLs and names of the named indexes, for the provenance of the credentials sent to them.
+fn named_index_urls(index_urls: &IndexUrls) -> Vec<(Url, String)> {
+    index_urls
+        .defined_indexes()
+        .filter_map(|index| Some((index.url().url().clone(), index.name.as_ref()?.to_string())))
+        .collect()
+}
+
 /// A client for fetching packages from a `PyPI`-compatible index.
 #[derive(Debug, Clone)]
 pub struct RegistryClient {
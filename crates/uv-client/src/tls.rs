@@ -1,9 +1,10 @@
 use reqwest::Identity;
 use std::ffi::OsStr;
 use std::io::Read;
+use std::path::Path;
 
 #[derive(thiserror::Error, Debug)]
-pub(crate) enum CertificateError {
+pub enum CertificateError {
     #[error(transparent)]
     Io(#[from] std::io::Error),
     #[error(transparent)]
@@ -12,8 +13,19 @@ pub(crate) enum CertificateError {
 
 /// Return the `Identity` from the provided file.
 pub(crate) fn read_identity(ssl_client_cert: &OsStr) -> Result<Identity, CertificateError> {
+    read_client_identity(Path::new(ssl_client_cert), None)
+}
+
+/// Return the `Identity` from a PEM file with a certificate, and a PEM file with its private key.
+///
+/// Without a key file, the private key is read from the certificate file.
+pub fn read_client_identity(cert: &Path, key: Option<&Path>) -> Result<Identity, CertificateError> {
     let mut buf = Vec::new();
-    fs_err::File::open(ssl_client_cert)?.read_to_end(&mut buf)?;
+    fs_err::File::open(cert)?.read_to_end(&mut buf)?;
+    if let Some(key) = key {
+        buf.push(b'\n');
+        fs_err::File::open(key)?.read_to_end(&mut buf)?;
+    }
     Identity::from_pem(&buf).map_err(|tls_err| {
         debug_assert!(tls_err.is_builder(), "must be a rustls::Error internally");
         CertificateError::Reqwest(tls_err)
@@ -1,4 +1,8 @@
-use uv_auth::{self, KeyringProvider};
+use uv_auth::{
+    self, ChainedStore, CredentialStore, IndexKeyringProvider, KeyringCommandError, KeyringCompat,
+    KeyringProvider,
+};
+use uv_warnings::warn_user_once;
 
 /// Keyring provider type to use for credential lookup.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -11,6 +15,16 @@ pub enum KeyringProviderType {
     Disabled,
     /// Use the `keyring` command for credential lookup.
     Subprocess,
+    /// Use the credential store of the operating system for credential lookup.
+    Native,
+    /// Use the credential helpers configured for git (`git credential`) for credential lookup.
+    Git,
+    /// Use the 1Password CLI (`op`) for credential lookup.
+    #[serde(rename = "op")]
+    #[cfg_attr(feature = "clap", value(name = "op"))]
+    OnePassword,
+    /// Use Vault (`VAULT_ADDR` and `VAULT_TOKEN`) for credential lookup.
+    Vault,
     // /// Not yet implemented
     // Auto,
     // /// Not implemented yet. Maybe use <https://docs.rs/keyring/latest/keyring/> for this?
@@ -19,10 +33,126 @@ pub enum KeyringProviderType {
 // See <https://pip.pypa.io/en/stable/topics/authentication/#keyring-support> for details.
 
 impl KeyringProviderType {
+    /// Create the [`KeyringProvider`], if any.
+    ///
+    /// If the keyring command is overridden with an invalid `UV_KEYRING_COMMAND`, keyring
+    /// authentication is disabled with a warning.
     pub fn to_provider(&self) -> Option<KeyringProvider> {
+        self.try_to_provider().unwrap_or_else(|err| {
+            warn_user_once!("{err}; keyring authentication is disabled");
+            None
+        })
+    }
+
+    /// Create the [`KeyringProvider`], if any, failing if the keyring command is overridden with
+    /// an invalid `UV_KEYRING_COMMAND`.
+    pub fn try_to_provider(&self) -> Result<Option<KeyringProvider>, KeyringCommandError> {
         match self {
-            Self::Disabled => None,
-            Self::Subprocess => Some(KeyringProvider::subprocess()),
+            Self::Disabled => Ok(None),
+            Self::Subprocess => KeyringProvider::subprocess_from_env().map(Some),
+            #[cfg(feature = "native-keyring")]
+            Self::Native => Ok(Some(KeyringProvider::native())),
+            #[cfg(not(feature = "native-keyring"))]
+            Self::Native => {
+                warn_user_once!(
+                    "The native keyring provider is not available in this build of uv (requires the `native-keyring` feature); keyring authentication is disabled"
+                );
+                Ok(None)
+            }
+            Self::Git => Ok(Some(KeyringProvider::git_credential())),
+            #[cfg(feature = "onepassword")]
+            Self::OnePassword => Ok(Some(KeyringProvider::onepassword())),
+            #[cfg(not(feature = "onepassword"))]
+            Self::OnePassword => {
+                warn_user_once!(
+                    "The 1Password keyring provider is not available in this build of uv (requires the `onepassword` feature); keyring authentication is disabled"
+                );
+                Ok(None)
+            }
+            #[cfg(feature = "vault")]
+            Self::Vault => {
+                let provider = KeyringProvider::vault();
+                if provider.is_none() {
+                    warn_user_once!(
+                        "`VAULT_ADDR` and `VAULT_TOKEN` must be set to use the vault keyring provider; keyring authentication is disabled"
+                    );
+                }
+                Ok(provider)
+            }
+            #[cfg(not(feature = "vault"))]
+            Self::Vault => {
+                warn_user_once!(
+                    "The vault keyring provider is not available in this build of uv (requires the `vault` feature); keyring authentication is disabled"
+                );
+                Ok(None)
+            }
+        }
+    }
+
+    /// Create a [`KeyringProvider`] that tries each of the given providers in order, e.g., the
+    /// credential store of the operating system before the `keyring` command.
+    ///
+    /// Providers that are disabled or unavailable are skipped, with a warning as for
+    /// [`KeyringProviderType::to_provider`]. A single remaining provider is returned as is.
+    pub fn to_chained_provider(providers: &[Self]) -> Option<KeyringProvider> {
+        let mut providers = providers
+            .iter()
+            .filter_map(Self::to_provider)
+            .collect::<Vec<_>>();
+        if providers.len() <= 1 {
+            return providers.pop();
+        }
+        // Lookups are cached by the chain, rather than by each provider.
+        let stores = providers
+            .into_iter()
+            .map(|provider| Box::new(provider.uncached()) as Box<dyn CredentialStore>)
+            .collect();
+        Some(KeyringProvider::chained(ChainedStore::new(stores)))
+    }
+}
+
+impl From<IndexKeyringProvider> for KeyringProviderType {
+    fn from(value: IndexKeyringProvider) -> Self {
+        match value {
+            IndexKeyringProvider::Disabled => Self::Disabled,
+            IndexKeyringProvider::Subprocess => Self::Subprocess,
+            IndexKeyringProvider::Native => Self::Native,
+            IndexKeyringProvider::Git => Self::Git,
+            IndexKeyringProvider::OnePassword => Self::OnePassword,
+            IndexKeyringProvider::Vault => Self::Vault,
+        }
+    }
+}
+
+impl From<KeyringProviderType> for IndexKeyringProvider {
+    fn from(value: KeyringProviderType) -> Self {
+        match value {
+            KeyringProviderType::Disabled => Self::Disabled,
+            KeyringProviderType::Subprocess => Self::Subprocess,
+            KeyringProviderType::Native => Self::Native,
+            KeyringProviderType::Git => Self::Git,
+            KeyringProviderType::OnePassword => Self::OnePassword,
+            KeyringProviderType::Vault => Self::Vault,
+        }
+    }
+}
+
+/// Another tool whose keyring entries are checked for credentials, after those of uv.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum KeyringCompatType {
+    /// The entries that `twine` stores for the upload URL of an index.
+    Twine,
+    /// The entries that `poetry` stores for an index under `poetry-repository-<name>`.
+    Poetry,
+}
+
+impl From<KeyringCompatType> for KeyringCompat {
+    fn from(value: KeyringCompatType) -> Self {
+        match value {
+            KeyringCompatType::Twine => Self::Twine,
+            KeyringCompatType::Poetry => Self::Poetry,
         }
     }
 }
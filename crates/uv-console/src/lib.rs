@@ -1,34 +1,19 @@
 use console::{measure_text_width, style, Key, Term};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering as AtomicOrdering};
 use std::{cmp::Ordering, iter};
 
+/// The number of [`InterruptGuard`]s that are alive, during which Ctrl-C doesn't exit the process.
+static DEFERRED: AtomicUsize = AtomicUsize::new(0);
+
+/// Whether Ctrl-C was pressed while an [`InterruptGuard`] was alive.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
 /// Prompt the user for confirmation in the given [`Term`].
 ///
 /// This is a slimmed-down version of `dialoguer::Confirm`, with the post-confirmation report
 /// enabled.
 pub fn confirm(message: &str, term: &Term, default: bool) -> std::io::Result<bool> {
-    // Set the Ctrl-C handler to exit the process.
-    let result = ctrlc::set_handler(move || {
-        let term = Term::stderr();
-        term.show_cursor().ok();
-        term.write_str("\n").ok();
-        term.flush().ok();
-
-        #[allow(clippy::exit, clippy::cast_possible_wrap)]
-        std::process::exit(if cfg!(windows) {
-            0xC000_013A_u32 as i32
-        } else {
-            130
-        });
-    });
-
-    match result {
-        Ok(()) => {}
-        Err(ctrlc::Error::MultipleHandlers) => {
-            // If multiple handlers were set, we assume that the existing handler is our
-            // confirmation handler, and continue.
-        }
-        Err(err) => return Err(std::io::Error::new(std::io::ErrorKind::Other, err)),
-    }
+    set_ctrlc_handler()?;
 
     let prompt = format!(
         "{} {} {} {} {}",
@@ -75,15 +60,157 @@ pub fn confirm(message: &str, term: &Term, default: bool) -> std::io::Result<boo
     Ok(response)
 }
 
+/// Prompt the user to select one of the given items in the given [`Term`], returning the index of
+/// the selected item.
+///
+/// The items are listed with a number, and the prompt is repeated until a valid number is entered.
+pub fn select(message: &str, items: &[String], term: &Term) -> std::io::Result<usize> {
+    set_ctrlc_handler()?;
+
+    term.write_line(&format!(
+        "{} {}",
+        style("?".to_string()).for_stderr().yellow(),
+        style(message).for_stderr().bold(),
+    ))?;
+    for (number, item) in items.iter().enumerate() {
+        term.write_line(&format!(
+            "  {} {item}",
+            style(format!("{})", number + 1)).for_stderr().cyan(),
+        ))?;
+    }
+
+    let prompt = format!("Enter a number [1-{}]: ", items.len());
+    loop {
+        let response = input(&prompt, term)?;
+        match response.trim().parse::<usize>() {
+            Ok(number) if (1..=items.len()).contains(&number) => return Ok(number - 1),
+            _ => term.write_line(&format!(
+                "{} Enter a number between 1 and {}",
+                style("✘".to_string()).for_stderr().red(),
+                items.len()
+            ))?,
+        }
+    }
+}
+
+/// Set the Ctrl-C handler to exit the process, restoring the terminal first.
+///
+/// While an [`InterruptGuard`] is alive, the exit is deferred until the guard is dropped.
+fn set_ctrlc_handler() -> std::io::Result<()> {
+    let result = ctrlc::set_handler(move || {
+        if DEFERRED.load(AtomicOrdering::SeqCst) > 0 {
+            INTERRUPTED.store(true, AtomicOrdering::SeqCst);
+            return;
+        }
+        exit_interrupted();
+    });
+
+    match result {
+        Ok(()) => Ok(()),
+        Err(ctrlc::Error::MultipleHandlers) => {
+            // If multiple handlers were set, we assume that the existing handler is our
+            // prompt handler, and continue.
+            Ok(())
+        }
+        Err(err) => Err(std::io::Error::new(std::io::ErrorKind::Other, err)),
+    }
+}
+
+/// Restore the terminal, e.g., echo after a password prompt, and exit the process as if it was
+/// killed by Ctrl-C.
+fn exit_interrupted() -> ! {
+    #[cfg(unix)]
+    terminal_modes::restore();
+
+    let term = Term::stderr();
+    term.show_cursor().ok();
+    term.write_str("\n").ok();
+    term.flush().ok();
+
+    #[allow(clippy::exit, clippy::cast_possible_wrap)]
+    std::process::exit(if cfg!(windows) {
+        0xC000_013A_u32 as i32
+    } else {
+        130
+    });
+}
+
+/// Defer the exit on Ctrl-C until the returned guard is dropped, e.g., to finish a sequence of
+/// changes that must not be left half done.
+///
+/// The guard reports whether Ctrl-C was pressed in the meantime, for the sequence to be cut short
+/// or rolled back. Dropping the guard then exits the process.
+pub fn defer_interrupts() -> std::io::Result<InterruptGuard> {
+    set_ctrlc_handler()?;
+    DEFERRED.fetch_add(1, AtomicOrdering::SeqCst);
+    Ok(InterruptGuard { _private: () })
+}
+
+/// A guard that defers the exit on Ctrl-C, see [`defer_interrupts`].
+#[derive(Debug)]
+pub struct InterruptGuard {
+    _private: (),
+}
+
+impl InterruptGuard {
+    /// Whether Ctrl-C was pressed since the guard was created.
+    pub fn interrupted(&self) -> bool {
+        INTERRUPTED.load(AtomicOrdering::SeqCst)
+    }
+}
+
+impl Drop for InterruptGuard {
+    fn drop(&mut self) {
+        if DEFERRED.fetch_sub(1, AtomicOrdering::SeqCst) == 1
+            && INTERRUPTED.load(AtomicOrdering::SeqCst)
+        {
+            exit_interrupted();
+        }
+    }
+}
+
+/// Open the controlling terminal of the process, to prompt the user even if both stdout and stderr
+/// are redirected, e.g., to a log file.
+///
+/// Returns [`None`] if the process has no controlling terminal, e.g., in CI.
+#[cfg(unix)]
+pub fn controlling_term() -> Option<Term> {
+    let read = std::fs::File::open("/dev/tty").ok()?;
+    let write = std::fs::OpenOptions::new()
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+    let term = Term::read_write_pair(read, write);
+    term.is_term().then_some(term)
+}
+
+/// Open the controlling terminal of the process.
+///
+/// A [`Term`] can't be created for the console (`CONIN$` and `CONOUT$`) on Windows, so this
+/// always returns [`None`].
+#[cfg(not(unix))]
+pub fn controlling_term() -> Option<Term> {
+    None
+}
+
 /// Prompt the user for password in the given [`Term`].
 ///
 /// This is a slimmed-down version of `dialoguer::Password`.
 pub fn password(prompt: &str, term: &Term) -> std::io::Result<String> {
+    set_ctrlc_handler()?;
+
     term.write_str(prompt)?;
     term.show_cursor()?;
     term.flush()?;
 
-    let input = term.read_secure_line()?;
+    // Echo is turned off while the password is read, so save the modes of the terminal to
+    // restore them if the prompt is interrupted.
+    #[cfg(unix)]
+    terminal_modes::save();
+    let input = term.read_secure_line();
+    #[cfg(unix)]
+    terminal_modes::forget();
+    let input = input?;
 
     term.clear_line()?;
 
@@ -261,3 +388,89 @@ pub fn input(prompt: &str, term: &Term) -> std::io::Result<String> {
 
     Ok(input)
 }
+
+/// The modes of the terminal that a password is read from, to restore them if the process is
+/// interrupted while echo is turned off.
+#[cfg(unix)]
+mod terminal_modes {
+    use std::io::IsTerminal;
+    use std::os::fd::{AsFd, OwnedFd};
+    use std::sync::Mutex;
+
+    use nix::sys::termios::{tcgetattr, tcsetattr, SetArg, Termios};
+
+    /// The terminal and its modes before the prompt.
+    static SAVED: Mutex<Option<SavedModes>> = Mutex::new(None);
+
+    #[derive(Debug)]
+    pub(crate) struct SavedModes {
+        fd: OwnedFd,
+        termios: Termios,
+    }
+
+    impl SavedModes {
+        pub(crate) fn save(fd: OwnedFd) -> nix::Result<Self> {
+            let termios = tcgetattr(&fd)?;
+            Ok(Self { fd, termios })
+        }
+
+        pub(crate) fn restore(&self) -> nix::Result<()> {
+            tcsetattr(&self.fd, SetArg::TCSANOW, &self.termios)
+        }
+    }
+
+    /// Save the modes of the terminal that [`console::Term::read_secure_line`] reads from: stdin
+    /// if it's a terminal, and the controlling terminal otherwise.
+    pub(crate) fn save() {
+        let stdin = std::io::stdin();
+        let fd = if stdin.is_terminal() {
+            stdin.as_fd().try_clone_to_owned()
+        } else {
+            std::fs::File::open("/dev/tty").map(OwnedFd::from)
+        };
+        let saved = fd.ok().and_then(|fd| SavedModes::save(fd).ok());
+        *SAVED.lock().unwrap() = saved;
+    }
+
+    /// Forget the saved modes, once the prompt restored them itself.
+    pub(crate) fn forget() {
+        SAVED.lock().unwrap().take();
+    }
+
+    /// Restore the saved modes, if any.
+    pub(crate) fn restore() {
+        if let Some(saved) = SAVED.lock().unwrap().take() {
+            saved.restore().ok();
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use nix::pty::openpty;
+        use nix::sys::termios::LocalFlags;
+
+        use super::*;
+
+        /// Echo is turned back on for a terminal whose modes were saved before the prompt.
+        #[test]
+        fn restore_echo() {
+            let pty = openpty(None, None).unwrap();
+            let echo = |fd: &OwnedFd| {
+                tcgetattr(fd)
+                    .unwrap()
+                    .local_flags
+                    .contains(LocalFlags::ECHO)
+            };
+            assert!(echo(&pty.slave));
+
+            let saved = SavedModes::save(pty.slave.try_clone().unwrap()).unwrap();
+            let mut termios = tcgetattr(&pty.slave).unwrap();
+            termios.local_flags.remove(LocalFlags::ECHO);
+            tcsetattr(&pty.slave, SetArg::TCSANOW, &termios).unwrap();
+            assert!(!echo(&pty.slave));
+
+            saved.restore().unwrap();
+            assert!(echo(&pty.slave));
+        }
+    }
+}
@@ -4,7 +4,7 @@ use std::str::FromStr;
 use thiserror::Error;
 use url::Url;
 
-use uv_auth::Credentials;
+use uv_auth::{Credentials, EnvCredentialProvider};
 
 use crate::index_name::{IndexName, IndexNameError};
 use crate::origin::Origin;
@@ -182,7 +182,7 @@ impl Index {
 
         if let Some(name) = self.name.as_ref() {
             // If credentials are provided via the environment, prefer those.
-            if let Some(credentials) = Credentials::from_env(name.to_env_var()) {
+            if let Some(credentials) = EnvCredentialProvider::new(name.as_ref()).credentials() {
                 return Some(credentials);
             }
         }
@@ -277,10 +277,7 @@ impl<'a> IndexLocations {
         if self.no_index {
             None
         } else {
-            let mut seen = FxHashSet::default();
-            self.indexes
-                .iter()
-                .filter(move |index| index.name.as_ref().is_none_or(|name| seen.insert(name)))
+            unique_indexes(&self.indexes)
                 .find(|index| index.default)
                 .or_else(|| Some(&DEFAULT_INDEX))
         }
@@ -293,12 +290,8 @@ impl<'a> IndexLocations {
         if self.no_index {
             Either::Left(std::iter::empty())
         } else {
-            let mut seen = FxHashSet::default();
             Either::Right(
-                self.indexes
-                    .iter()
-                    .filter(move |index| index.name.as_ref().is_none_or(|name| seen.insert(name)))
-                    .filter(|index| !index.default && !index.explicit),
+                unique_indexes(&self.indexes).filter(|index| !index.default && !index.explicit),
             )
         }
     }
@@ -324,12 +317,7 @@ impl<'a> IndexLocations {
         if self.no_index {
             Either::Left(std::iter::empty())
         } else {
-            let mut seen = FxHashSet::default();
-            Either::Right(
-                self.indexes
-                    .iter()
-                    .filter(move |index| index.name.as_ref().is_none_or(|name| seen.insert(name))),
-            )
+            Either::Right(unique_indexes(&self.indexes))
         }
     }
 
@@ -363,14 +351,8 @@ impl<'a> IndexLocations {
         } else {
             let mut indexes = vec![];
 
-            let mut seen = FxHashSet::default();
             let mut default = false;
-            for index in {
-                self.indexes
-                    .iter()
-                    .chain(self.flat_index.iter())
-                    .filter(move |index| index.name.as_ref().is_none_or(|name| seen.insert(name)))
-            } {
+            for index in unique_indexes(self.indexes.iter().chain(self.flat_index.iter())) {
                 if index.default {
                     if default {
                         continue;
@@ -416,10 +398,7 @@ impl<'a> IndexUrls {
         if self.no_index {
             None
         } else {
-            let mut seen = FxHashSet::default();
-            self.indexes
-                .iter()
-                .filter(move |index| index.name.as_ref().is_none_or(|name| seen.insert(name)))
+            unique_indexes(&self.indexes)
                 .find(|index| index.default)
                 .or_else(|| Some(&DEFAULT_INDEX))
         }
@@ -432,12 +411,8 @@ impl<'a> IndexUrls {
         if self.no_index {
             Either::Left(std::iter::empty())
         } else {
-            let mut seen = FxHashSet::default();
             Either::Right(
-                self.indexes
-                    .iter()
-                    .filter(move |index| index.name.as_ref().is_none_or(|name| seen.insert(name)))
-                    .filter(|index| !index.default && !index.explicit),
+                unique_indexes(&self.indexes).filter(|index| !index.default && !index.explicit),
             )
         }
     }
@@ -470,30 +445,48 @@ impl<'a> IndexUrls {
             Either::Left(std::iter::empty())
         } else {
             Either::Right(
-                {
-                    let mut seen = FxHashSet::default();
-                    self.indexes
-                        .iter()
-                        .filter(move |index| {
-                            index.name.as_ref().is_none_or(|name| seen.insert(name))
-                        })
-                        .filter(|index| !index.default)
-                }
-                .chain({
-                    let mut seen = FxHashSet::default();
-                    self.indexes
-                        .iter()
-                        .filter(move |index| {
-                            index.name.as_ref().is_none_or(|name| seen.insert(name))
-                        })
-                        .find(|index| index.default)
-                        .into_iter()
-                }),
+                unique_indexes(&self.indexes)
+                    .filter(|index| !index.default)
+                    .chain(unique_indexes(&self.indexes).find(|index| index.default)),
             )
         }
     }
 }
 
+/// Filter out [`Index`] entries that are shadowed by an earlier entry with the same name.
+///
+/// Indexes are ordered by precedence, e.g., project-level definitions precede user-level
+/// definitions, so the first definition of each name takes effect. Unnamed indexes are never
+/// shadowed.
+pub fn unique_indexes<'a>(
+    indexes: impl IntoIterator<Item = &'a Index>,
+) -> impl Iterator<Item = &'a Index> {
+    let mut seen = FxHashSet::default();
+    indexes
+        .into_iter()
+        .filter(move |index| index.name.as_ref().is_none_or(|name| seen.insert(name)))
+}
+
+/// Return the [`Index`] entries that are shadowed by an earlier entry with the same name, as
+/// pairs of the shadowed entry and the entry that takes effect instead.
+///
+/// See [`unique_indexes`] for the entries that take effect.
+pub fn shadowed_indexes<'a>(
+    indexes: impl IntoIterator<Item = &'a Index>,
+) -> impl Iterator<Item = (&'a Index, &'a Index)> {
+    let mut effective = FxHashMap::default();
+    indexes.into_iter().filter_map(move |index| {
+        let name = index.name.as_ref()?;
+        match effective.entry(name) {
+            std::collections::hash_map::Entry::Occupied(entry) => Some((index, *entry.get())),
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                entry.insert(index);
+                None
+            }
+        }
+    })
+}
+
 bitflags::bitflags! {
     #[derive(Debug, Copy, Clone)]
     struct Flags: u8 {
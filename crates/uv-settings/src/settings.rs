@@ -5,8 +5,8 @@ use url::Url;
 
 use uv_cache_info::CacheKey;
 use uv_configuration::{
-    ConfigSettings, IndexStrategy, KeyringProviderType, PackageNameSpecifier, RequiredVersion,
-    TargetTriple, TrustedHost, TrustedPublishing,
+    ConfigSettings, IndexStrategy, KeyringCompatType, KeyringProviderType, PackageNameSpecifier,
+    RequiredVersion, TargetTriple, TrustedHost, TrustedPublishing,
 };
 use uv_distribution_types::{
     Index, IndexUrl, IndexUrlError, PipExtraIndex, PipFindLinks, PipIndex, StaticMetadata,
@@ -307,6 +307,51 @@ pub struct GlobalOptions {
         "#
     )]
     pub allow_insecure_host: Option<Vec<TrustedHost>>,
+    /// The hosts that the keyring may be queried for credentials.
+    ///
+    /// Expects a list of hostnames (e.g., `pypi.example.com`), or wildcards for all subdomains of
+    /// a domain (e.g., `*.example.com`), optionally followed by a port (e.g., `localhost:8080`).
+    /// When set, the keyring is not queried for any other host, so that credentials are never sent
+    /// to an unexpected host, e.g., a lookalike of an internal index.
+    ///
+    /// Can be overridden with `UV_KEYRING_ALLOW_HOSTS`.
+    #[option(
+        default = "None",
+        value_type = "list[str]",
+        example = r#"
+            keyring-allow-hosts = ["*.corp.example.com"]
+        "#
+    )]
+    pub keyring_allow_hosts: Option<Vec<String>>,
+    /// The hosts that the keyring is never queried for credentials.
+    ///
+    /// Accepts the same patterns as `keyring-allow-hosts`, and takes precedence over it.
+    ///
+    /// Can be overridden with `UV_KEYRING_DENY_HOSTS`.
+    #[option(
+        default = "[]",
+        value_type = "list[str]",
+        example = r#"
+            keyring-deny-hosts = ["public.corp.example.com"]
+        "#
+    )]
+    pub keyring_deny_hosts: Option<Vec<String>>,
+    /// Other tools whose keyring entries are checked for credentials.
+    ///
+    /// When no credentials are stored in the keyring for the URL of an index, the entries that
+    /// these tools store for the index are checked: `twine` stores credentials under the upload
+    /// URL of the index (its `publish-url`), and `poetry` stores credentials under
+    /// `poetry-repository-<name>`, with the name of the index.
+    ///
+    /// By default, only the entries of uv are checked.
+    #[option(
+        default = "[]",
+        value_type = "list[str]",
+        example = r#"
+            keyring-compat = ["twine", "poetry"]
+        "#
+    )]
+    pub keyring_compat: Option<Vec<KeyringCompatType>>,
 }
 
 /// Settings relevant to all installer operations.
@@ -485,8 +530,9 @@ pub struct ResolverInstallerOptions {
     pub index_strategy: Option<IndexStrategy>,
     /// Attempt to use `keyring` for authentication for index URLs.
     ///
-    /// At present, only `--keyring-provider subprocess` is supported, which configures uv to
-    /// use the `keyring` CLI to handle authentication.
+    /// `--keyring-provider subprocess` configures uv to use the `keyring` CLI to handle
+    /// authentication, and `--keyring-provider native` configures uv to use the credential store of
+    /// the operating system directly (if uv was built with the `native-keyring` feature).
     #[option(
         default = "\"disabled\"",
         value_type = "str",
@@ -995,8 +1041,9 @@ pub struct PipOptions {
     pub index_strategy: Option<IndexStrategy>,
     /// Attempt to use `keyring` for authentication for index URLs.
     ///
-    /// At present, only `--keyring-provider subprocess` is supported, which configures uv to
-    /// use the `keyring` CLI to handle authentication.
+    /// `--keyring-provider subprocess` configures uv to use the `keyring` CLI to handle
+    /// authentication, and `--keyring-provider native` configures uv to use the credential store of
+    /// the operating system directly (if uv was built with the `native-keyring` feature).
     #[option(
         default = "disabled",
         value_type = "str",
@@ -1745,6 +1792,9 @@ pub struct OptionsWire {
     concurrent_downloads: Option<NonZeroUsize>,
     concurrent_builds: Option<NonZeroUsize>,
     concurrent_installs: Option<NonZeroUsize>,
+    keyring_allow_hosts: Option<Vec<String>>,
+    keyring_deny_hosts: Option<Vec<String>>,
+    keyring_compat: Option<Vec<KeyringCompatType>>,
 
     // #[serde(flatten)]
     // top_level: ResolverInstallerOptions
@@ -1831,6 +1881,9 @@ impl From<OptionsWire> for Options {
             concurrent_downloads,
             concurrent_builds,
             concurrent_installs,
+            keyring_allow_hosts,
+            keyring_deny_hosts,
+            keyring_compat,
             index,
             index_url,
             extra_index_url,
@@ -1894,6 +1947,9 @@ impl From<OptionsWire> for Options {
                 concurrent_installs,
                 // Used twice for backwards compatibility
                 allow_insecure_host: allow_insecure_host.clone(),
+                keyring_allow_hosts,
+                keyring_deny_hosts,
+                keyring_compat,
             },
             top_level: ResolverInstallerOptions {
                 index,
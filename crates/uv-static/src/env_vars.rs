@@ -127,6 +127,63 @@ impl EnvVars {
     /// will use this value as the keyring provider.
     pub const UV_KEYRING_PROVIDER: &'static str = "UV_KEYRING_PROVIDER";
 
+    /// The command to run in place of `keyring` with the `subprocess` keyring provider, e.g.,
+    /// `python3 -m keyring`. The command is split into arguments like a POSIX shell would, so
+    /// arguments containing spaces can be quoted.
+    pub const UV_KEYRING_COMMAND: &'static str = "UV_KEYRING_COMMAND";
+
+    /// If set to `1`, the `keyring` command inherits `PYTHONHOME` and `PYTHONPATH` from uv's
+    /// environment, which are otherwise removed so that the interpreter of an activated project
+    /// environment can't break the `keyring` CLI.
+    pub const UV_KEYRING_INHERIT_ENV: &'static str = "UV_KEYRING_INHERIT_ENV";
+
+    /// The hosts to query the keyring for, separated by commas or whitespace, e.g.,
+    /// `*.corp.example.com,localhost:8080`. The keyring is not queried for other hosts. Takes
+    /// precedence over the `keyring-allow-hosts` setting.
+    pub const UV_KEYRING_ALLOW_HOSTS: &'static str = "UV_KEYRING_ALLOW_HOSTS";
+
+    /// The hosts to never query the keyring for, separated by commas or whitespace, e.g.,
+    /// `public.corp.example.com`. Takes precedence over the `keyring-deny-hosts` setting.
+    pub const UV_KEYRING_DENY_HOSTS: &'static str = "UV_KEYRING_DENY_HOSTS";
+
+    /// The 1Password vault to read and write items in with the `op` keyring provider. By default,
+    /// items are read from any vault the `op` CLI has access to, and created in its default vault.
+    pub const UV_OP_VAULT: &'static str = "UV_OP_VAULT";
+
+    /// The title of the 1Password item for a keyring entry with the `op` keyring provider, in
+    /// which `{service}` is replaced by the service name (e.g., `https://example.com`) and
+    /// `{username}` by the username. Defaults to `{service}`.
+    pub const UV_OP_ITEM_TEMPLATE: &'static str = "UV_OP_ITEM_TEMPLATE";
+
+    /// The mount of the KV v2 secrets engine to read secrets from with the `vault` keyring
+    /// provider. Defaults to `secret`.
+    pub const UV_VAULT_MOUNT: &'static str = "UV_VAULT_MOUNT";
+
+    /// The path of the Vault secret for a keyring entry with the `vault` keyring provider, within
+    /// the mount, in which `{service}` is replaced by the service name with `/` after the scheme
+    /// (e.g., `https/example.com`) and `{username}` by the username. Defaults to `uv/{service}`.
+    pub const UV_VAULT_PATH_TEMPLATE: &'static str = "UV_VAULT_PATH_TEMPLATE";
+
+    /// Equivalent to the `--auth-config` command-line argument. Expects a path to the file that
+    /// uv records the usernames and settings of index credentials in, in place of `auth.toml` in
+    /// the user state directory.
+    pub const UV_AUTH_CONFIG_FILE: &'static str = "UV_AUTH_CONFIG_FILE";
+
+    /// The password to store with `uv index credentials set`, for CI systems that can only pass
+    /// secrets in the environment. Used only without `--password` and `--password-stdin`, which it
+    /// can't be combined with. The `keyring` command doesn't inherit it.
+    pub const UV_ADD_CREDENTIALS_PASSWORD: &'static str = "UV_ADD_CREDENTIALS_PASSWORD";
+
+    /// Enables the audit log of operations on credentials. Expects a path to a file that uv
+    /// appends a JSON line to for each password it reads, stores, or removes in the keyring, and
+    /// for each change to the credentials of an index. Passwords are never recorded.
+    pub const UV_AUTH_AUDIT_LOG: &'static str = "UV_AUTH_AUDIT_LOG";
+
+    /// Set to `1` to record which credentials fetched each entry of the cache, for `uv auth
+    /// provenance` and `uv cache prune --credential`. Only the realm, the index name, the kind of
+    /// source, and a fingerprint of the password are recorded, in `provenance.jsonl` in the cache.
+    pub const UV_AUTH_PROVENANCE: &'static str = "UV_AUTH_PROVENANCE";
+
     /// Equivalent to the `--config-file` command-line argument. Expects a path to a
     /// local `uv.toml` file to use as the configuration file.
     pub const UV_CONFIG_FILE: &'static str = "UV_CONFIG_FILE";
@@ -443,6 +500,14 @@ impl EnvVars {
     /// The standard `SHELL` posix env var.
     pub const SHELL: &'static str = "SHELL";
 
+    /// The standard `USER` posix env var, used as a candidate username when discovering
+    /// credentials in the keyring.
+    pub const USER: &'static str = "USER";
+
+    /// The standard `USERNAME` Windows env var, used as a candidate username when discovering
+    /// credentials in the keyring.
+    pub const USERNAME: &'static str = "USERNAME";
+
     /// The standard `PWD` posix env var.
     pub const PWD: &'static str = "PWD";
 
@@ -478,6 +543,16 @@ impl EnvVars {
     #[attr_hidden]
     pub const GIT_TERMINAL_PROMPT: &'static str = "GIT_TERMINAL_PROMPT";
 
+    /// The program git runs to prompt for credentials, e.g., in a GUI. Removed when running
+    /// `git credential` for the `git` keyring provider.
+    #[attr_hidden]
+    pub const GIT_ASKPASS: &'static str = "GIT_ASKPASS";
+
+    /// The program git and SSH run to prompt for credentials, if `GIT_ASKPASS` is not set.
+    /// Removed when running `git credential` for the `git` keyring provider.
+    #[attr_hidden]
+    pub const SSH_ASKPASS: &'static str = "SSH_ASKPASS";
+
     /// Used in tests for better git isolation.
     ///
     /// For example, we run some tests in ~/.local/share/uv/tests.
@@ -513,6 +588,10 @@ impl EnvVars {
     /// Adds directories to Python module search path (e.g., `PYTHONPATH=/path/to/modules`).
     pub const PYTHONPATH: &'static str = "PYTHONPATH";
 
+    /// Sets the location of the standard Python libraries.
+    #[attr_hidden]
+    pub const PYTHONHOME: &'static str = "PYTHONHOME";
+
     /// Used in tests to enforce a consistent locale setting.
     #[attr_hidden]
     pub const LC_ALL: &'static str = "LC_ALL";
@@ -524,6 +603,12 @@ impl EnvVars {
     /// Use to set the .netrc file location.
     pub const NETRC: &'static str = "NETRC";
 
+    /// The address of the Vault server, used by the `vault` keyring provider.
+    pub const VAULT_ADDR: &'static str = "VAULT_ADDR";
+
+    /// The token to authenticate to Vault with, used by the `vault` keyring provider.
+    pub const VAULT_TOKEN: &'static str = "VAULT_TOKEN";
+
     /// The standard `PAGER` posix env var. Used by `uv` to configure the appropriate pager.
     pub const PAGER: &'static str = "PAGER";
 
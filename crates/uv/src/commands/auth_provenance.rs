@@ -0,0 +1,128 @@
+use std::fmt::Write;
+
+use anyhow::{Context, Result};
+use owo_colors::OwoColorize;
+use url::Url;
+
+use uv_auth::ProvenanceRecord;
+use uv_cache::Cache;
+use uv_distribution_filename::DistFilename;
+use uv_fs::Simplified;
+use uv_normalize::PackageName;
+
+use crate::commands::ExitStatus;
+use crate::printer::Printer;
+
+/// Show which credentials fetched the cached distributions and index pages of a package, as
+/// recorded in the provenance log of the cache.
+pub(crate) fn auth_provenance(
+    package: &PackageName,
+    cache: &Cache,
+    printer: Printer,
+) -> Result<ExitStatus> {
+    let log = cache.provenance_log();
+    let records = uv_auth::read_provenance_log(&log).with_context(|| {
+        format!(
+            "Failed to read the provenance log at: {}",
+            log.user_display()
+        )
+    })?;
+
+    // Skip the entries that were removed from the cache since, and report each set of credentials
+    // once per artifact.
+    let mut reported: Vec<(String, &ProvenanceRecord)> = Vec::new();
+    for record in &records {
+        let Some(artifact) = artifact(&record.url, package) else {
+            continue;
+        };
+        if !record.entry.exists() {
+            continue;
+        }
+        if reported.iter().any(|(reported, other)| {
+            *reported == artifact && other.credentials == record.credentials
+        }) {
+            continue;
+        }
+        reported.push((artifact, record));
+    }
+
+    if reported.is_empty() {
+        writeln!(
+            printer.stderr(),
+            "No credentials were recorded for the cached artifacts of `{package}`"
+        )?;
+        return Ok(ExitStatus::Success);
+    }
+
+    for (artifact, record) in reported {
+        let credentials = &record.credentials;
+        let target = if let Some(index) = &credentials.index {
+            format!("index `{index}` ({})", credentials.realm)
+        } else {
+            credentials.realm.clone()
+        };
+        writeln!(
+            printer.stdout(),
+            "{}: {} ({}) on {target}",
+            artifact.bold(),
+            credentials.fingerprint.cyan(),
+            credentials.source
+        )?;
+    }
+
+    Ok(ExitStatus::Success)
+}
+
+/// The artifact of the package that a URL refers to: the filename of a distribution, or the URL
+/// of the index page of the package, e.g., `https://example.com/simple/anyio/`.
+fn artifact(url: &str, package: &PackageName) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+    let segment = url
+        .path_segments()?
+        .rev()
+        .find(|segment| !segment.is_empty())?;
+    // The metadata of a wheel, see PEP 658.
+    let filename = segment.strip_suffix(".metadata").unwrap_or(segment);
+    if let Some(filename) = DistFilename::try_from_filename(filename, package) {
+        return (filename.name() == package).then(|| segment.to_string());
+    }
+    let name = segment.parse::<PackageName>().ok()?;
+    (name == *package).then(|| url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn artifacts() {
+        let package = "anyio".parse::<PackageName>().unwrap();
+        for (url, expected) in [
+            (
+                "https://example.com/files/anyio-4.3.0-py3-none-any.whl",
+                Some("anyio-4.3.0-py3-none-any.whl"),
+            ),
+            (
+                "https://example.com/files/anyio-4.3.0-py3-none-any.whl.metadata",
+                Some("anyio-4.3.0-py3-none-any.whl.metadata"),
+            ),
+            (
+                "https://example.com/files/anyio-4.3.0.tar.gz",
+                Some("anyio-4.3.0.tar.gz"),
+            ),
+            (
+                "https://example.com/simple/anyio/",
+                Some("https://example.com/simple/anyio/"),
+            ),
+            (
+                "https://example.com/simple/AnyIO",
+                Some("https://example.com/simple/AnyIO"),
+            ),
+            ("https://example.com/files/idna-3.6-py3-none-any.whl", None),
+            ("https://example.com/simple/idna/", None),
+            ("https://example.com/simple/", None),
+        ] {
+            assert_eq!(artifact(url, &package).as_deref(), expected, "{url}");
+        }
+    }
+}
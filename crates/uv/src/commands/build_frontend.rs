@@ -523,6 +523,8 @@ async fn build_package(
         .index_urls(index_locations.index_urls())
         .index_strategy(index_strategy)
         .keyring(keyring_provider)
+        .keyring_from_cli(network_settings.keyring_provider_from_cli)
+        .keyring_metrics(network_settings.keyring_metrics.clone())
         .allow_insecure_host(network_settings.allow_insecure_host.clone())
         .markers(interpreter.markers())
         .platform(interpreter.platform())
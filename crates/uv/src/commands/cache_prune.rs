@@ -2,15 +2,25 @@ use std::fmt::Write;
 
 use anyhow::{Context, Result};
 use owo_colors::OwoColorize;
+use tracing::debug;
 
-use uv_cache::{Cache, Removal};
+use uv_cache::{rm_rf, Cache, Removal};
 use uv_fs::Simplified;
+use uv_warnings::warn_user;
 
 use crate::commands::{human_readable_bytes, ExitStatus};
 use crate::printer::Printer;
 
 /// Prune all unreachable objects from the cache.
-pub(crate) fn cache_prune(ci: bool, cache: &Cache, printer: Printer) -> Result<ExitStatus> {
+///
+/// With a credential fingerprint, the entries that were fetched with the credentials are removed
+/// first, so that the objects only they referenced are pruned too.
+pub(crate) fn cache_prune(
+    ci: bool,
+    credential: Option<&str>,
+    cache: &Cache,
+    printer: Printer,
+) -> Result<ExitStatus> {
     if !cache.root().exists() {
         writeln!(
             printer.stderr(),
@@ -28,6 +38,17 @@ pub(crate) fn cache_prune(ci: bool, cache: &Cache, printer: Printer) -> Result<E
 
     let mut summary = Removal::default();
 
+    // Remove the entries that were fetched with the credentials.
+    if let Some(fingerprint) = credential {
+        let (entries, removal) = prune_credential(fingerprint, cache).with_context(|| {
+            format!("Failed to prune cache at: {}", cache.root().user_display())
+        })?;
+        if entries == 0 {
+            warn_user!("No cache entries were recorded as fetched with `{fingerprint}`");
+        }
+        summary += removal;
+    }
+
     // Prune the source distribution cache, which is tightly coupled to the builder crate.
     summary += uv_distribution::prune(cache)
         .with_context(|| format!("Failed to prune cache at: {}", cache.root().user_display()))?;
@@ -71,3 +92,30 @@ pub(crate) fn cache_prune(ci: bool, cache: &Cache, printer: Printer) -> Result<E
 
     Ok(ExitStatus::Success)
 }
+
+/// Remove the cache entries that the provenance log records as fetched with the credentials of the
+/// given fingerprint, and drop their records from the log.
+///
+/// Returns the number of entries that were recorded for the credentials.
+fn prune_credential(fingerprint: &str, cache: &Cache) -> std::io::Result<(usize, Removal)> {
+    let log = cache.provenance_log();
+    let (records, kept): (Vec<_>, Vec<_>) = uv_auth::read_provenance_log(&log)?
+        .into_iter()
+        .partition(|record| record.credentials.fingerprint == fingerprint);
+    if records.is_empty() {
+        return Ok((0, Removal::default()));
+    }
+
+    let root = std::path::absolute(cache.root())?;
+    let mut summary = Removal::default();
+    for record in &records {
+        // Never remove anything outside of the cache, e.g., if the log was edited.
+        if record.entry.starts_with(&root) {
+            debug!("Removing cache entry: {}", record.entry.display());
+            summary += rm_rf(&record.entry)?;
+        }
+    }
+    uv_auth::write_provenance_log(&log, &kept)?;
+
+    Ok((records.len(), summary))
+}
@@ -0,0 +1,62 @@
+use std::io;
+
+use clap_complete_command::Shell;
+
+/// Completes `--name` in `uv index credentials`, and the index of `uv auth login` and
+/// `uv auth logout`, on top of the `_uv` function generated by clap.
+const BASH: &str = r#"
+_uv_index_names() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" prev="${COMP_WORDS[COMP_CWORD-1]}"
+    if [[ " ${COMP_WORDS[*]} " == *" credentials "* && "${prev}" == "--name" ]] \
+        || [[ " ${COMP_WORDS[*]} " == *" auth "* && ( "${prev}" == "login" || "${prev}" == "logout" ) && "${cur}" != -* ]]; then
+        COMPREPLY=($(compgen -W "$("${COMP_WORDS[0]}" index names 2>/dev/null)" -- "${cur}"))
+        return 0
+    fi
+    _uv "$@"
+}
+
+if [[ "${BASH_VERSINFO[0]}" -eq 4 && "${BASH_VERSINFO[1]}" -ge 4 || "${BASH_VERSINFO[0]}" -gt 4 ]]; then
+    complete -F _uv_index_names -o nosort -o bashdefault -o default uv
+else
+    complete -F _uv_index_names -o bashdefault -o default uv
+fi
+"#;
+
+/// Like [`BASH`], registered only when the script is sourced rather than autoloaded as `_uv`.
+const ZSH: &str = r#"
+_uv_index_names() {
+    if [[ ${words[(I)credentials]} -gt 0 && ${words[CURRENT-1]} == --name ]] \
+        || [[ ${words[(I)auth]} -gt 0 && ${words[CURRENT-1]} == (login|logout) && ${words[CURRENT]} != -* ]]; then
+        local -a names
+        names=(${(f)"$(${words[1]} index names 2>/dev/null)"})
+        compadd -a names
+        return
+    fi
+    _uv "$@"
+}
+
+if [ "$funcstack[1]" != "_uv" ]; then
+    compdef _uv_index_names uv
+fi
+"#;
+
+/// Adds the index names to the candidates generated by clap.
+const FISH: &str = r#"
+complete -c uv -n "__fish_seen_subcommand_from credentials; and __fish_seen_subcommand_from set rotate unset check" -l name -f -a "(uv index names 2>/dev/null)"
+complete -c uv -n "__fish_seen_subcommand_from auth; and __fish_seen_subcommand_from login logout" -f -a "(uv index names 2>/dev/null)"
+"#;
+
+/// Write the completion of index names for the given shell, to follow the script generated by
+/// clap.
+///
+/// The names are read with the hidden `uv index names` command, which doesn't access the keyring.
+/// Outside a project, or if the configuration can't be read, no names are completed.
+pub(crate) fn index_name_completion(shell: Shell, writer: &mut impl io::Write) -> io::Result<()> {
+    let script = match shell {
+        Shell::Bash => BASH,
+        Shell::Zsh => ZSH,
+        Shell::Fish => FISH,
+        _ => return Ok(()),
+    };
+    writer.write_all(script.as_bytes())
+}
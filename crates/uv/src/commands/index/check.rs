@@ -0,0 +1,361 @@
+use std::fmt::Write;
+
+use anyhow::Result;
+use futures::StreamExt;
+use owo_colors::OwoColorize;
+use reqwest::StatusCode;
+use reqwest_middleware::ClientWithMiddleware;
+use serde::Serialize;
+use tracing::{debug, warn};
+use url::Url;
+
+use uv_auth::{AuthConfig, KeyringProvider, NetrcProvider, SecretString};
+use uv_cli::IndexCredentialsFormat;
+use uv_client::{AuthIntegration, BaseClientBuilder};
+use uv_distribution_types::{unique_indexes, Index};
+
+use crate::commands::index::keyring::{
+    fetch_configured_credentials, IndexKeyrings, KEYRING_CONCURRENCY,
+};
+use crate::commands::index::target::select_index;
+use crate::commands::ExitStatus;
+use crate::printer::Printer;
+use crate::settings::{IndexCheckCredentialsSettings, NetworkSettings};
+
+/// Check that the stored credentials for one or all indexes are accepted by the index.
+///
+/// With [`IndexCredentialsFormat::Json`], the result of each check is written to stdout as a
+/// [`CheckedIndex`]. Returns a failure if any checked index rejects its credentials or cannot be
+/// reached.
+pub(crate) async fn check_credentials(
+    settings: IndexCheckCredentialsSettings,
+    network_settings: &NetworkSettings,
+    printer: Printer,
+) -> Result<ExitStatus> {
+    let IndexCheckCredentialsSettings {
+        name,
+        format,
+        keyring_timeout,
+        keyring_provider,
+        default_keyring_provider,
+        index: indexes,
+    } = settings;
+    let effective = if let Some(name) = name {
+        vec![select_index(&indexes, &name)?]
+    } else {
+        if indexes.is_empty() {
+            writeln!(printer.stderr(), "No indexes configured.")?;
+            if matches!(format, IndexCredentialsFormat::Json) {
+                writeln!(printer.stdout(), "[]")?;
+            }
+            return Ok(ExitStatus::Success);
+        }
+        unique_indexes(&indexes).collect()
+    };
+
+    let auth_config =
+        AuthConfig::load().inspect_err(|err| warn!("Could not load auth config due to: {err}"))?;
+    let keyrings = IndexKeyrings::new(
+        keyring_provider,
+        default_keyring_provider,
+        keyring_timeout,
+        &network_settings.keyring_metrics,
+        &effective,
+        &auth_config,
+    );
+    let netrc = NetrcProvider::load();
+
+    // Only attach the credentials from the environment, the keyring, or a netrc file, rather than
+    // any from the URL.
+    let client = BaseClientBuilder::new()
+        .connectivity(network_settings.connectivity)
+        .native_tls(network_settings.native_tls)
+        .allow_insecure_host(network_settings.allow_insecure_host.clone())
+        .auth_integration(AuthIntegration::NoAuthMiddleware)
+        .build();
+
+    let checks = futures::stream::iter(effective.iter().map(|index| {
+        let keyring_provider = keyrings.for_index(index, &auth_config);
+        check_configured_index(
+            client.for_host(index.raw_url()),
+            index,
+            &auth_config,
+            keyring_provider,
+            netrc.as_ref(),
+        )
+    }))
+    .buffered(KEYRING_CONCURRENCY)
+    .collect::<Vec<_>>()
+    .await;
+
+    let mut failed = false;
+    let mut checked = Vec::new();
+    for (index, check) in effective.iter().zip(checks) {
+        let index_name = index
+            .name
+            .as_ref()
+            .expect("Index should have a name")
+            .to_string();
+        failed |= check.is_failure();
+        match format {
+            IndexCredentialsFormat::Text => writeln!(
+                printer.stderr(),
+                "{} {}",
+                format!("Index: {index_name}").bold(),
+                check.message()
+            )?,
+            IndexCredentialsFormat::Json => checked.push(CheckedIndex {
+                index: index_name,
+                url: index.url.redacted().to_string(),
+                result: check.code(),
+                message: check.message(),
+            }),
+        }
+    }
+    if matches!(format, IndexCredentialsFormat::Json) {
+        writeln!(printer.stdout(), "{}", serde_json::to_string(&checked)?)?;
+    }
+
+    if failed {
+        Ok(ExitStatus::Failure)
+    } else {
+        Ok(ExitStatus::Success)
+    }
+}
+
+/// The result of checking the stored credentials for an index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum CredentialCheck {
+    /// The index has no entry in the auth config, and was not checked.
+    NotConfigured,
+    /// The index has an entry in the auth config, but no password in the keyring.
+    Missing,
+    /// The keyring could not be queried.
+    Unavailable { reason: String },
+    /// The index accepted the credentials.
+    Accepted,
+    /// The index rejected the credentials.
+    Rejected { status: StatusCode },
+    /// The index responded with an unexpected status.
+    UnexpectedStatus { status: StatusCode },
+    /// The index could not be reached.
+    Unreachable { reason: String },
+}
+
+impl CredentialCheck {
+    /// Whether the check should fail the command.
+    pub(super) fn is_failure(&self) -> bool {
+        !matches!(self, Self::NotConfigured | Self::Accepted)
+    }
+
+    /// The stable code of the result, for `--output-format json`.
+    fn code(&self) -> &'static str {
+        match self {
+            Self::NotConfigured => "not-configured",
+            Self::Missing => "missing",
+            Self::Unavailable { .. } => "keyring-unavailable",
+            Self::Accepted => "accepted",
+            Self::Rejected { .. } => "rejected",
+            Self::UnexpectedStatus { .. } => "unexpected-status",
+            Self::Unreachable { .. } => "unreachable",
+        }
+    }
+
+    pub(super) fn message(&self) -> String {
+        match self {
+            Self::NotConfigured => "has no credentials configured".to_string(),
+            Self::Missing => "has no credentials in the keyring".to_string(),
+            Self::Unavailable { reason } => {
+                format!("cannot be checked, keyring unavailable: {reason}")
+            }
+            Self::Accepted => "accepted the credentials".to_string(),
+            Self::Rejected { status } => format!("rejected the credentials ({status})"),
+            Self::UnexpectedStatus { status } => {
+                format!("responded with an unexpected status ({status})")
+            }
+            Self::Unreachable { reason } => format!("could not be reached: {reason}"),
+        }
+    }
+}
+
+/// The result of checking the credentials of an index, as written with `--output-format json`.
+#[derive(Debug, Serialize)]
+struct CheckedIndex {
+    index: String,
+    url: String,
+    /// The [`CredentialCheck::code`] of the result.
+    result: &'static str,
+    message: String,
+}
+
+/// Check the credentials of the entry in the auth config for an index, or those in the
+/// environment or a netrc file, against the index, see [`check_index`].
+pub(super) async fn check_configured_index(
+    client: &ClientWithMiddleware,
+    index: &Index,
+    auth_config: &AuthConfig,
+    keyring_provider: Option<&KeyringProvider>,
+    netrc: Option<&NetrcProvider>,
+) -> CredentialCheck {
+    let url = index.raw_url();
+    let usernames = auth_config
+        .find_entry(url)
+        .map(|entry| entry.usernames.clone())
+        .unwrap_or_default();
+    match fetch_configured_credentials(index, &usernames, keyring_provider, netrc).await {
+        Ok(Some((credentials, _))) => {
+            check_index(
+                client,
+                url,
+                credentials.username().unwrap_or_default(),
+                credentials.password().map(SecretString::expose),
+            )
+            .await
+        }
+        Ok(None) if !usernames.is_empty() => CredentialCheck::Missing,
+        Ok(None) => CredentialCheck::NotConfigured,
+        Err(err) => CredentialCheck::Unavailable {
+            reason: err.to_string(),
+        },
+    }
+}
+
+/// Request the root of the index with the given credentials, and classify the response.
+async fn check_index(
+    client: &ClientWithMiddleware,
+    url: &Url,
+    username: &str,
+    password: Option<&str>,
+) -> CredentialCheck {
+    debug!("Checking credentials for {username}@{url}");
+    let response = client
+        .get(url.clone())
+        .basic_auth(username, password)
+        .send()
+        .await;
+    classify_response(response)
+}
+
+/// Request the root of the index without credentials, to detect whether it requires any.
+///
+/// The response is classified as for [`check_index`], i.e., [`CredentialCheck::Accepted`] if the
+/// index can be read without credentials.
+pub(super) async fn probe_index(client: &ClientWithMiddleware, url: &Url) -> CredentialCheck {
+    debug!("Checking whether {url} requires credentials");
+    classify_response(client.get(url.clone()).send().await)
+}
+
+/// Classify the response of an index to a request for its root.
+fn classify_response(
+    response: Result<reqwest::Response, reqwest_middleware::Error>,
+) -> CredentialCheck {
+    match response {
+        Ok(response) if response.status().is_success() => CredentialCheck::Accepted,
+        Ok(response)
+            if matches!(
+                response.status(),
+                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+            ) =>
+        {
+            CredentialCheck::Rejected {
+                status: response.status(),
+            }
+        }
+        Ok(response) => CredentialCheck::UnexpectedStatus {
+            status: response.status(),
+        },
+        Err(err) => CredentialCheck::Unreachable {
+            reason: err.to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn check_index_responses() {
+        use wiremock::matchers::{basic_auth, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(basic_auth("user", "password"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let client = ClientWithMiddleware::from(reqwest::Client::new());
+        let url = Url::parse(&format!("{}/simple/", server.uri())).unwrap();
+        let check = check_index(&client, &url, "user", Some("password")).await;
+        assert_eq!(check, CredentialCheck::Accepted);
+        assert!(!check.is_failure());
+
+        let check = check_index(&client, &url, "user", Some("wrong")).await;
+        assert_eq!(
+            check,
+            CredentialCheck::Rejected {
+                status: StatusCode::UNAUTHORIZED
+            }
+        );
+        assert!(check.is_failure());
+        assert_eq!(
+            check.message(),
+            "rejected the credentials (401 Unauthorized)"
+        );
+    }
+
+    #[tokio::test]
+    async fn check_index_unreachable() {
+        // Reserve a port, then close it, so that nothing is listening.
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let client = ClientWithMiddleware::from(reqwest::Client::new());
+        let url = Url::parse(&format!("http://127.0.0.1:{port}/simple/")).unwrap();
+        let check = check_index(&client, &url, "user", Some("password")).await;
+        assert!(
+            matches!(check, CredentialCheck::Unreachable { .. }),
+            "{check:?}"
+        );
+        assert!(check.is_failure());
+    }
+
+    #[tokio::test]
+    async fn probe_index_responses() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let client = ClientWithMiddleware::from(reqwest::Client::new());
+        for (status, expected) in [
+            (200, CredentialCheck::Accepted),
+            (
+                401,
+                CredentialCheck::Rejected {
+                    status: StatusCode::UNAUTHORIZED,
+                },
+            ),
+            (
+                404,
+                CredentialCheck::UnexpectedStatus {
+                    status: StatusCode::NOT_FOUND,
+                },
+            ),
+        ] {
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .respond_with(ResponseTemplate::new(status))
+                .mount(&server)
+                .await;
+            let url = Url::parse(&format!("{}/simple/", server.uri())).unwrap();
+            assert_eq!(probe_index(&client, &url).await, expected);
+        }
+    }
+}
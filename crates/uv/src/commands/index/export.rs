@@ -0,0 +1,23 @@
+use std::fmt::Write;
+
+use anyhow::Result;
+use tracing::warn;
+
+use uv_auth::AuthConfig;
+
+use crate::printer::Printer;
+
+/// Write the entries of the auth config as JSON to stdout, to carry them over to another machine
+/// with [`import_credentials`](super::import::import_credentials).
+///
+/// Passwords are stored in the keyring, never in the auth config, so they're not exported.
+pub(crate) fn export_credentials(printer: Printer) -> Result<()> {
+    let auth_config =
+        AuthConfig::load().inspect_err(|err| warn!("Could not load auth config due to: {err}"))?;
+    writeln!(
+        printer.stdout(),
+        "{}",
+        serde_json::to_string_pretty(&auth_config.export())?
+    )?;
+    Ok(())
+}
@@ -0,0 +1,317 @@
+use std::fmt::Write;
+use std::io::Read;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use tracing::{debug, warn};
+use url::Url;
+
+use uv_auth::{
+    AuthConfig, AuthConfigEntry, AuthConfigEntryKind, AuthConfigExport, AuthScheme, ImportOutcome,
+    ImportedEntry, KeyringMetrics, SecretString, AUTH_CONFIG_EXPORT_VERSION,
+};
+use uv_warnings::warn_user;
+
+use crate::commands::index::keyring::IndexKeyrings;
+use crate::commands::index::prompt::{confirm_password, ProcessTerminals};
+use crate::commands::index::{format_usernames, TOKEN_USERNAME};
+use crate::printer::Printer;
+use crate::settings::IndexImportCredentialsSettings;
+
+/// Merge the entries written by [`export_credentials`](super::export::export_credentials) into
+/// the auth config, reading them from `file`, or stdin if omitted or `-`.
+///
+/// Unless `skip_passwords` is set, the user is then prompted for the password of each username of
+/// the entries that were added or replaced, which is stored in the keyring. The terminal is checked
+/// before the auth config is changed.
+pub(crate) async fn import_credentials(
+    settings: IndexImportCredentialsSettings,
+    keyring_metrics: &Arc<KeyringMetrics>,
+    printer: Printer,
+) -> Result<()> {
+    let IndexImportCredentialsSettings {
+        file,
+        overwrite,
+        skip_passwords,
+        keyring_timeout,
+        keyring_provider,
+        default_keyring_provider,
+    } = settings;
+    let contents = if let Some(file) = file.filter(|file| file.as_os_str() != "-") {
+        fs_err::read_to_string(file)?
+    } else {
+        let mut contents = String::new();
+        std::io::stdin()
+            .read_to_string(&mut contents)
+            .context("Failed to read the credentials to import from stdin")?;
+        contents
+    };
+    let export = parse_credentials_export(&contents)?;
+
+    let term = if skip_passwords {
+        None
+    } else {
+        let Some(term) = ProcessTerminals.prompt_term() else {
+            bail!(
+                "No terminal to prompt for the passwords of the imported entries; use `--skip-passwords` and set them with `uv index credentials set`"
+            );
+        };
+        Some(term)
+    };
+
+    let imported = AuthConfig::update(|auth_config| auth_config.import(export, overwrite))
+        .inspect_err(|err| warn!("Could not save auth config due to: {err}"))?;
+    let auth_config =
+        AuthConfig::load().inspect_err(|err| warn!("Could not load auth config due to: {err}"))?;
+    report_imported_entries(&auth_config, &imported, printer)?;
+
+    let Some(term) = term else {
+        return Ok(());
+    };
+    let mut keyrings = IndexKeyrings::new(
+        keyring_provider,
+        default_keyring_provider,
+        keyring_timeout,
+        keyring_metrics,
+        &[],
+        &auth_config,
+    );
+    store_imported_passwords(
+        &auth_config,
+        &imported,
+        &mut keyrings,
+        |secret| {
+            confirm_password(secret, |prompt| {
+                term.password(prompt).context("Failed to read password")
+            })
+        },
+        printer,
+    )
+    .await
+}
+
+/// Parse the entries written by [`export_credentials`](super::export::export_credentials).
+fn parse_credentials_export(contents: &str) -> Result<AuthConfigExport> {
+    let export: AuthConfigExport = serde_json::from_str(contents).context(
+        "Failed to parse the credentials to import; expected the output of `uv index credentials export`",
+    )?;
+    if export.version != AUTH_CONFIG_EXPORT_VERSION {
+        bail!(
+            "Unsupported version {} of the credentials to import; expected version {AUTH_CONFIG_EXPORT_VERSION}",
+            export.version
+        );
+    }
+    Ok(export)
+}
+
+/// Return the entry of the auth config that was imported, and a description of it for messages.
+fn imported_entry<'a>(
+    auth_config: &'a AuthConfig,
+    imported: &ImportedEntry,
+) -> Option<(&'a AuthConfigEntry, String)> {
+    match imported.kind {
+        AuthConfigEntryKind::Index => {
+            let entry = auth_config.indexes.get(&imported.key)?;
+            let description = match &entry.name {
+                Some(name) => format!("index '{name}'"),
+                None => format!("index `{}`", imported.key),
+            };
+            Some((entry, description))
+        }
+        AuthConfigEntryKind::Url => {
+            let entry = auth_config.urls.get(&imported.key)?;
+            Some((entry, format!("URL '{}'", imported.key)))
+        }
+    }
+}
+
+/// Report what happened to each imported entry, warning about the conflicts that were kept.
+fn report_imported_entries(
+    auth_config: &AuthConfig,
+    imported: &[ImportedEntry],
+    printer: Printer,
+) -> Result<()> {
+    for imported in imported {
+        let Some((entry, description)) = imported_entry(auth_config, imported) else {
+            continue;
+        };
+        match imported.outcome {
+            ImportOutcome::Added => writeln!(
+                printer.stderr(),
+                "Added {description} with {}",
+                format_usernames(&entry.usernames)
+            )?,
+            ImportOutcome::Replaced => writeln!(
+                printer.stderr(),
+                "Replaced {description} with {}",
+                format_usernames(&entry.usernames)
+            )?,
+            ImportOutcome::Unchanged => debug!("The entry for {description} is unchanged"),
+            ImportOutcome::Conflict => {
+                warn_user!(
+                    "Kept the existing entry for {description}, which differs from the imported one; use `--overwrite` to replace it"
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Prompt for the password of each username of the imported entries that were added or replaced
+/// with `prompt`, and store it in the keyring.
+///
+/// Entries with a credential helper are skipped, since the helper issues their credentials, as are
+/// index entries that don't record the URL of their index.
+async fn store_imported_passwords(
+    auth_config: &AuthConfig,
+    imported: &[ImportedEntry],
+    keyrings: &mut IndexKeyrings,
+    mut prompt: impl FnMut(&str) -> Result<SecretString>,
+    printer: Printer,
+) -> Result<()> {
+    for imported in imported {
+        if !matches!(
+            imported.outcome,
+            ImportOutcome::Added | ImportOutcome::Replaced
+        ) {
+            continue;
+        }
+        let Some((entry, description)) = imported_entry(auth_config, imported) else {
+            continue;
+        };
+        if entry.helper.is_some() {
+            continue;
+        }
+        let url = match imported.kind {
+            AuthConfigEntryKind::Index => entry.url.clone(),
+            AuthConfigEntryKind::Url => Url::parse(&imported.key).ok(),
+        };
+        let Some(url) = url else {
+            warn_user!(
+                "The URL of {description} is unknown; set its password with `uv index credentials set`"
+            );
+            continue;
+        };
+        let Some(keyring_provider) = keyrings.for_recorded_mut(entry.keyring_provider) else {
+            warn_user!(
+                "The keyring is disabled for {description}; use `--keyring-provider` to store its password"
+            );
+            continue;
+        };
+        for username in &entry.usernames {
+            let secret = if entry.scheme == AuthScheme::Bearer || username == TOKEN_USERNAME {
+                "token"
+            } else {
+                "password"
+            };
+            let password = prompt(&format!("{secret} for user '{username}' of {description}"))?;
+            keyring_provider
+                .set_scoped(&url, username, password.expose(), entry.keyring_scope)
+                .await;
+            writeln!(
+                printer.stderr(),
+                "Stored the {secret} for user '{username}' of {description}"
+            )?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use uv_auth::CredentialHelper;
+
+    use crate::commands::index::testing::{dummy_keyrings, stored};
+
+    use super::*;
+
+    #[test]
+    fn parse_malformed_credentials_export() {
+        let export = parse_credentials_export(
+            r#"{"version": 1, "indexes": {"example.com": {"usernames": ["user"]}}}"#,
+        )
+        .unwrap();
+        assert_eq!(export.indexes["example.com"].usernames, ["user"]);
+
+        for contents in [
+            "",
+            "[]",
+            r#"{"version": 1, "indexes": {"example.com": {}}}"#,
+            r#"{"version": 1, "passwords": {}}"#,
+        ] {
+            let err = parse_credentials_export(contents).unwrap_err();
+            assert!(err
+                .to_string()
+                .starts_with("Failed to parse the credentials"));
+        }
+        let err = parse_credentials_export(r#"{"version": 2}"#).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unsupported version 2 of the credentials to import; expected version 1"
+        );
+    }
+
+    #[tokio::test]
+    async fn store_passwords_of_imported_entries() {
+        let added = Url::parse("https://added.example.com/simple").unwrap();
+        let kept = Url::parse("https://kept.example.com/simple").unwrap();
+        let helper = Url::parse("https://helper.example.com/simple").unwrap();
+        let wheel =
+            Url::parse("https://files.example.com/wheels/pkg-1.0-py3-none-any.whl").unwrap();
+
+        let mut exported = AuthConfig::default();
+        exported.add_entry(&added, "user".to_string());
+        exported.add_entry(&added, TOKEN_USERNAME.to_string());
+        exported.set_index_name(&added, Some("internal".to_string()));
+        exported.add_entry(&kept, "other-user".to_string());
+        exported.add_entry(&helper, "aws".to_string());
+        exported.set_helper(&helper, Some(CredentialHelper::Gcloud { adc: false }));
+        exported.add_url_entry(&wheel, "user".to_string());
+
+        let mut auth_config = AuthConfig::default();
+        auth_config.add_entry(&kept, "user".to_string());
+        let imported = auth_config.import(exported.export(), false);
+
+        // Only the entries that were added are prompted for, except for a credential helper.
+        let mut keyrings = dummy_keyrings(&[]);
+        let mut prompts = Vec::new();
+        store_imported_passwords(
+            &auth_config,
+            &imported,
+            &mut keyrings,
+            |label| {
+                prompts.push(label.to_string());
+                Ok(SecretString::from("password"))
+            },
+            Printer::Quiet,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            prompts,
+            [
+                "password for user 'user' of index 'internal'",
+                "token for user '__token__' of index 'internal'",
+                "password for user 'user' of URL 'https://files.example.com/wheels/pkg-1.0-py3-none-any.whl'",
+            ]
+        );
+        assert!(stored(&keyrings, &added, "user").await);
+        assert!(stored(&keyrings, &added, TOKEN_USERNAME).await);
+        assert!(stored(&keyrings, &wheel, "user").await);
+        assert!(!stored(&keyrings, &kept, "other-user").await);
+        assert!(!stored(&keyrings, &helper, "aws").await);
+
+        // Importing the same entries again doesn't prompt.
+        let imported = auth_config.import(exported.export(), false);
+        store_imported_passwords(
+            &auth_config,
+            &imported,
+            &mut keyrings,
+            |_| panic!("unexpected prompt"),
+            Printer::Quiet,
+        )
+        .await
+        .unwrap();
+    }
+}
@@ -0,0 +1,367 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use tracing::warn;
+use url::Url;
+
+use uv_auth::{
+    AuthConfig, CredentialSource, CredentialSources, Credentials, EnvCredentialProvider,
+    IndexKeyringProvider, KeyringError, KeyringIndex, KeyringMetrics, KeyringProvider,
+    KeyringScope, NetrcProvider,
+};
+use uv_configuration::KeyringProviderType;
+use uv_distribution_types::Index;
+
+use crate::commands::index::target::select_target;
+use crate::commands::index::CredentialsError;
+
+/// Fetch the credentials for an index, trying each of the configured usernames in order, see
+/// [`fetch_index_credentials`].
+pub(super) async fn fetch_configured_credentials(
+    index: &Index,
+    usernames: &[String],
+    keyring_provider: Option<&KeyringProvider>,
+    netrc: Option<&NetrcProvider>,
+) -> Result<Option<(Credentials, CredentialSource)>, KeyringError> {
+    if usernames.is_empty() {
+        return fetch_index_credentials(index, None, keyring_provider, netrc).await;
+    }
+    for username in usernames {
+        if let Some(found) =
+            fetch_index_credentials(index, Some(username), keyring_provider, netrc).await?
+        {
+            return Ok(Some(found));
+        }
+    }
+    Ok(None)
+}
+
+/// Fetch the credentials for an index from the environment, the keyring, or a netrc file, in
+/// order of precedence, see [`CredentialSources`].
+///
+/// The environment is only checked for a named index, and the keyring is skipped if no
+/// [`KeyringProvider`] is given.
+pub(super) async fn fetch_index_credentials(
+    index: &Index,
+    username: Option<&str>,
+    keyring_provider: Option<&KeyringProvider>,
+    netrc: Option<&NetrcProvider>,
+) -> Result<Option<(Credentials, CredentialSource)>, KeyringError> {
+    let name = index.name.as_ref().map(AsRef::as_ref);
+    let env = name.map(EnvCredentialProvider::new);
+    let keyring_index = KeyringIndex {
+        name,
+        publish_url: index.publish_url.as_ref(),
+    };
+    CredentialSources::new(env, keyring_provider, netrc)
+        .fetch(index.raw_url(), username, Some(&keyring_index))
+        .await
+}
+
+/// The maximum number of concurrent keyring lookups when listing credentials.
+///
+/// Each lookup may spawn a `keyring` process, which starts a Python interpreter.
+pub(super) const KEYRING_CONCURRENCY: usize = 4;
+
+/// The keyring service that the credentials for a URL are stored under, for `--dry-run`.
+pub(super) fn keyring_service_name(url: &Url) -> String {
+    KeyringProvider::service_name(url).unwrap_or_else(|| url.to_string())
+}
+
+/// The keyring service name that the credentials for a URL are stored under with the given scope.
+pub(super) fn scoped_service_name(url: &Url, scope: KeyringScope) -> String {
+    KeyringProvider::scoped_service_name(url, scope).unwrap_or_else(|| url.to_string())
+}
+
+/// Create the [`KeyringProvider`] for the given type to store credentials with, applying the
+/// timeout if one was provided, and recording its queries in the metrics of the command.
+///
+/// Fails if the keyring is disabled, since credentials can't be stored without a keyring, or if
+/// the keyring command is overridden with an invalid `UV_KEYRING_COMMAND`.
+pub(crate) fn keyring_provider_for(
+    keyring_provider: KeyringProviderType,
+    keyring_timeout: Option<Duration>,
+    keyring_metrics: &Arc<KeyringMetrics>,
+) -> Result<KeyringProvider> {
+    let Some(keyring_provider) =
+        optional_keyring_provider_for(keyring_provider, keyring_timeout, keyring_metrics)?
+    else {
+        return Err(CredentialsError::KeyringDisabled.into());
+    };
+    Ok(keyring_provider)
+}
+
+/// Like [`keyring_provider_for`], returning [`None`] if the keyring is disabled.
+pub(crate) fn optional_keyring_provider_for(
+    keyring_provider: KeyringProviderType,
+    keyring_timeout: Option<Duration>,
+    keyring_metrics: &Arc<KeyringMetrics>,
+) -> Result<Option<KeyringProvider>> {
+    Ok(keyring_provider.try_to_provider()?.map(|keyring_provider| {
+        let keyring_provider = keyring_provider.with_total_metrics(keyring_metrics.clone());
+        match keyring_timeout {
+            Some(timeout) => keyring_provider.with_timeout(timeout),
+            None => keyring_provider,
+        }
+    }))
+}
+
+/// Select the keyring provider type for an index.
+///
+/// The provider given on the command line takes precedence over the provider recorded for the
+/// index in the auth config, which takes precedence over the default provider.
+fn index_keyring_provider(
+    keyring_provider: Option<KeyringProviderType>,
+    recorded: Option<IndexKeyringProvider>,
+    default_keyring_provider: KeyringProviderType,
+) -> KeyringProviderType {
+    keyring_provider
+        .or(recorded.map(KeyringProviderType::from))
+        .unwrap_or(default_keyring_provider)
+}
+
+/// Select the keyring provider type for the index with the given name, or the given URL, see
+/// [`index_keyring_provider`].
+pub(crate) fn keyring_provider_for_index(
+    name: Option<&str>,
+    url: Option<&str>,
+    indexes: &[Index],
+    keyring_provider: Option<KeyringProviderType>,
+    default_keyring_provider: KeyringProviderType,
+) -> Result<KeyringProviderType> {
+    let target = select_target(indexes, name, url)?;
+    let recorded = AuthConfig::load()
+        .inspect_err(|err| warn!("Could not load auth config due to: {err}"))
+        .ok()
+        .and_then(|auth_config| target.keyring_provider(&auth_config));
+    Ok(index_keyring_provider(
+        keyring_provider,
+        recorded,
+        default_keyring_provider,
+    ))
+}
+
+/// The name of a keyring provider, as given to `--keyring-provider`.
+fn keyring_provider_name(provider_type: KeyringProviderType) -> &'static str {
+    match provider_type {
+        KeyringProviderType::Disabled => "disabled",
+        KeyringProviderType::Subprocess => "subprocess",
+        KeyringProviderType::Native => "native",
+        KeyringProviderType::Git => "git",
+        KeyringProviderType::OnePassword => "op",
+        KeyringProviderType::Vault => "vault",
+    }
+}
+
+/// The keyring providers used to look up the credentials of several indexes.
+pub(super) struct IndexKeyrings {
+    /// The provider given on the command line, if any.
+    pub(super) keyring_provider: Option<KeyringProviderType>,
+    /// The provider for indexes without a provider in the auth config.
+    pub(super) default_keyring_provider: KeyringProviderType,
+    /// The providers for each selected type, or [`None`] if the keyring is disabled.
+    pub(super) providers: Vec<(KeyringProviderType, Option<KeyringProvider>)>,
+}
+
+impl IndexKeyrings {
+    /// Create the providers selected for the given indexes and the entries of the auth config,
+    /// applying the timeout if one was provided, and recording their queries in the metrics of
+    /// the command.
+    ///
+    /// Lookups are not cached, to verify each entry against the keyring itself.
+    pub(super) fn new(
+        keyring_provider: Option<KeyringProviderType>,
+        default_keyring_provider: KeyringProviderType,
+        keyring_timeout: Option<Duration>,
+        keyring_metrics: &Arc<KeyringMetrics>,
+        indexes: &[&Index],
+        auth_config: &AuthConfig,
+    ) -> Self {
+        let mut keyrings = Self {
+            keyring_provider,
+            default_keyring_provider,
+            providers: Vec::new(),
+        };
+        let recorded = indexes
+            .iter()
+            .map(|index| recorded_keyring_provider(index, auth_config))
+            .chain(
+                auth_config
+                    .indexes
+                    .values()
+                    .chain(auth_config.urls.values())
+                    .map(|entry| entry.keyring_provider),
+            );
+        for recorded in recorded {
+            let provider_type = keyrings.provider_type(recorded);
+            if keyrings
+                .providers
+                .iter()
+                .any(|(existing, _)| *existing == provider_type)
+            {
+                continue;
+            }
+            let provider = provider_type.to_provider().map(|provider| {
+                let provider = provider.with_total_metrics(keyring_metrics.clone());
+                let provider = match keyring_timeout {
+                    Some(timeout) => provider.with_timeout(timeout),
+                    None => provider,
+                };
+                provider.uncached()
+            });
+            keyrings.providers.push((provider_type, provider));
+        }
+        keyrings
+    }
+
+    /// Describe whether each enabled provider can be used, e.g., `Keyring: subprocess
+    /// (available)`, or [`None`] if the keyring is disabled.
+    pub(super) async fn status(&self) -> Option<String> {
+        let mut statuses = Vec::new();
+        for (provider_type, provider) in &self.providers {
+            let Some(provider) = provider else {
+                continue;
+            };
+            let status = match provider.availability().await {
+                Ok(()) => "available".to_string(),
+                Err(err) => format!("unavailable: {err}"),
+            };
+            statuses.push(format!(
+                "{} ({status})",
+                keyring_provider_name(*provider_type)
+            ));
+        }
+        if statuses.is_empty() {
+            None
+        } else {
+            Some(format!("Keyring: {}", statuses.join(", ")))
+        }
+    }
+
+    fn provider_type(&self, recorded: Option<IndexKeyringProvider>) -> KeyringProviderType {
+        index_keyring_provider(
+            self.keyring_provider,
+            recorded,
+            self.default_keyring_provider,
+        )
+    }
+
+    /// Return the provider selected for the given index, or [`None`] if the keyring is disabled.
+    pub(super) fn for_index(
+        &self,
+        index: &Index,
+        auth_config: &AuthConfig,
+    ) -> Option<&KeyringProvider> {
+        self.for_recorded(recorded_keyring_provider(index, auth_config))
+    }
+
+    /// Return the provider selected for an entry with the given recorded provider, or [`None`] if
+    /// the keyring is disabled.
+    pub(super) fn for_recorded(
+        &self,
+        recorded: Option<IndexKeyringProvider>,
+    ) -> Option<&KeyringProvider> {
+        let provider_type = self.provider_type(recorded);
+        self.providers
+            .iter()
+            .find(|(existing, _)| *existing == provider_type)
+            .and_then(|(_, provider)| provider.as_ref())
+    }
+
+    /// Return the provider selected for an entry with the given recorded provider, for
+    /// modification, see [`IndexKeyrings::for_recorded`].
+    pub(super) fn for_recorded_mut(
+        &mut self,
+        recorded: Option<IndexKeyringProvider>,
+    ) -> Option<&mut KeyringProvider> {
+        let provider_type = self.provider_type(recorded);
+        self.providers
+            .iter_mut()
+            .find(|(existing, _)| *existing == provider_type)
+            .and_then(|(_, provider)| provider.as_mut())
+    }
+}
+
+/// Return the keyring provider recorded for an index in the auth config, if any.
+pub(super) fn recorded_keyring_provider(
+    index: &Index,
+    auth_config: &AuthConfig,
+) -> Option<IndexKeyringProvider> {
+    auth_config
+        .find_entry(index.raw_url())
+        .and_then(|entry| entry.keyring_provider)
+}
+
+#[cfg(test)]
+mod tests {
+    use uv_auth::SecretString;
+
+    use crate::commands::index::testing::index;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn fetch_index_credentials_discovers_username() {
+        let index = index("discovered", "https://example.com/simple");
+        let keyring = KeyringProvider::dummy([(("https://example.com", "svc-bot"), "secret")]);
+
+        // Without a username, both the username and the password are read from the keyring.
+        let (credentials, source) = fetch_index_credentials(&index, None, Some(&keyring), None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(source, CredentialSource::Keyring);
+        assert_eq!(credentials.username(), Some("svc-bot"));
+        assert_eq!(
+            credentials.password().map(SecretString::expose),
+            Some("secret")
+        );
+
+        // A configured username is not replaced by a discovered one.
+        assert!(
+            fetch_index_credentials(&index, Some("user"), Some(&keyring), None)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_index_credentials_compat() {
+        let index = index("internal", "https://example.com/simple");
+        let keyring = KeyringProvider::dummy([(("poetry-repository-internal", "user"), "secret")])
+            .with_compat(vec![uv_auth::KeyringCompat::Poetry]);
+
+        // The credentials stored by poetry are found with the name of the index.
+        let (credentials, source) =
+            fetch_index_credentials(&index, Some("user"), Some(&keyring), None)
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(source, CredentialSource::Keyring);
+        assert_eq!(
+            credentials.password().map(SecretString::expose),
+            Some("secret")
+        );
+    }
+
+    #[test]
+    fn index_keyring_provider_precedence() {
+        use KeyringProviderType::{Disabled, Native, Subprocess};
+
+        assert_eq!(index_keyring_provider(None, None, Disabled), Disabled);
+        assert_eq!(
+            index_keyring_provider(None, Some(IndexKeyringProvider::Native), Subprocess),
+            Native
+        );
+        assert_eq!(
+            index_keyring_provider(
+                Some(Subprocess),
+                Some(IndexKeyringProvider::Native),
+                Disabled
+            ),
+            Subprocess
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use std::fmt::Write;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use uv_auth::KeyringMetrics;
+
+use crate::commands::index::keyring::{keyring_provider_for, keyring_provider_for_index};
+use crate::commands::index::set::{set_credentials, StoreOutcome, StoredCredentials};
+use crate::commands::index::target::{describe_target, select_configured_index};
+use crate::printer::Printer;
+use crate::settings::IndexAddCredentialsSettings;
+
+/// Log in to an index, storing its credentials as [`set_credentials`] does.
+///
+/// Without an index or a URL, the index is selected from the configured indexes, see
+/// [`select_configured_index`].
+///
+/// Returns the credentials that were stored, e.g., to report them with `--output-format json`.
+pub(crate) async fn login(
+    mut settings: IndexAddCredentialsSettings,
+    keyring_metrics: &Arc<KeyringMetrics>,
+    printer: Printer,
+) -> Result<StoredCredentials> {
+    if settings.name.is_none() && settings.url.is_none() {
+        settings.name = Some(select_configured_index(&settings.index, printer)?);
+    }
+    let (target, target_url) = describe_target(
+        &settings.index,
+        settings.name.as_deref(),
+        settings.url.as_deref(),
+    )?;
+
+    let keyring_provider_type = keyring_provider_for_index(
+        settings.name.as_deref(),
+        settings.url.as_deref(),
+        &settings.index,
+        settings.keyring_provider,
+        settings.default_keyring_provider,
+    )?;
+    let mut keyring_provider = keyring_provider_for(
+        keyring_provider_type,
+        settings.keyring_timeout,
+        keyring_metrics,
+    )?;
+    let stored = set_credentials(settings, &mut keyring_provider, printer).await?;
+    if matches!(
+        stored.outcome,
+        StoreOutcome::Declined | StoreOutcome::DryRun
+    ) {
+        return Ok(stored);
+    }
+
+    writeln!(
+        printer.stderr(),
+        "Logged in to {target} ({target_url}) as user '{}'",
+        stored.username
+    )?;
+    Ok(stored)
+}
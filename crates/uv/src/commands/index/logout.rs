@@ -0,0 +1,55 @@
+use std::fmt::Write;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use uv_auth::KeyringMetrics;
+
+use crate::commands::index::keyring::{keyring_provider_for_index, optional_keyring_provider_for};
+use crate::commands::index::target::{describe_target, select_configured_index};
+use crate::commands::index::unset::{unset_credentials, RemovedCredentials};
+use crate::printer::Printer;
+use crate::settings::IndexUnsetCredentialsSettings;
+
+/// Log out of an index, removing its credentials as [`unset_credentials`] does.
+///
+/// Without an index or a URL, the index is selected from the configured indexes, see
+/// [`select_configured_index`].
+///
+/// Returns what was removed, e.g., to report it with `--output-format json`.
+pub(crate) async fn logout(
+    mut settings: IndexUnsetCredentialsSettings,
+    keyring_metrics: &Arc<KeyringMetrics>,
+    printer: Printer,
+) -> Result<RemovedCredentials> {
+    if settings.name.is_none() && settings.url.is_none() {
+        settings.name = Some(select_configured_index(&settings.index, printer)?);
+    }
+    let (target, target_url) = describe_target(
+        &settings.index,
+        settings.name.as_deref(),
+        settings.url.as_deref(),
+    )?;
+
+    let keyring_provider = keyring_provider_for_index(
+        settings.name.as_deref(),
+        settings.url.as_deref(),
+        &settings.index,
+        settings.keyring_provider,
+        settings.default_keyring_provider,
+    )?;
+    let mut keyring_provider =
+        optional_keyring_provider_for(keyring_provider, settings.keyring_timeout, keyring_metrics)?;
+    let dry_run = settings.dry_run;
+    let removed = unset_credentials(settings, keyring_provider.as_mut(), printer).await?;
+    if dry_run {
+        return Ok(removed);
+    }
+
+    writeln!(
+        printer.stderr(),
+        "Logged out of {target} ({target_url}) as user '{}'",
+        removed.username
+    )?;
+    Ok(removed)
+}
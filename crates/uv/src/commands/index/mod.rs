@@ -0,0 +1,434 @@
+use std::fmt::Write;
+
+use anyhow::{bail, Context, Result};
+use console::Term;
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use tracing::{debug, warn};
+use url::Url;
+
+use uv_auth::{
+    AuthConfig, AuthConfigError, KeyringCommandError, KeyringError, KeyringProvider, KeyringScope,
+    SecretString,
+};
+
+use crate::commands::ExitStatus;
+use crate::printer::Printer;
+
+pub(crate) mod check;
+pub(crate) mod export;
+pub(crate) mod import;
+pub(crate) mod keyring;
+pub(crate) mod list;
+pub(crate) mod login;
+pub(crate) mod logout;
+pub(crate) mod names;
+mod prompt;
+pub(crate) mod prune;
+pub(crate) mod rotate;
+pub(crate) mod set;
+pub(crate) mod setup;
+pub(crate) mod target;
+pub(crate) mod unset;
+
+#[cfg(test)]
+mod testing;
+
+/// The username used for token-based authentication.
+const TOKEN_USERNAME: &str = "__token__";
+
+/// An error of the credentials commands that is reported with a stable code with
+/// `--output-format json`, see [`error_code`].
+#[derive(Debug, thiserror::Error)]
+enum CredentialsError {
+    #[error(
+        "No indexes are configured in this project; add one with `[[tool.uv.index]]` in pyproject.toml"
+    )]
+    NoIndexes,
+    #[error("No index found with the name '{name}'{}", did_you_mean(suggestion.as_deref()))]
+    IndexNotFound {
+        name: String,
+        suggestion: Option<String>,
+    },
+    #[error("No username provided and no terminal to prompt for one; use `--username`")]
+    NoUsername,
+    #[error("The username must not be empty or only whitespace")]
+    EmptyUsername,
+    #[error(
+        "No terminal to prompt for the credentials of {}; store them with `uv auth login`",
+        missing.join(", ")
+    )]
+    NoTerminalForSetup { missing: Vec<String> },
+    #[error(
+        "No {secret} provided and no terminal to prompt for one; use `--password` or `--password-stdin`"
+    )]
+    NoPassword { secret: &'static str },
+    #[error(
+        "Credentials for {target} already exist for user '{username}'. Use `--yes` to overwrite them."
+    )]
+    Exists { target: String, username: String },
+    #[error(
+        "The URL of {target} contains the username '{embedded}', which takes precedence over '{username}'; remove it from the URL, or use `--yes` to store the credentials anyway"
+    )]
+    EmbeddedUsername {
+        target: String,
+        embedded: String,
+        username: String,
+    },
+    #[error("Can't store the {secret} in the keyring")]
+    KeyringUnavailable {
+        secret: &'static str,
+        #[source]
+        err: KeyringError,
+    },
+    #[error(
+        "Storing credentials requires a keyring provider, but the keyring is disabled; enable one with `--keyring-provider`, e.g., `--keyring-provider subprocess`, or the `keyring-provider` setting"
+    )]
+    KeyringDisabled,
+    #[error(
+        "Failed to store the {secret} in the keyring under `{service}`; the auth config was left unchanged"
+    )]
+    KeyringNotUpdated {
+        secret: &'static str,
+        service: String,
+    },
+    #[error(
+        "Stored the {secret} in the keyring under `{service}`, but failed to update the auth config; {}",
+        rollback_note(*rolled_back)
+    )]
+    ConfigNotUpdated {
+        secret: &'static str,
+        service: String,
+        rolled_back: bool,
+        #[source]
+        err: anyhow::Error,
+    },
+    #[error(
+        "Removed the password from the keyring under `{service}`, but failed to update the auth config; {}",
+        restore_note(*restored)
+    )]
+    ConfigNotUpdatedAfterRemoval {
+        service: String,
+        restored: bool,
+        #[source]
+        err: anyhow::Error,
+    },
+}
+
+impl CredentialsError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::NoIndexes => "no-indexes",
+            Self::IndexNotFound { .. } => "index-not-found",
+            Self::NoUsername => "missing-username",
+            Self::EmptyUsername => "invalid-username",
+            Self::NoTerminalForSetup { .. } => "missing-credentials",
+            Self::NoPassword { .. } => "missing-password",
+            Self::Exists { .. } => "credentials-exist",
+            Self::EmbeddedUsername { .. } => "embedded-username",
+            Self::KeyringUnavailable { .. } => "keyring-unavailable",
+            Self::KeyringDisabled => "keyring-disabled",
+            Self::KeyringNotUpdated { .. } => "keyring-not-updated",
+            Self::ConfigNotUpdated { .. } | Self::ConfigNotUpdatedAfterRemoval { .. } => {
+                "auth-config-not-updated"
+            }
+        }
+    }
+}
+
+/// Suggest the name of an index in an error message, if any.
+fn did_you_mean(suggestion: Option<&str>) -> String {
+    suggestion
+        .map(|suggestion| format!("; did you mean '{suggestion}'?"))
+        .unwrap_or_default()
+}
+
+/// Describe whether the keyring was rolled back, after the auth config couldn't be updated to match
+/// the credentials stored in it.
+fn rollback_note(rolled_back: bool) -> &'static str {
+    if rolled_back {
+        "the keyring was rolled back"
+    } else {
+        "rolling back the keyring failed, so it may hold credentials the auth config doesn't know about"
+    }
+}
+
+/// Describe whether the password was restored in the keyring, after the auth config couldn't be
+/// updated to match its removal.
+fn restore_note(restored: bool) -> &'static str {
+    if restored {
+        "the password was restored in the keyring"
+    } else {
+        "restoring the password in the keyring failed, so the auth config entry has no password"
+    }
+}
+
+/// The stable code of the error of a credentials command, for `--output-format json`.
+///
+/// Errors that aren't one of the [`CredentialsError`]s are classified by their cause, e.g., an
+/// invalid auth config, and otherwise reported as `error`.
+fn error_code(err: &anyhow::Error) -> &'static str {
+    for cause in err.chain() {
+        if let Some(err) = cause.downcast_ref::<CredentialsError>() {
+            return err.code();
+        }
+        if cause.is::<AuthConfigError>() {
+            return "auth-config";
+        }
+        if cause.is::<KeyringError>() || cause.is::<KeyringCommandError>() {
+            return "keyring";
+        }
+    }
+    "error"
+}
+
+/// The error of a credentials command, as written with `--output-format json`.
+#[derive(Debug, Serialize)]
+struct ErrorOutput {
+    error_code: &'static str,
+    message: String,
+}
+
+/// Write the result of a credentials command to stdout as a single JSON object, for
+/// `--output-format json`.
+///
+/// An error is written as an object with its [`error_code`], and also reported on stderr, for
+/// humans. Returns the exit status of the command.
+pub(crate) fn write_json_result<T: Serialize>(
+    result: Result<T>,
+    printer: Printer,
+) -> Result<ExitStatus> {
+    match result {
+        Ok(output) => {
+            writeln!(printer.stdout(), "{}", serde_json::to_string(&output)?)?;
+            Ok(ExitStatus::Success)
+        }
+        Err(err) => {
+            let output = ErrorOutput {
+                error_code: error_code(&err),
+                message: format!("{err:#}"),
+            };
+            writeln!(printer.stdout(), "{}", serde_json::to_string(&output)?)?;
+            writeln!(printer.stderr(), "{}: {err:#}", "error".red().bold())?;
+            Ok(ExitStatus::Error)
+        }
+    }
+}
+
+/// Roll back the password stored for a username, when the auth config couldn't be updated to
+/// match: the previous password is stored again, or the new one is removed if there was none.
+///
+/// Returns whether the keyring was rolled back.
+async fn restore_password(
+    keyring_provider: &mut KeyringProvider,
+    url: &Url,
+    scope: KeyringScope,
+    username: &str,
+    previous: Option<&SecretString>,
+) -> bool {
+    if let Some(previous) = previous {
+        debug!("Restoring the previous password for {url} and user {username} in keyring");
+        keyring_provider
+            .set_scoped(url, username, previous.expose(), scope)
+            .await
+    } else {
+        debug!("Removing the password for {url} and user {username} from keyring");
+        keyring_provider.unset_scoped(url, username, scope).await
+    }
+}
+
+/// Format a number of auth config entries for a message, e.g., `1 entry` or `2 entries`.
+fn format_entries(count: usize) -> String {
+    if count == 1 {
+        "1 entry".to_string()
+    } else {
+        format!("{count} entries")
+    }
+}
+
+/// Determine the username of the credentials for an index.
+///
+/// The username given explicitly takes precedence over the username in the auth config, followed
+/// by the username entered at the prompt. If several usernames are configured, one must be given
+/// explicitly.
+fn resolve_username(
+    username: Option<String>,
+    configured: &[String],
+    prompt: impl FnOnce() -> Result<Option<String>>,
+) -> Result<Option<String>> {
+    if let Some(username) = username {
+        return Ok(Some(username));
+    }
+    match configured {
+        [] => prompt(),
+        [configured] => {
+            debug!("Using the username '{configured}' from the auth config");
+            Ok(Some(configured.clone()))
+        }
+        _ => bail!(
+            "Several usernames are configured ({}); select one with `--username`",
+            format_usernames(configured)
+        ),
+    }
+}
+
+/// Format configured usernames for a message, e.g., `user 'a'` or `users 'a', 'b'`.
+fn format_usernames(usernames: &[String]) -> String {
+    let quoted = usernames
+        .iter()
+        .map(|username| format!("'{username}'"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    if usernames.len() == 1 {
+        format!("user {quoted}")
+    } else {
+        format!("users {quoted}")
+    }
+}
+
+/// Offer to persist usernames discovered in the keyring during this invocation to the auth
+/// config, so that future invocations can skip discovery.
+///
+/// Only prompts if stderr is a terminal.
+pub(crate) fn adopt_discovered_usernames() -> Result<()> {
+    let discovered = uv_auth::take_discovered_usernames();
+    if discovered.is_empty() {
+        return Ok(());
+    }
+
+    let term = Term::stderr();
+    if !term.is_term() {
+        debug!("Not persisting discovered keyring usernames in a non-interactive session");
+        return Ok(());
+    }
+
+    let auth_config =
+        AuthConfig::load().inspect_err(|err| warn!("Could not load auth config due to: {err}"))?;
+    let adopted = adopt_usernames(&auth_config, discovered, |url, username| {
+        let message = format!(
+            "Found credentials for `{username}` in the keyring for {url}. Remember this username for future runs?"
+        );
+        uv_console::confirm(&message, &term, true).context("Failed to read confirmation")
+    })?;
+    if !adopted.is_empty() {
+        AuthConfig::update(|auth_config| {
+            for (url, username) in adopted {
+                auth_config.add_entry(&url, username);
+            }
+        })
+        .inspect_err(|err| warn!("Could not save auth config due to: {err}"))?;
+    }
+
+    Ok(())
+}
+
+/// Return the discovered usernames that are confirmed and not yet configured in the auth config.
+fn adopt_usernames(
+    auth_config: &AuthConfig,
+    discovered: Vec<(Url, String)>,
+    mut confirm: impl FnMut(&Url, &str) -> Result<bool>,
+) -> Result<Vec<(Url, String)>> {
+    let mut adopted = Vec::new();
+    for (url, username) in discovered {
+        if auth_config.find_entry(&url).is_some() {
+            continue;
+        }
+        if confirm(&url, &username)? {
+            debug!("Adding discovered username {username} for {url} to the auth config");
+            adopted.push((url, username));
+        }
+    }
+    Ok(adopted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adopt_usernames_confirmed() {
+        let mut auth_config = AuthConfig::default();
+        auth_config.add_entry(
+            &Url::parse("https://configured.example.com/simple").unwrap(),
+            "user".to_string(),
+        );
+
+        let discovered = vec![
+            (
+                Url::parse("https://configured.example.com/simple").unwrap(),
+                TOKEN_USERNAME.to_string(),
+            ),
+            (
+                Url::parse("https://accepted.example.com/simple").unwrap(),
+                TOKEN_USERNAME.to_string(),
+            ),
+            (
+                Url::parse("https://declined.example.com/simple").unwrap(),
+                TOKEN_USERNAME.to_string(),
+            ),
+        ];
+        let mut prompted = Vec::new();
+        let adopted = adopt_usernames(&auth_config, discovered, |url, _| {
+            prompted.push(url.host_str().unwrap().to_string());
+            Ok(url.host_str() == Some("accepted.example.com"))
+        })
+        .unwrap();
+
+        // Hosts that are already configured are not prompted for, or overwritten.
+        assert_eq!(
+            prompted,
+            vec!["accepted.example.com", "declined.example.com"]
+        );
+        assert_eq!(
+            adopted,
+            vec![(
+                Url::parse("https://accepted.example.com/simple").unwrap(),
+                TOKEN_USERNAME.to_string()
+            )]
+        );
+
+        // Nothing is adopted if every prompt is declined.
+        let discovered = vec![(
+            Url::parse("https://other.example.com/simple").unwrap(),
+            "user".to_string(),
+        )];
+        assert!(adopt_usernames(&auth_config, discovered, |_, _| Ok(false))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn resolve_username_sources() {
+        let no_prompt = || -> Result<Option<String>> { panic!("Should not prompt") };
+
+        // The username given explicitly.
+        let configured = ["configured".to_string()];
+        let several = ["configured".to_string(), "ci-bot".to_string()];
+
+        let username = resolve_username(Some("flag".to_string()), &[], no_prompt).unwrap();
+        assert_eq!(username.as_deref(), Some("flag"));
+
+        // The username given explicitly wins over the ones in the auth config.
+        let username = resolve_username(Some("flag".to_string()), &several, no_prompt).unwrap();
+        assert_eq!(username.as_deref(), Some("flag"));
+
+        // The username in the auth config.
+        let username = resolve_username(None, &configured, no_prompt).unwrap();
+        assert_eq!(username.as_deref(), Some("configured"));
+
+        // Several usernames in the auth config are ambiguous.
+        let err = resolve_username(None, &several, no_prompt).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Several usernames are configured (users 'configured', 'ci-bot'); select one with `--username`"
+        );
+
+        // The username entered at the prompt.
+        let username = resolve_username(None, &[], || Ok(Some("prompted".to_string()))).unwrap();
+        assert_eq!(username.as_deref(), Some("prompted"));
+
+        // No username, and no way to prompt.
+        let username = resolve_username(None, &[], || Ok(None)).unwrap();
+        assert_eq!(username, None);
+    }
+}
@@ -0,0 +1,18 @@
+use std::fmt::Write;
+
+use anyhow::Result;
+
+use uv_distribution_types::{unique_indexes, Index};
+
+use crate::printer::Printer;
+
+/// Print the names of the configured indexes, one per line, for shell completion.
+///
+/// Unnamed indexes, and indexes shadowed by an earlier one with the same name, are skipped. The
+/// keyring isn't accessed, so completing a name never prompts.
+pub(crate) fn list_index_names(indexes: &[Index], printer: Printer) -> Result<()> {
+    for name in unique_indexes(indexes).filter_map(|index| index.name.as_ref()) {
+        writeln!(printer.stdout(), "{name}")?;
+    }
+    Ok(())
+}
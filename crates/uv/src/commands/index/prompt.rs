@@ -0,0 +1,647 @@
+use std::fmt::Display;
+use std::io::Read;
+
+use anyhow::{bail, Context, Result};
+use console::Term;
+use itertools::Itertools;
+use tracing::debug;
+
+use uv_auth::{KeyringProvider, SecretString};
+use uv_warnings::warn_user;
+
+use crate::commands::index::target::CredentialTarget;
+use crate::commands::index::{CredentialsError, TOKEN_USERNAME};
+
+/// Determine whether the existing credentials for an index may be overwritten.
+///
+/// Unless `force` is set, the user is asked to confirm. `confirm` is [`None`] if the user cannot
+/// be prompted, in which case an error is returned.
+pub(super) fn allow_overwrite(
+    target: &impl Display,
+    existing_username: &str,
+    force: bool,
+    confirm: Option<impl FnOnce(&str) -> Result<bool>>,
+) -> Result<bool> {
+    if force {
+        debug!("Overwriting existing credentials for {target}");
+        return Ok(true);
+    }
+    let Some(confirm) = confirm else {
+        return Err(CredentialsError::Exists {
+            target: target.to_string(),
+            username: existing_username.to_string(),
+        }
+        .into());
+    };
+    confirm(&format!(
+        "Credentials for {target} already exist for user '{existing_username}'. Overwrite?"
+    ))
+}
+
+/// Remove the whitespace around an entered secret, and check it for characters that were likely
+/// copied along with it, see [`SecretString::sanitize`].
+///
+/// Removing whitespace is reported. Unless `force` is set, the user is asked to confirm storing a
+/// secret with suspicious characters, and `None` is returned if they decline. `confirm` is
+/// [`None`] if the user cannot be prompted, in which case an error is returned.
+pub(super) fn check_secret(
+    password: SecretString,
+    secret: &str,
+    force: bool,
+    confirm: Option<impl FnOnce(&str) -> Result<bool>>,
+) -> Result<Option<SecretString>> {
+    let (password, issues) = password.sanitize();
+    if issues.trimmed {
+        if password.is_empty() {
+            bail!("The {secret} consists only of whitespace");
+        }
+        warn_user!("Removed whitespace around the {secret}, which usually comes from copying it");
+    }
+    if issues.suspicious.is_empty() {
+        return Ok(Some(password));
+    }
+
+    let (kinds, verb) = match issues.suspicious.as_slice() {
+        [kind] => (kind.to_string(), "comes"),
+        [init @ .., last] => (format!("{} and {last}", init.iter().join(", ")), "come"),
+        [] => unreachable!(),
+    };
+    debug!("The {secret} contains {kinds}");
+    let problem = format!("The {secret} contains {kinds}, which usually {verb} from copying it");
+    if force {
+        warn_user!("{problem}");
+        return Ok(Some(password));
+    }
+    let Some(confirm) = confirm else {
+        bail!("{problem}. Use `--yes` to store it anyway.");
+    };
+    let store = confirm(&format!("{problem}. Store it anyway?"))?;
+    Ok(store.then_some(password))
+}
+
+/// Read a password from the given reader, excluding a single trailing newline.
+pub(super) fn read_password_stdin(mut reader: impl Read) -> Result<SecretString> {
+    let mut password = String::new();
+    let read = reader.read_to_string(&mut password);
+    if let Some(stripped) = password.strip_suffix('\n') {
+        let stripped = stripped.strip_suffix('\r').unwrap_or(stripped);
+        password.truncate(stripped.len());
+    }
+    // Wrap the input before checking for errors, so that it's zeroized even if incomplete.
+    let password = SecretString::new(password);
+    read.context("Failed to read password from stdin")?;
+    if password.is_empty() {
+        bail!("No password was provided on stdin");
+    }
+    Ok(password)
+}
+
+/// The terminals that the user can be prompted on.
+pub(super) trait Terminals {
+    type Term: PromptTerm;
+
+    /// The terminal that stderr is attached to, if any.
+    fn stderr(&self) -> Option<Self::Term>;
+
+    /// The terminal that stdout is attached to, if any.
+    fn stdout(&self) -> Option<Self::Term>;
+
+    /// The controlling terminal of the process, if any.
+    fn controlling(&self) -> Option<Self::Term>;
+
+    /// The terminal to prompt on: stderr, or stdout if stderr is redirected, or the controlling
+    /// terminal if both are redirected, e.g., with `2>log.txt`.
+    ///
+    /// Returns [`None`] if the session is not interactive.
+    fn prompt_term(&self) -> Option<Self::Term> {
+        self.stderr()
+            .or_else(|| self.stdout())
+            .or_else(|| self.controlling())
+    }
+}
+
+/// A terminal that the user can be prompted on.
+pub(super) trait PromptTerm {
+    fn input(&self, prompt: &str) -> std::io::Result<String>;
+
+    fn password(&self, prompt: &str) -> std::io::Result<String>;
+}
+
+/// The terminals of the current process.
+pub(super) struct ProcessTerminals;
+
+impl Terminals for ProcessTerminals {
+    type Term = Term;
+
+    fn stderr(&self) -> Option<Term> {
+        Some(Term::stderr()).filter(Term::is_term)
+    }
+
+    fn stdout(&self) -> Option<Term> {
+        Some(Term::stdout()).filter(Term::is_term)
+    }
+
+    fn controlling(&self) -> Option<Term> {
+        uv_console::controlling_term()
+    }
+}
+
+impl PromptTerm for Term {
+    fn input(&self, prompt: &str) -> std::io::Result<String> {
+        uv_console::input(prompt, self)
+    }
+
+    fn password(&self, prompt: &str) -> std::io::Result<String> {
+        uv_console::password(prompt, self)
+    }
+}
+
+/// Prompt for a username, offering `default` if the input is left empty.
+///
+/// Returns [`None`] if there is no terminal to prompt on.
+pub(super) fn prompt_username_input(default: Option<&str>) -> Result<Option<String>> {
+    prompt_username_on(&ProcessTerminals, default)
+}
+
+fn prompt_username_on(terminals: &impl Terminals, default: Option<&str>) -> Result<Option<String>> {
+    let Some(term) = terminals.prompt_term() else {
+        return Ok(None);
+    };
+    let username = prompt_username(default, |prompt| {
+        term.input(prompt).context("Failed to read username")
+    })?;
+    Ok(Some(username))
+}
+
+fn prompt_username(
+    default: Option<&str>,
+    read: impl FnOnce(&str) -> Result<String>,
+) -> Result<String> {
+    let Some(default) = default else {
+        return read("Enter username ('__token__' if using a token): ");
+    };
+    let username = read(&format!(
+        "Enter username ('__token__' if using a token) [{default}]: "
+    ))?;
+    if username.is_empty() {
+        Ok(default.to_string())
+    } else {
+        Ok(username)
+    }
+}
+
+/// Validate a username before storing credentials for it.
+///
+/// Surrounding whitespace, e.g., from a pasted value, is removed; the username is otherwise kept
+/// exactly as given, including backslashes (`DOMAIN\user`), `@`, and inner spaces.
+pub(super) fn normalize_username(username: &str) -> Result<String, CredentialsError> {
+    let trimmed = username.trim();
+    if trimmed.is_empty() {
+        return Err(CredentialsError::EmptyUsername);
+    }
+    if trimmed.len() != username.len() {
+        debug!("Removed the whitespace around the username '{trimmed}'");
+    }
+    Ok(trimmed.to_string())
+}
+
+/// Whether a username looks like an email address, e.g., `user@example.com`.
+fn looks_like_email(username: &str) -> bool {
+    let Some((local, domain)) = username.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !username.contains(char::is_whitespace)
+        && !domain.contains('@')
+        && domain
+            .split_once('.')
+            .is_some_and(|(host, tld)| !host.is_empty() && !tld.is_empty())
+}
+
+/// A warning for a username that looks like an email address, when the index is known to expect
+/// a token rather than the password of an account.
+///
+/// PyPI only accepts API tokens, with the username `__token__`, as does an entry that is already
+/// configured with `__token__`; Azure Artifacts feeds expect a personal access token.
+pub(super) fn email_username_warning(
+    username: &str,
+    target: &CredentialTarget,
+    configured: &[String],
+    azure: bool,
+) -> Option<String> {
+    if !looks_like_email(username) {
+        return None;
+    }
+    if azure {
+        return Some(format!(
+            "The username '{username}' looks like an email address, but {target} expects a personal access token rather than the password of an account"
+        ));
+    }
+    let pypi = matches!(
+        target.url().host_str(),
+        Some("pypi.org" | "upload.pypi.org" | "test.pypi.org")
+    );
+    let token_entry = configured
+        .iter()
+        .any(|configured| configured == TOKEN_USERNAME);
+    if pypi || token_entry {
+        return Some(format!(
+            "The username '{username}' looks like an email address, but {target} expects a token rather than the password of an account; store the token with `--username {TOKEN_USERNAME}`"
+        ));
+    }
+    None
+}
+
+/// Prompt for a password (or token) to store in the keyring, see [`prompt_password_input`].
+///
+/// The keyring is checked first, see [`KeyringProvider::availability`], so that a secret that
+/// can't be stored isn't entered in vain.
+pub(super) async fn prompt_password_to_store(
+    keyring_provider: &KeyringProvider,
+    secret: &'static str,
+) -> Result<Option<SecretString>> {
+    if ProcessTerminals.prompt_term().is_none() {
+        return Ok(None);
+    }
+    keyring_provider
+        .availability()
+        .await
+        .map_err(|err| CredentialsError::KeyringUnavailable { secret, err })?;
+    prompt_password_input(secret)
+}
+
+/// The number of attempts to enter a password and its confirmation.
+const PASSWORD_ATTEMPTS: usize = 3;
+
+/// Prompt for a password (or token), which must be entered twice.
+///
+/// Returns [`None`] if there is no terminal to prompt on. Ctrl-C during a prompt exits the
+/// process; the prompts are read before anything is written to the keyring or the auth config.
+fn prompt_password_input(secret: &str) -> Result<Option<SecretString>> {
+    prompt_password_on(&ProcessTerminals, secret)
+}
+
+fn prompt_password_on(terminals: &impl Terminals, secret: &str) -> Result<Option<SecretString>> {
+    let Some(term) = terminals.prompt_term() else {
+        return Ok(None);
+    };
+    let password = confirm_password(secret, |prompt| {
+        term.password(prompt).context("Failed to read password")
+    })?;
+    Ok(Some(password))
+}
+
+/// Read a secret twice with `prompt`, retrying up to [`PASSWORD_ATTEMPTS`] times until both
+/// entries match.
+pub(super) fn confirm_password(
+    secret: &str,
+    mut prompt: impl FnMut(&str) -> Result<String>,
+) -> Result<SecretString> {
+    for attempt in 1..=PASSWORD_ATTEMPTS {
+        let password = SecretString::new(prompt(&format!("Enter {secret}: "))?);
+        let confirmation = SecretString::new(prompt(&format!("Confirm {secret}: "))?);
+        if password == confirmation {
+            return Ok(password);
+        }
+        if attempt < PASSWORD_ATTEMPTS {
+            warn_user!("The {secret}s do not match, try again");
+        }
+    }
+    bail!("The {secret}s did not match after {PASSWORD_ATTEMPTS} attempts")
+}
+
+#[cfg(test)]
+mod tests {
+    use uv_distribution_types::Index;
+
+    use crate::commands::index::error_code;
+    use crate::commands::index::testing::{index, FakeTerminals};
+
+    use super::*;
+
+    #[test]
+    fn check_secret_confirm() {
+        let never = None::<fn(&str) -> Result<bool>>;
+
+        // Clean and trimmed secrets are stored without confirmation.
+        let password = check_secret("hunter2".into(), "password", false, never)
+            .unwrap()
+            .unwrap();
+        assert_eq!(password.expose(), "hunter2");
+        let password = check_secret(" hunter2\r\n".into(), "password", false, never)
+            .unwrap()
+            .unwrap();
+        assert_eq!(password.expose(), "hunter2");
+        assert_eq!(
+            check_secret(" \n".into(), "token", false, never)
+                .unwrap_err()
+                .to_string(),
+            "The token consists only of whitespace"
+        );
+
+        // Suspicious characters need confirmation, naming only their kinds.
+        let suspicious = || SecretString::from("hunter\u{200B}2\u{00A0}\n");
+        let mut message = String::new();
+        let stored = check_secret(
+            suspicious(),
+            "password",
+            false,
+            Some(|prompt: &str| {
+                message = prompt.to_string();
+                Ok(false)
+            }),
+        )
+        .unwrap();
+        assert!(stored.is_none());
+        assert_eq!(
+            message,
+            "The password contains a zero-width character and a non-breaking space, which usually come from copying it. Store it anyway?"
+        );
+        let stored = check_secret(suspicious(), "password", false, Some(|_: &str| Ok(true)))
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.expose(), "hunter\u{200B}2\u{00A0}");
+
+        // Without a terminal, `--yes` is required.
+        assert_eq!(
+            check_secret("line\none".into(), "password", false, never)
+                .unwrap_err()
+                .to_string(),
+            "The password contains a line break, which usually comes from copying it. Use `--yes` to store it anyway."
+        );
+        let stored = check_secret("line\none".into(), "password", true, never)
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.expose(), "line\none");
+    }
+
+    #[test]
+    fn allow_overwrite_prompt() {
+        // The user is asked to confirm.
+        let mut prompt = None;
+        assert!(allow_overwrite(
+            &"index 'internal'",
+            "svc-bot",
+            false,
+            Some(|message: &str| {
+                prompt = Some(message.to_string());
+                Ok(true)
+            })
+        )
+        .unwrap());
+        assert_eq!(
+            prompt.as_deref(),
+            Some("Credentials for index 'internal' already exist for user 'svc-bot'. Overwrite?")
+        );
+
+        // Declining the prompt keeps the existing credentials.
+        assert!(!allow_overwrite(
+            &"index 'internal'",
+            "svc-bot",
+            false,
+            Some(|_: &str| Ok(false))
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn allow_overwrite_force() {
+        // With `--yes`, the user is not prompted, even if they could be.
+        assert!(allow_overwrite(
+            &"index 'internal'",
+            "svc-bot",
+            true,
+            Some(|_: &str| -> Result<bool> { unreachable!("The user should not be prompted") })
+        )
+        .unwrap());
+        assert!(allow_overwrite(
+            &"index 'internal'",
+            "svc-bot",
+            true,
+            None::<fn(&str) -> Result<bool>>
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn allow_overwrite_non_interactive() {
+        let err = allow_overwrite(
+            &"index 'internal'",
+            "svc-bot",
+            false,
+            None::<fn(&str) -> Result<bool>>,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Credentials for index 'internal' already exist for user 'svc-bot'. Use `--yes` to overwrite them."
+        );
+    }
+
+    #[test]
+    fn password_stdin() {
+        assert_eq!(
+            read_password_stdin(&b"secret\n"[..]).unwrap().expose(),
+            "secret"
+        );
+        assert_eq!(
+            read_password_stdin(&b"secret\r\n"[..]).unwrap().expose(),
+            "secret"
+        );
+        assert_eq!(
+            read_password_stdin(&b"secret"[..]).unwrap().expose(),
+            "secret"
+        );
+        // Only a single trailing newline is removed.
+        assert_eq!(
+            read_password_stdin(&b"secret\n\n"[..]).unwrap().expose(),
+            "secret\n"
+        );
+        // Whitespace is part of the password.
+        assert_eq!(
+            read_password_stdin(&b" a secret with spaces \n"[..])
+                .unwrap()
+                .expose(),
+            " a secret with spaces "
+        );
+        assert!(read_password_stdin(&b"\n"[..]).is_err());
+        assert!(read_password_stdin(&b""[..]).is_err());
+    }
+
+    #[test]
+    fn prompt_terminal_fallback() {
+        let terminals = |stderr, stdout, controlling| FakeTerminals {
+            stderr,
+            stdout,
+            controlling,
+            answer: "user",
+        };
+
+        // Prompts are shown on stderr, then stdout, then the controlling terminal.
+        let name = |terminals: FakeTerminals| terminals.prompt_term().map(|term| term.name);
+        assert_eq!(name(terminals(true, true, true)), Some("stderr"));
+        assert_eq!(name(terminals(false, true, true)), Some("stdout"));
+        assert_eq!(name(terminals(false, false, true)), Some("tty"));
+
+        // With stderr and stdout redirected, e.g., to a log file, the controlling terminal is
+        // prompted.
+        let redirected = terminals(false, false, true);
+        assert_eq!(
+            prompt_username_on(&redirected, None).unwrap().as_deref(),
+            Some("user")
+        );
+        assert_eq!(
+            prompt_password_on(&redirected, "token")
+                .unwrap()
+                .as_ref()
+                .map(SecretString::expose),
+            Some("user")
+        );
+
+        // Without any terminal, e.g., in CI, there is no prompt.
+        let piped = terminals(false, false, false);
+        assert_eq!(piped.prompt_term().map(|term| term.name), None);
+        assert_eq!(prompt_username_on(&piped, Some("default")).unwrap(), None);
+        assert_eq!(prompt_password_on(&piped, "password").unwrap(), None);
+    }
+
+    #[test]
+    fn confirm_password_retries() {
+        fn prompts<'a>(
+            entries: &'a [&'a str],
+            seen: &'a mut Vec<String>,
+        ) -> impl FnMut(&str) -> Result<String> + 'a {
+            let mut entries = entries.iter();
+            move |prompt| {
+                seen.push(prompt.to_string());
+                Ok(entries.next().expect("Too many prompts").to_string())
+            }
+        }
+
+        // The entries match on the first attempt.
+        let mut seen = Vec::new();
+        let password = confirm_password("password", prompts(&["secret", "secret"], &mut seen));
+        assert_eq!(password.unwrap().expose(), "secret");
+        assert_eq!(seen, ["Enter password: ", "Confirm password: "]);
+
+        // A mismatch prompts for both entries again.
+        let mut seen = Vec::new();
+        let password = confirm_password(
+            "token",
+            prompts(&["secret", "secert", "secret", "secret"], &mut seen),
+        );
+        assert_eq!(password.unwrap().expose(), "secret");
+        assert_eq!(seen.len(), 4);
+        assert_eq!(seen[2], "Enter token: ");
+
+        // After three mismatches, give up.
+        let mut seen = Vec::new();
+        let err = confirm_password(
+            "password",
+            prompts(&["a", "b", "c", "d", "e", "f"], &mut seen),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The passwords did not match after 3 attempts"
+        );
+        assert_eq!(seen.len(), 6);
+
+        // A failure to read a prompt is returned as-is.
+        let err = confirm_password("password", |_| bail!("Failed to read password")).unwrap_err();
+        assert_eq!(err.to_string(), "Failed to read password");
+    }
+
+    #[test]
+    fn prompt_username_default() {
+        // Without a default, the input is used as-is.
+        let username = prompt_username(None, |prompt| {
+            assert_eq!(prompt, "Enter username ('__token__' if using a token): ");
+            Ok("user".to_string())
+        })
+        .unwrap();
+        assert_eq!(username, "user");
+
+        // The default is shown, and used for empty input.
+        let username = prompt_username(Some("configured"), |prompt| {
+            assert_eq!(
+                prompt,
+                "Enter username ('__token__' if using a token) [configured]: "
+            );
+            Ok(String::new())
+        })
+        .unwrap();
+        assert_eq!(username, "configured");
+
+        // Other input replaces the default.
+        let username = prompt_username(Some("configured"), |_| Ok("other".to_string())).unwrap();
+        assert_eq!(username, "other");
+    }
+
+    #[test]
+    fn normalize_username_characters() {
+        // Backslashes, `@`, and inner spaces are kept; surrounding whitespace is removed.
+        for username in ["DOMAIN\\user", "user@example.com", "first last"] {
+            assert_eq!(normalize_username(username).unwrap(), username);
+            assert_eq!(
+                normalize_username(&format!(" {username}\t\n")).unwrap(),
+                username
+            );
+        }
+
+        // Empty and whitespace-only usernames are rejected.
+        for username in ["", "  ", "\t\n"] {
+            let err = anyhow::Error::from(normalize_username(username).unwrap_err());
+            assert_eq!(error_code(&err), "invalid-username");
+            assert_eq!(
+                err.to_string(),
+                "The username must not be empty or only whitespace"
+            );
+        }
+    }
+
+    #[test]
+    fn email_username_for_token_index() {
+        assert!(looks_like_email("user@example.com"));
+        for username in [
+            "user",
+            "DOMAIN\\user",
+            "@example.com",
+            "user@localhost",
+            "user@example.",
+            "first last@example.com",
+            "a@b@example.com",
+        ] {
+            assert!(!looks_like_email(username), "{username}");
+        }
+
+        let pypi = index("pypi", "https://pypi.org/simple");
+        let internal = index("internal", "https://internal.example.com/simple");
+        let warning = |index: &Index, username: &str, configured: &[String], azure: bool| {
+            email_username_warning(username, &CredentialTarget::Index(index), configured, azure)
+        };
+
+        // PyPI only accepts tokens.
+        insta::assert_snapshot!(
+            warning(&pypi, "user@example.com", &[], false).unwrap(),
+            @"The username 'user@example.com' looks like an email address, but index 'pypi' expects a token rather than the password of an account; store the token with `--username __token__`"
+        );
+        assert_eq!(warning(&pypi, "user", &[], false), None);
+
+        // An entry that is configured with `__token__` expects a token as well.
+        assert!(warning(
+            &internal,
+            "user@example.com",
+            &[TOKEN_USERNAME.to_string()],
+            false
+        )
+        .is_some());
+        assert_eq!(warning(&internal, "user@example.com", &[], false), None);
+
+        // Azure Artifacts feeds expect a personal access token.
+        insta::assert_snapshot!(
+            warning(&internal, "user@example.com", &[], true).unwrap(),
+            @"The username 'user@example.com' looks like an email address, but index 'internal' expects a personal access token rather than the password of an account"
+        );
+    }
+}
@@ -0,0 +1,254 @@
+use std::fmt::Write;
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use tracing::{debug, warn};
+
+use uv_auth::{AuthConfig, KeyringMetrics};
+use uv_distribution_types::{unique_indexes, Index};
+use uv_warnings::warn_user;
+
+use crate::commands::index::keyring::IndexKeyrings;
+use crate::commands::index::prompt::ProcessTerminals;
+use crate::commands::index::target::CredentialTarget;
+use crate::commands::index::unset::remove_entry_credentials;
+use crate::commands::index::{format_entries, format_usernames};
+use crate::printer::Printer;
+use crate::settings::IndexPruneCredentialsSettings;
+
+/// Remove the entries of the auth config that no configured index uses, e.g., after the URL of an
+/// index changed or the index was removed.
+///
+/// The keyring entries are removed as well, if the entry records the URL of its index. With
+/// `dry_run`, the entries are only listed.
+pub(crate) async fn prune_credentials(
+    settings: IndexPruneCredentialsSettings,
+    keyring_metrics: &Arc<KeyringMetrics>,
+    printer: Printer,
+) -> Result<()> {
+    let IndexPruneCredentialsSettings {
+        yes,
+        dry_run,
+        keyring_timeout,
+        keyring_provider,
+        default_keyring_provider,
+        index: indexes,
+    } = settings;
+    let auth_config =
+        AuthConfig::load().inspect_err(|err| warn!("Could not load auth config due to: {err}"))?;
+    let orphaned = auth_config
+        .orphaned_entries(indexes.iter().map(Index::raw_url))
+        .into_iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    if orphaned.is_empty() {
+        writeln!(printer.stderr(), "No orphaned credentials found.")?;
+        return Ok(());
+    }
+
+    for key in &orphaned {
+        writeln!(
+            printer.stderr(),
+            "Found credentials for `{key}` with {}, which no configured index uses",
+            format_usernames(&auth_config.indexes[key].usernames)
+        )?;
+    }
+    if dry_run {
+        writeln!(
+            printer.stderr(),
+            "Would remove {}",
+            format_entries(orphaned.len())
+        )?;
+        return Ok(());
+    }
+
+    let confirm = ProcessTerminals.prompt_term().map(|term| {
+        move |message: &str| {
+            uv_console::confirm(message, &term, false).context("Failed to read confirmation")
+        }
+    });
+    if !allow_prune(orphaned.len(), yes, confirm)? {
+        debug!("Not removing orphaned credentials");
+        return Ok(());
+    }
+
+    let effective = unique_indexes(&indexes).collect::<Vec<_>>();
+    let mut keyrings = IndexKeyrings::new(
+        keyring_provider,
+        default_keyring_provider,
+        keyring_timeout,
+        keyring_metrics,
+        &effective,
+        &auth_config,
+    );
+    remove_orphaned_credentials(&auth_config, &orphaned, &mut keyrings, printer).await?;
+
+    AuthConfig::update(|auth_config| {
+        for key in &orphaned {
+            auth_config.indexes.remove(key);
+        }
+    })?;
+    writeln!(
+        printer.stderr(),
+        "Removed {}",
+        format_entries(orphaned.len())
+    )?;
+
+    Ok(())
+}
+
+/// Determine whether orphaned entries should be removed.
+///
+/// Unless `yes` is set, the user is asked to confirm. `confirm` is [`None`] if the user cannot be
+/// prompted, in which case an error is returned.
+fn allow_prune(
+    count: usize,
+    yes: bool,
+    confirm: Option<impl FnOnce(&str) -> Result<bool>>,
+) -> Result<bool> {
+    if yes {
+        return Ok(true);
+    }
+    let Some(confirm) = confirm else {
+        bail!(
+            "Found {} that no configured index uses. Use `--yes` to remove them.",
+            format_entries(count)
+        );
+    };
+    confirm(&format!("Remove {}?", format_entries(count)))
+}
+
+/// Remove the keyring entries of the given orphaned entries of the auth config.
+///
+/// Entries that don't record the URL of their index are left in the keyring, with a warning.
+async fn remove_orphaned_credentials(
+    auth_config: &AuthConfig,
+    orphaned: &[String],
+    keyrings: &mut IndexKeyrings,
+    printer: Printer,
+) -> Result<()> {
+    for key in orphaned {
+        let entry = &auth_config.indexes[key];
+        let Some(url) = &entry.url else {
+            warn_user!(
+                "The index URL of the credentials for `{key}` is unknown; remove them from the keyring manually"
+            );
+            continue;
+        };
+        remove_entry_credentials(
+            &CredentialTarget::Url(url.clone()),
+            &entry.usernames,
+            entry.keyring_provider,
+            entry.keyring_scope,
+            keyrings,
+            false,
+            printer,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use crate::commands::index::testing::{dummy_keyrings, index, stored};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn prune_orphaned_credentials() {
+        let internal = Url::parse("https://internal.example.com/simple").unwrap();
+        let deleted = Url::parse("https://deleted.example.com/simple").unwrap();
+        let legacy = Url::parse("https://legacy.example.com/simple").unwrap();
+
+        let mut auth_config = AuthConfig::default();
+        auth_config.add_entry(&internal, "user".to_string());
+        auth_config.add_entry(&deleted, "user".to_string());
+        auth_config.add_entry(&legacy, "user".to_string());
+        auth_config
+            .indexes
+            .get_mut("legacy.example.com")
+            .unwrap()
+            .url = None;
+        let orphaned_for = |indexes: &[Index]| {
+            auth_config
+                .orphaned_entries(indexes.iter().map(Index::raw_url))
+                .into_iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        };
+
+        // Nothing to prune while every entry is used.
+        let indexes = [
+            index("internal", "https://internal.example.com/simple"),
+            index("deleted", "https://deleted.example.com/simple"),
+            index("legacy", "https://legacy.example.com/simple"),
+        ];
+        assert!(orphaned_for(&indexes).is_empty());
+
+        // Renaming an index keeps its entry, which is keyed by host.
+        let indexes = [
+            index("renamed", "https://internal.example.com/simple"),
+            index("deleted", "https://deleted.example.com/simple"),
+            index("legacy", "https://legacy.example.com/simple"),
+        ];
+        assert!(orphaned_for(&indexes).is_empty());
+
+        // Moving an index to another host, or deleting one, leaves its entry behind.
+        let indexes = [index("internal", "https://pkgs.example.com/simple")];
+        let orphaned = orphaned_for(&indexes);
+        assert_eq!(
+            orphaned,
+            [
+                "deleted.example.com",
+                "internal.example.com",
+                "legacy.example.com"
+            ]
+        );
+
+        let mut keyrings = dummy_keyrings(&[
+            ("https://internal.example.com", "user"),
+            ("https://deleted.example.com", "user"),
+            ("https://legacy.example.com", "user"),
+        ]);
+        remove_orphaned_credentials(&auth_config, &orphaned, &mut keyrings, Printer::Quiet)
+            .await
+            .unwrap();
+        assert!(!stored(&keyrings, &internal, "user").await);
+        assert!(!stored(&keyrings, &deleted, "user").await);
+        // Without a recorded URL, the keyring entry can't be found.
+        assert!(stored(&keyrings, &legacy, "user").await);
+    }
+
+    #[test]
+    fn allow_prune_prompt() {
+        let mut prompt = None;
+        assert!(allow_prune(
+            2,
+            false,
+            Some(|message: &str| {
+                prompt = Some(message.to_string());
+                Ok(true)
+            })
+        )
+        .unwrap());
+        assert_eq!(prompt.as_deref(), Some("Remove 2 entries?"));
+
+        // `--yes` skips the prompt.
+        assert!(allow_prune(
+            1,
+            true,
+            Some(|_: &str| -> Result<bool> { unreachable!("The user should not be prompted") })
+        )
+        .unwrap());
+
+        // Without a way to prompt, `--yes` is required.
+        let err = allow_prune(1, false, None::<fn(&str) -> Result<bool>>).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Found 1 entry that no configured index uses. Use `--yes` to remove them."
+        );
+    }
+}
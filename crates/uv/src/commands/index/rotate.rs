@@ -0,0 +1,175 @@
+use std::fmt::Write;
+
+use anyhow::{bail, Context, Result};
+use tracing::{debug, warn};
+use url::Url;
+
+use uv_auth::{AuthConfig, Credentials, KeyringProvider, KeyringScope, SecretString};
+
+use crate::commands::index::keyring::scoped_service_name;
+use crate::commands::index::prompt::{
+    check_secret, prompt_password_to_store, read_password_stdin, ProcessTerminals,
+};
+use crate::commands::index::target::select_target;
+use crate::commands::index::{resolve_username, TOKEN_USERNAME};
+use crate::printer::Printer;
+use crate::settings::IndexRotateCredentialsSettings;
+
+/// Replace the password of the existing credentials for an index, keeping the username.
+///
+/// With `dry_run`, the keyring service that would be changed is printed instead, without
+/// prompting for the password. Unless `force` is set, the user is asked to confirm storing a
+/// password with suspicious characters, see [`check_secret`].
+pub(crate) async fn rotate_credentials(
+    settings: IndexRotateCredentialsSettings,
+    keyring_provider: &mut KeyringProvider,
+    printer: Printer,
+) -> Result<()> {
+    let IndexRotateCredentialsSettings {
+        name,
+        url,
+        username,
+        password,
+        password_stdin,
+        force,
+        dry_run,
+        index: indexes,
+        ..
+    } = settings;
+    let target = select_target(&indexes, name.as_deref(), url.as_deref())?;
+    let url = target.url();
+
+    let auth_config =
+        AuthConfig::load().inspect_err(|err| warn!("Could not load auth config due to: {err}"))?;
+    let configured = target.usernames(&auth_config);
+    if configured.is_empty() {
+        bail!(
+            "No credentials are configured for {target}; add them with `uv index credentials set {}`",
+            target.selector()
+        );
+    }
+    let Some(username) = resolve_username(username, &configured, || Ok(None))? else {
+        unreachable!("A configured username should be selected");
+    };
+    if !configured.contains(&username) {
+        bail!(
+            "No credentials are configured for {target} with user '{username}'; add them with `uv index credentials set {} --username {username}`",
+            target.selector()
+        );
+    }
+
+    let secret = if username == TOKEN_USERNAME {
+        "token"
+    } else {
+        "password"
+    };
+    let scope = target.keyring_scope(&auth_config);
+    if dry_run {
+        writeln!(
+            printer.stderr(),
+            "Would replace the {secret} for user '{username}' in the keyring under `{}`",
+            scoped_service_name(url, scope)
+        )?;
+        return Ok(());
+    }
+
+    let password = match password {
+        Some(p) => SecretString::new(p),
+        None if password_stdin => read_password_stdin(std::io::stdin().lock())?,
+        None => match prompt_password_to_store(keyring_provider, secret).await? {
+            Some(p) => p,
+            None => bail!(
+                "No {secret} provided and no terminal to prompt for one; use `--password` or `--password-stdin`"
+            ),
+        },
+    };
+    let confirm = ProcessTerminals.prompt_term().map(|term| {
+        move |message: &str| {
+            uv_console::confirm(message, &term, false).context("Failed to read confirmation")
+        }
+    });
+    let Some(password) = check_secret(password, secret, force, confirm)? else {
+        debug!("Not replacing the {secret} for {target}");
+        return Ok(());
+    };
+
+    debug!("Will replace password for {target} with URL {url} and user {username} in keyring");
+    store_password(keyring_provider, url, scope, &username, &password).await?;
+
+    writeln!(
+        printer.stderr(),
+        "Rotated the {secret} for {target} (user '{username}')"
+    )?;
+    Ok(())
+}
+
+/// Store a password in the keyring, and check that it was stored by reading it back.
+///
+/// The existing entry is overwritten rather than removed first, so that a failed write leaves the
+/// previous password in place.
+async fn store_password(
+    keyring_provider: &mut KeyringProvider,
+    url: &Url,
+    scope: KeyringScope,
+    username: &str,
+    password: &SecretString,
+) -> Result<()> {
+    keyring_provider
+        .set_scoped(url, username, password.expose(), scope)
+        .await;
+    let stored = keyring_provider.fetch_scoped(url, username, scope).await;
+    if stored.as_ref().and_then(Credentials::password) != Some(password) {
+        bail!(
+            "Failed to store the new password for user '{username}' in the keyring; the previous credentials were left unchanged"
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn store_password_keeps_previous_on_failure() {
+        let url = Url::parse("https://example.com/simple").unwrap();
+
+        let mut keyring = KeyringProvider::dummy([(("https://example.com", "user"), "old")]);
+        store_password(
+            &mut keyring,
+            &url,
+            KeyringScope::Host,
+            "user",
+            &"new".into(),
+        )
+        .await
+        .unwrap();
+        let credentials = keyring.fetch(&url, "user").await.unwrap();
+        assert_eq!(
+            credentials.password().map(SecretString::expose),
+            Some("new")
+        );
+
+        // A failed write is reported, and the previous password survives.
+        let mut keyring =
+            KeyringProvider::read_only_dummy([(("https://example.com", "user"), "old")]);
+        let err = store_password(
+            &mut keyring,
+            &url,
+            KeyringScope::Host,
+            "user",
+            &"new".into(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Failed to store the new password for user 'user' in the keyring; the previous credentials were left unchanged"
+        );
+        let credentials = keyring.fetch(&url, "user").await.unwrap();
+        assert_eq!(
+            credentials.password().map(SecretString::expose),
+            Some("old")
+        );
+    }
+}
@@ -0,0 +1,331 @@
+use std::fmt::Write;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use owo_colors::OwoColorize;
+use reqwest_middleware::ClientWithMiddleware;
+use tracing::warn;
+
+use uv_auth::{AuthConfig, KeyringMetrics, NetrcProvider};
+use uv_client::{AuthIntegration, BaseClientBuilder};
+use uv_configuration::KeyringProviderType;
+use uv_distribution_types::{unique_indexes, Index};
+
+use crate::commands::index::check::{check_configured_index, probe_index, CredentialCheck};
+use crate::commands::index::keyring::IndexKeyrings;
+use crate::commands::index::login::login;
+use crate::commands::index::prompt::ProcessTerminals;
+use crate::commands::index::target::{embedded_credentials, CredentialTarget};
+use crate::commands::index::CredentialsError;
+use crate::commands::ExitStatus;
+use crate::printer::Printer;
+use crate::settings::{AuthSetupSettings, IndexAddCredentialsSettings, NetworkSettings};
+
+/// Set up the credentials for each named index, in order, e.g., after cloning a project.
+///
+/// An index whose credentials are accepted, or that can be read without credentials, is skipped,
+/// so that running the setup again continues with the indexes that still need credentials. For
+/// the others, the usernames known for the index are shown, the credentials are stored as with
+/// [`login`], and then checked against the index. Indexes with a credential helper are left as
+/// they are.
+///
+/// Without a terminal to prompt on, nothing is stored, and the command fails with the indexes
+/// that need credentials. Returns a failure if any index rejected the credentials or cannot be
+/// reached.
+pub(crate) async fn setup_credentials(
+    settings: AuthSetupSettings,
+    network_settings: &NetworkSettings,
+    printer: Printer,
+) -> Result<ExitStatus> {
+    let AuthSetupSettings {
+        keyring_timeout,
+        keyring_provider,
+        default_keyring_provider,
+        index: indexes,
+    } = settings;
+
+    // Credentials are stored for an index by name, as with `uv auth login`.
+    let configured = unique_indexes(&indexes)
+        .filter(|index| index.name.is_some())
+        .collect::<Vec<_>>();
+    if configured.is_empty() {
+        writeln!(printer.stderr(), "No indexes configured.")?;
+        return Ok(ExitStatus::Success);
+    }
+
+    let interactive = ProcessTerminals.prompt_term().is_some();
+    let netrc = NetrcProvider::load();
+    // As in `check_credentials`, only the credentials from the environment, the keyring, or a
+    // netrc file are attached.
+    let client = BaseClientBuilder::new()
+        .connectivity(network_settings.connectivity)
+        .native_tls(network_settings.native_tls)
+        .allow_insecure_host(network_settings.allow_insecure_host.clone())
+        .auth_integration(AuthIntegration::NoAuthMiddleware)
+        .build();
+    let mut states = Vec::with_capacity(configured.len());
+    for index in configured.iter().copied() {
+        let target = CredentialTarget::Index(index);
+        let url = index.raw_url();
+        let (stored, auth_config) = check_stored_credentials(
+            client.for_host(url),
+            index,
+            keyring_provider,
+            default_keyring_provider,
+            keyring_timeout,
+            &network_settings.keyring_metrics,
+            netrc.as_ref(),
+        )
+        .await?;
+        if stored == CredentialCheck::Accepted {
+            states.push(SetupState::Ready { stored: false });
+            continue;
+        }
+        if target
+            .entry(&auth_config)
+            .is_some_and(|entry| entry.helper.is_some())
+        {
+            states.push(SetupState::Helper);
+            continue;
+        }
+
+        let state = match probe_index(client.for_host(url), url).await {
+            CredentialCheck::Accepted => SetupState::Open,
+            // Without a terminal, credentials that the index rejected can't be replaced, and
+            // are reported as such rather than as missing.
+            CredentialCheck::Rejected { .. }
+                if matches!(stored, CredentialCheck::Unavailable { .. })
+                    || (!interactive && matches!(stored, CredentialCheck::Rejected { .. })) =>
+            {
+                SetupState::Failed(stored)
+            }
+            CredentialCheck::Rejected { .. } if !interactive => SetupState::NeedsCredentials,
+            CredentialCheck::Rejected { .. } => {
+                writeln!(
+                    printer.stderr(),
+                    "Setting up {target} ({})",
+                    index.url.redacted()
+                )?;
+                let hints = username_hints(index, &auth_config);
+                if !hints.is_empty() {
+                    writeln!(printer.stderr(), "Known usernames: {}", hints.join(", "))?;
+                }
+                // A username in the URL of the index takes precedence, so it isn't asked for.
+                let username = embedded_credentials(index)
+                    .and_then(|credentials| credentials.username().map(str::to_string));
+                let settings = IndexAddCredentialsSettings {
+                    name: index.name.as_ref().map(ToString::to_string),
+                    username,
+                    keyring_timeout,
+                    keyring_provider,
+                    default_keyring_provider,
+                    index: indexes.clone(),
+                    ..IndexAddCredentialsSettings::default()
+                };
+                login(settings, &network_settings.keyring_metrics, printer).await?;
+                let (check, _) = check_stored_credentials(
+                    client.for_host(url),
+                    index,
+                    keyring_provider,
+                    default_keyring_provider,
+                    keyring_timeout,
+                    &network_settings.keyring_metrics,
+                    netrc.as_ref(),
+                )
+                .await?;
+                match check {
+                    CredentialCheck::Accepted => SetupState::Ready { stored: true },
+                    check => SetupState::Failed(check),
+                }
+            }
+            probe => SetupState::Failed(probe),
+        };
+        states.push(state);
+    }
+
+    for (index, state) in configured.iter().copied().zip(&states) {
+        let index_name = index
+            .name
+            .as_ref()
+            .expect("Index should have a name")
+            .to_string();
+        writeln!(
+            printer.stderr(),
+            "{} {}",
+            format!("Index: {index_name}").bold(),
+            state.message()
+        )?;
+    }
+
+    let missing = configured
+        .iter()
+        .copied()
+        .zip(&states)
+        .filter(|(_, state)| **state == SetupState::NeedsCredentials)
+        .map(|(index, _)| {
+            format!(
+                "{} ({})",
+                CredentialTarget::Index(index),
+                index.url.redacted()
+            )
+        })
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        return Err(CredentialsError::NoTerminalForSetup { missing }.into());
+    }
+    if states.iter().any(SetupState::is_failure) {
+        Ok(ExitStatus::Failure)
+    } else {
+        Ok(ExitStatus::Success)
+    }
+}
+
+/// Check the stored credentials for an index against it, see [`check_configured_index`].
+///
+/// The auth config is loaded for each check, since storing the credentials of an index changes
+/// it, and returned along with the check.
+async fn check_stored_credentials(
+    client: &ClientWithMiddleware,
+    index: &Index,
+    keyring_provider: Option<KeyringProviderType>,
+    default_keyring_provider: KeyringProviderType,
+    keyring_timeout: Option<Duration>,
+    keyring_metrics: &Arc<KeyringMetrics>,
+    netrc: Option<&NetrcProvider>,
+) -> Result<(CredentialCheck, AuthConfig)> {
+    let auth_config =
+        AuthConfig::load().inspect_err(|err| warn!("Could not load auth config due to: {err}"))?;
+    let keyrings = IndexKeyrings::new(
+        keyring_provider,
+        default_keyring_provider,
+        keyring_timeout,
+        keyring_metrics,
+        &[index],
+        &auth_config,
+    );
+    let check = check_configured_index(
+        client,
+        index,
+        &auth_config,
+        keyrings.for_index(index, &auth_config),
+        netrc,
+    )
+    .await;
+    Ok((check, auth_config))
+}
+
+/// The state of an index after [`setup_credentials`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SetupState {
+    /// The index accepted its credentials, which were stored by the setup if `stored`.
+    Ready { stored: bool },
+    /// The index can be read without credentials.
+    Open,
+    /// The index has a credential helper, which the setup leaves as it is.
+    Helper,
+    /// The index requires credentials, but there is no terminal to prompt for them.
+    NeedsCredentials,
+    /// The index could not be set up, e.g., since it rejected the credentials.
+    Failed(CredentialCheck),
+}
+
+impl SetupState {
+    /// Whether the state should fail the command.
+    fn is_failure(&self) -> bool {
+        matches!(self, Self::NeedsCredentials | Self::Failed(_))
+    }
+
+    fn message(&self) -> String {
+        match self {
+            Self::Ready { stored: false } => "is already set up".to_string(),
+            Self::Ready { stored: true } => "accepted the new credentials".to_string(),
+            Self::Open => "doesn't require credentials".to_string(),
+            Self::Helper => "uses a credential helper".to_string(),
+            Self::NeedsCredentials => "needs credentials".to_string(),
+            Self::Failed(check) => check.message(),
+        }
+    }
+}
+
+/// The usernames known for an index, to show before prompting for its credentials, e.g.,
+/// `svc-bot (project auth config)`.
+///
+/// The username in the URL of the index comes first, since it takes precedence, followed by the
+/// usernames recorded in the auth config, e.g., committed with the project.
+fn username_hints(index: &Index, auth_config: &AuthConfig) -> Vec<String> {
+    let embedded = embedded_credentials(index).and_then(|credentials| {
+        credentials
+            .username()
+            .map(|username| format!("{username} (URL)"))
+    });
+    let recorded = auth_config
+        .find_entry(index.raw_url())
+        .into_iter()
+        .flat_map(|entry| {
+            entry
+                .usernames
+                .iter()
+                .map(move |username| format!("{username} ({} auth config)", entry.layer))
+        });
+    embedded.into_iter().chain(recorded).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::StatusCode;
+
+    use uv_auth::ConfigLayer;
+
+    use crate::commands::index::testing::index;
+
+    use super::*;
+
+    /// The username in the URL of an index comes before the usernames in the auth config, which
+    /// are shown with the auth config they're from.
+    #[test]
+    fn setup_username_hints() {
+        let plain = index("internal", "https://example.com/simple");
+        let embedded = index("embedded", "https://svc@example.org/simple");
+
+        let mut auth_config = AuthConfig::default();
+        assert!(username_hints(&plain, &auth_config).is_empty());
+        assert_eq!(
+            username_hints(&embedded, &auth_config),
+            ["svc (URL)".to_string()]
+        );
+
+        auth_config.add_entry(plain.raw_url(), "svc-bot".to_string());
+        auth_config.add_entry(embedded.raw_url(), "other".to_string());
+        for entry in auth_config.indexes.values_mut() {
+            entry.layer = ConfigLayer::Project;
+        }
+        assert_eq!(
+            username_hints(&plain, &auth_config),
+            ["svc-bot (project auth config)".to_string()]
+        );
+        assert_eq!(
+            username_hints(&embedded, &auth_config),
+            [
+                "svc (URL)".to_string(),
+                "other (project auth config)".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn setup_state_failure() {
+        assert!(!SetupState::Ready { stored: true }.is_failure());
+        assert!(!SetupState::Open.is_failure());
+        assert!(!SetupState::Helper.is_failure());
+        assert!(SetupState::NeedsCredentials.is_failure());
+        let rejected = SetupState::Failed(CredentialCheck::Rejected {
+            status: StatusCode::FORBIDDEN,
+        });
+        assert!(rejected.is_failure());
+        assert_eq!(
+            rejected.message(),
+            "rejected the credentials (403 Forbidden)"
+        );
+    }
+}
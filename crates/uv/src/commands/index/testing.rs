@@ -0,0 +1,136 @@
+use std::str::FromStr;
+
+use url::Url;
+
+use uv_auth::{ConfigLayer, KeyringProvider};
+use uv_configuration::KeyringProviderType;
+use uv_distribution_types::{Index, IndexName, IndexUrl};
+
+use crate::commands::index::keyring::IndexKeyrings;
+use crate::commands::index::prompt::{PromptTerm, Terminals};
+use crate::commands::index::set::set_credentials;
+use crate::commands::index::unset::unset_credentials;
+use crate::printer::Printer;
+use crate::settings::{IndexAddCredentialsSettings, IndexUnsetCredentialsSettings};
+
+pub(super) fn index(name: &str, url: &str) -> Index {
+    Index {
+        name: Some(IndexName::from_str(name).unwrap()),
+        ..Index::from_extra_index_url(IndexUrl::from_str(url).unwrap())
+    }
+}
+
+/// Serializes the tests that change the paths of the auth config, which are global.
+pub(super) static AUTH_CONFIG_PATHS: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+pub(super) async fn set_layer(
+    keyring_provider: &mut KeyringProvider,
+    indexes: &[Index],
+    username: &str,
+    layer: ConfigLayer,
+) {
+    set_credentials(
+        IndexAddCredentialsSettings {
+            name: Some("internal".to_string()),
+            username: Some(username.to_string()),
+            password: Some("password".to_string()),
+            force: true,
+            layer,
+            index: indexes.to_vec(),
+            ..IndexAddCredentialsSettings::default()
+        },
+        keyring_provider,
+        Printer::Quiet,
+    )
+    .await
+    .unwrap();
+}
+
+pub(super) async fn unset_layers(
+    keyring_provider: &mut KeyringProvider,
+    indexes: &[Index],
+    username: &str,
+    all_layers: bool,
+) {
+    unset_credentials(
+        IndexUnsetCredentialsSettings {
+            name: Some("internal".to_string()),
+            username: Some(username.to_string()),
+            all_layers,
+            index: indexes.to_vec(),
+            ..IndexUnsetCredentialsSettings::default()
+        },
+        Some(keyring_provider),
+        Printer::Quiet,
+    )
+    .await
+    .unwrap();
+}
+
+/// Return whether the default keyring has credentials for the URL and username.
+pub(super) async fn stored(keyrings: &IndexKeyrings, url: &Url, username: &str) -> bool {
+    let keyring = keyrings.for_recorded(None).unwrap();
+    keyring.try_fetch(url, username).await.unwrap().is_some()
+}
+
+pub(super) fn dummy_keyrings(entries: &[(&str, &str)]) -> IndexKeyrings {
+    IndexKeyrings {
+        keyring_provider: None,
+        default_keyring_provider: KeyringProviderType::Subprocess,
+        providers: vec![(
+            KeyringProviderType::Subprocess,
+            Some(KeyringProvider::dummy(entries.iter().map(
+                |(service, username)| ((*service, *username), "password"),
+            ))),
+        )],
+    }
+}
+
+/// The terminals of a process whose streams are attached to a terminal, or redirected.
+pub(super) struct FakeTerminals {
+    pub(super) stderr: bool,
+    pub(super) stdout: bool,
+    pub(super) controlling: bool,
+    pub(super) answer: &'static str,
+}
+
+/// A terminal that answers every prompt with `answer`.
+pub(super) struct FakeTerm {
+    pub(super) name: &'static str,
+    pub(super) answer: &'static str,
+}
+
+impl FakeTerminals {
+    pub(super) fn term(&self, name: &'static str, attached: bool) -> Option<FakeTerm> {
+        attached.then_some(FakeTerm {
+            name,
+            answer: self.answer,
+        })
+    }
+}
+
+impl Terminals for FakeTerminals {
+    type Term = FakeTerm;
+
+    fn stderr(&self) -> Option<FakeTerm> {
+        self.term("stderr", self.stderr)
+    }
+
+    fn stdout(&self) -> Option<FakeTerm> {
+        self.term("stdout", self.stdout)
+    }
+
+    fn controlling(&self) -> Option<FakeTerm> {
+        self.term("tty", self.controlling)
+    }
+}
+
+impl PromptTerm for FakeTerm {
+    fn input(&self, _prompt: &str) -> std::io::Result<String> {
+        Ok(self.answer.to_string())
+    }
+
+    fn password(&self, _prompt: &str) -> std::io::Result<String> {
+        Ok(self.answer.to_string())
+    }
+}
@@ -0,0 +1,710 @@
+use std::fmt::{Display, Write};
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context, Result};
+use itertools::Itertools;
+use serde::Serialize;
+use tracing::{debug, warn};
+use url::Url;
+
+use uv_auth::{
+    audit, AuditOperation, AuditOutcome, AuthConfig, ConfigLayer, Credentials,
+    IndexKeyringProvider, KeyringMetrics, KeyringProvider, KeyringScope,
+};
+use uv_distribution_types::{unique_indexes, Index};
+use uv_fs::Simplified;
+use uv_warnings::warn_user;
+
+use crate::commands::index::keyring::{scoped_service_name, IndexKeyrings};
+use crate::commands::index::prompt::prompt_username_input;
+use crate::commands::index::target::{select_target, CredentialTarget};
+use crate::commands::index::{
+    format_entries, format_usernames, resolve_username, restore_password, CredentialsError,
+};
+use crate::printer::Printer;
+use crate::settings::IndexUnsetCredentialsSettings;
+
+/// Remove the credentials for an index, or a URL, from the keyring and the auth config.
+///
+/// Only the layer of the auth config that the entry is used from is changed, unless `all_layers`
+/// is set. With `dry_run`, the keyring services and the auth config that would be changed are
+/// printed instead.
+///
+/// Without a `keyring_provider`, i.e., if the keyring is disabled, only the auth config is changed,
+/// after a warning that the password, if any, is left in the keyring.
+///
+/// Credentials stored with [`KeyringScope::Url`] are removed for the URL of the index only. The
+/// username is kept in the auth config entry, which the indexes on a host share, if another index
+/// on the host still has credentials for it.
+///
+/// Returns what was removed, e.g., to report whether there were any credentials for the username.
+pub(crate) async fn unset_credentials(
+    settings: IndexUnsetCredentialsSettings,
+    keyring_provider: Option<&mut KeyringProvider>,
+    printer: Printer,
+) -> Result<RemovedCredentials> {
+    let IndexUnsetCredentialsSettings {
+        name,
+        url,
+        username,
+        all_layers,
+        dry_run,
+        index: indexes,
+        ..
+    } = settings;
+    let target = select_target(&indexes, name.as_deref(), url.as_deref())?;
+    let url = target.url();
+
+    let auth_config =
+        AuthConfig::load().inspect_err(|err| warn!("Could not load auth config due to: {err}"))?;
+    // If the URL of the index changed, the entry and the keyring entries are those of the URL the
+    // credentials were created for.
+    let drifted = target.drifted_entry(&auth_config);
+    let recorded = drifted.and_then(|entry| entry.url.clone());
+    let configured = drifted.map_or_else(
+        || target.usernames(&auth_config),
+        |entry| entry.usernames.clone(),
+    );
+    let scope = drifted.map_or_else(
+        || target.keyring_scope(&auth_config),
+        |entry| entry.keyring_scope,
+    );
+
+    let Some(username) = resolve_username(username, &configured, || prompt_username_input(None))?
+    else {
+        return Err(CredentialsError::NoUsername.into());
+    };
+
+    let mut removed = RemovedCredentials {
+        target: target.to_string(),
+        index: target.name(),
+        url: target.redacted_url(),
+        username: username.clone(),
+        service: scoped_service_name(url, scope),
+        keyring: false,
+        config: false,
+        dry_run,
+    };
+
+    // Keep the entry of other users, whose passwords are still in the keyring.
+    let keep_entry = !configured.is_empty() && !configured.contains(&username);
+    let layers = if all_layers {
+        AuthConfig::layers()
+    } else {
+        vec![drifted
+            .or_else(|| target.entry(&auth_config))
+            .map_or(ConfigLayer::User, |entry| entry.layer)]
+    };
+
+    if dry_run {
+        let services = recorded
+            .iter()
+            .chain([url])
+            .map(|url| scoped_service_name(url, scope))
+            .unique()
+            .join("` and `");
+        if keyring_provider.is_some() {
+            writeln!(
+                printer.stderr(),
+                "Would remove the password for user '{username}' from the keyring under `{services}`"
+            )?;
+        } else {
+            writeln!(
+                printer.stderr(),
+                "Would leave the password for user '{username}', if any, in the keyring under `{services}`, since the keyring is disabled"
+            )?;
+        }
+        if keep_entry {
+            writeln!(
+                printer.stderr(),
+                "Would keep the auth config entry for {target}, which is configured with {}",
+                format_usernames(&configured)
+            )?;
+        } else {
+            let paths = layers
+                .iter()
+                .map(|layer| Ok(AuthConfig::layer_path(*layer)?.user_display().to_string()))
+                .collect::<Result<Vec<_>>>()?
+                .join("` and `");
+            writeln!(
+                printer.stderr(),
+                "Would remove user '{username}' for {target} from the auth config at `{paths}`"
+            )?;
+        }
+        return Ok(removed);
+    }
+
+    // Ctrl-C is deferred until both the keyring and the auth config are updated, and the password
+    // is restored if the auth config can't be, as in `set_credentials`.
+    let interrupts = uv_console::defer_interrupts().context("Failed to set the Ctrl-C handler")?;
+
+    let mut keyring_provider = keyring_provider;
+    let mut previous = None;
+    if let Some(keyring_provider) = keyring_provider.as_deref_mut() {
+        // The password is kept to restore it, if the entry can't be removed from the auth config.
+        if !keep_entry {
+            previous = keyring_provider.fetch_scoped(url, &username, scope).await;
+        }
+        if let Some(recorded) = &recorded {
+            if matches!(keyring_provider.try_fetch(url, &username).await, Ok(None)) {
+                debug!("Unsetting the credentials for {recorded}, which they were created for");
+                removed.keyring |= keyring_provider
+                    .unset_scoped(recorded, &username, scope)
+                    .await;
+            }
+        }
+        removed.keyring |= unset_password(keyring_provider, url, &username, scope).await;
+        if scope == KeyringScope::Url
+            && shares_scoped_credentials(keyring_provider, &target, &indexes, &username).await
+        {
+            warn_user!(
+                "Keeping user '{username}' in the auth config entry for {target}, which another index on the host uses"
+            );
+            return Ok(removed);
+        }
+    } else {
+        warn_user!(
+            "The keyring is disabled, so the password for user '{username}', if any, was left in the keyring under `{}`; use `--keyring-provider` to remove it",
+            scoped_service_name(url, scope)
+        );
+    }
+    audit(
+        AuditOperation::RemoveCredentials,
+        &scoped_service_name(url, scope),
+        Some(&username),
+        AuditOutcome::Removed,
+    );
+
+    if keep_entry {
+        warn_user!(
+            "Keeping the auth config entry for {target}, which is configured with {}",
+            format_usernames(&configured)
+        );
+        return Ok(removed);
+    }
+    let updated = layers
+        .into_iter()
+        .try_fold(false, |changed, layer| -> Result<bool> {
+            if interrupts.interrupted() {
+                return Err(anyhow!("Interrupted"));
+            }
+            let removed_entry = AuthConfig::update_layer(layer, |auth_config| {
+                if let Some(recorded) = &recorded {
+                    auth_config.remove_username(recorded, &username)
+                } else {
+                    target.remove_username(auth_config, &username)
+                }
+            })?;
+            Ok(changed | removed_entry)
+        });
+    match updated {
+        Ok(changed) => removed.config = changed,
+        Err(err) => {
+            warn!("Could not save auth config due to: {err}");
+            let (Some(keyring_provider), true) = (keyring_provider, removed.keyring) else {
+                return Err(err);
+            };
+            let restored = match previous.as_ref().and_then(Credentials::password) {
+                Some(previous) => {
+                    restore_password(keyring_provider, url, scope, &username, Some(previous)).await
+                }
+                None => false,
+            };
+            return Err(CredentialsError::ConfigNotUpdatedAfterRemoval {
+                service: removed.service,
+                restored,
+                err,
+            }
+            .into());
+        }
+    }
+    drop(interrupts);
+
+    Ok(removed)
+}
+
+/// The credentials of a username that [`unset_credentials`] removed.
+#[derive(Debug)]
+pub(crate) struct RemovedCredentials {
+    /// The index, or URL, the credentials were removed for, e.g., `index 'internal'`.
+    pub(super) target: String,
+    /// The name of the index, or `None` for a URL.
+    index: Option<String>,
+    pub(super) url: String,
+    pub(super) username: String,
+    /// The keyring service that the password was stored under.
+    service: String,
+    /// Whether the keyring had a password for the username.
+    keyring: bool,
+    /// Whether the auth config had an entry for the username.
+    config: bool,
+    /// Whether nothing was removed, with `--dry-run`.
+    pub(super) dry_run: bool,
+}
+
+impl RemovedCredentials {
+    /// Whether anything was removed, from either the keyring or the auth config.
+    pub(crate) fn any(&self) -> bool {
+        self.keyring || self.config
+    }
+
+    /// The outcome of the removal, for `--output-format json`.
+    fn outcome(&self) -> &'static str {
+        if self.dry_run {
+            "dry-run"
+        } else if self.any() {
+            "removed"
+        } else {
+            "not-found"
+        }
+    }
+}
+
+/// Written as a single object with `--output-format json`, with the
+/// [`RemovedCredentials::outcome`].
+impl Serialize for RemovedCredentials {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("RemovedCredentials", 7)?;
+        state.serialize_field("index", &self.index)?;
+        state.serialize_field("url", &self.url)?;
+        state.serialize_field("username", &self.username)?;
+        state.serialize_field("service", &self.service)?;
+        state.serialize_field("keyring", &self.keyring)?;
+        state.serialize_field("config", &self.config)?;
+        state.serialize_field("outcome", self.outcome())?;
+        state.end()
+    }
+}
+
+impl Display for RemovedCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self {
+            target, username, ..
+        } = self;
+        let removed = match (self.keyring, self.config) {
+            (true, true) => "keyring entry and config entry",
+            (true, false) => "keyring entry",
+            (false, true) => "config entry",
+            (false, false) => {
+                return write!(
+                    f,
+                    "No credentials were stored for {target} (user '{username}')"
+                );
+            }
+        };
+        write!(f, "Removed {removed} for {target} (user '{username}')")
+    }
+}
+
+/// Remove the password for a username from the keyring, under the service name of the given
+/// scope.
+///
+/// With [`KeyringScope::Host`], e.g., if the auth config doesn't record the scope, the password
+/// stored for the URL itself is removed as well, since it isn't shared with any other index. The
+/// password stored for the host is kept with [`KeyringScope::Url`], since other indexes on the
+/// host may use it.
+///
+/// Returns whether a password was removed.
+async fn unset_password(
+    keyring_provider: &mut KeyringProvider,
+    url: &Url,
+    username: &str,
+    scope: KeyringScope,
+) -> bool {
+    let mut removed = keyring_provider.unset_scoped(url, username, scope).await;
+    if scope.is_host() {
+        removed |= keyring_provider
+            .unset_scoped(url, username, KeyringScope::Url)
+            .await;
+    }
+    removed
+}
+
+/// Whether the keyring has credentials for the username stored with [`KeyringScope::Url`] for
+/// another index on the same host as the target, which shares its entry in the auth config.
+async fn shares_scoped_credentials(
+    keyring_provider: &KeyringProvider,
+    target: &CredentialTarget<'_>,
+    indexes: &[Index],
+    username: &str,
+) -> bool {
+    let url = target.url();
+    for index in indexes {
+        let other = index.raw_url();
+        if other == url || AuthConfig::url_to_string(other) != AuthConfig::url_to_string(url) {
+            continue;
+        }
+        if keyring_provider
+            .fetch_scoped(other, username, KeyringScope::Url)
+            .await
+            .is_some()
+        {
+            return true;
+        }
+    }
+    false
+}
+
+/// Remove the credentials of every entry in the auth config, from the keyring and the auth config.
+///
+/// Entries for hosts that no index is configured for are removed using the index URL recorded on
+/// the entry. Entries without a recorded URL are skipped and kept, since the URL their credentials
+/// are stored for in the keyring is unknown.
+pub(crate) async fn unset_all_credentials(
+    settings: IndexUnsetCredentialsSettings,
+    keyring_metrics: &Arc<KeyringMetrics>,
+    printer: Printer,
+) -> Result<()> {
+    let IndexUnsetCredentialsSettings {
+        dry_run,
+        keyring_timeout,
+        keyring_provider,
+        default_keyring_provider,
+        index: indexes,
+        ..
+    } = settings;
+    let auth_config =
+        AuthConfig::load().inspect_err(|err| warn!("Could not load auth config due to: {err}"))?;
+    let effective = unique_indexes(&indexes).collect::<Vec<_>>();
+    let mut keyrings = IndexKeyrings::new(
+        keyring_provider,
+        default_keyring_provider,
+        keyring_timeout,
+        keyring_metrics,
+        &effective,
+        &auth_config,
+    );
+    let removed =
+        remove_all_credentials(&auth_config, &effective, &mut keyrings, dry_run, printer).await?;
+
+    if dry_run {
+        writeln!(
+            printer.stderr(),
+            "Would remove {}, skipping {}",
+            format_entries(removed.len()),
+            removed.skipped
+        )?;
+        return Ok(());
+    }
+
+    AuthConfig::update(|auth_config| {
+        for key in &removed.indexes {
+            auth_config.indexes.remove(key);
+        }
+        for key in &removed.urls {
+            auth_config.urls.remove(key);
+        }
+    })?;
+    writeln!(
+        printer.stderr(),
+        "Removed {}, skipped {}",
+        format_entries(removed.len()),
+        removed.skipped
+    )?;
+
+    Ok(())
+}
+
+/// The keys of the auth config entries whose credentials were removed from the keyring.
+#[derive(Debug, Default)]
+struct RemovedEntries {
+    /// The keys of the removed index entries.
+    indexes: Vec<String>,
+    /// The keys of the removed URL entries.
+    urls: Vec<String>,
+    /// The number of entries that were skipped.
+    skipped: usize,
+}
+
+impl RemovedEntries {
+    fn len(&self) -> usize {
+        self.indexes.len() + self.urls.len()
+    }
+}
+
+/// Remove the keyring entries of every entry in the auth config, see [`unset_all_credentials`].
+async fn remove_all_credentials(
+    auth_config: &AuthConfig,
+    indexes: &[&Index],
+    keyrings: &mut IndexKeyrings,
+    dry_run: bool,
+    printer: Printer,
+) -> Result<RemovedEntries> {
+    let mut removed = RemovedEntries::default();
+
+    let mut keys = auth_config.indexes.keys().collect::<Vec<_>>();
+    keys.sort();
+    for key in keys {
+        let entry = &auth_config.indexes[key];
+        let index = indexes.iter().find(|index| {
+            auth_config
+                .find_entry(index.raw_url())
+                .is_some_and(|found| std::ptr::eq(found, entry))
+        });
+        let target = match (index, &entry.url) {
+            (Some(index), _) => CredentialTarget::Index(index),
+            (None, Some(url)) => CredentialTarget::Url(url.clone()),
+            (None, None) => {
+                warn_user!(
+                    "Skipping the credentials for `{key}`, which no configured index uses; remove them from the keyring manually"
+                );
+                removed.skipped += 1;
+                continue;
+            }
+        };
+        remove_entry_credentials(
+            &target,
+            &entry.usernames,
+            entry.keyring_provider,
+            entry.keyring_scope,
+            keyrings,
+            dry_run,
+            printer,
+        )
+        .await?;
+        removed.indexes.push(key.clone());
+    }
+
+    let mut keys = auth_config.urls.keys().collect::<Vec<_>>();
+    keys.sort();
+    for key in keys {
+        let entry = &auth_config.urls[key];
+        let Ok(url) = Url::parse(key) else {
+            warn_user!("Skipping the credentials for `{key}`, which is not a valid URL");
+            removed.skipped += 1;
+            continue;
+        };
+        let target = CredentialTarget::Url(url);
+        remove_entry_credentials(
+            &target,
+            &entry.usernames,
+            entry.keyring_provider,
+            entry.keyring_scope,
+            keyrings,
+            dry_run,
+            printer,
+        )
+        .await?;
+        removed.urls.push(key.clone());
+    }
+
+    Ok(removed)
+}
+
+/// Remove the keyring entries for the usernames of an entry in the auth config.
+pub(super) async fn remove_entry_credentials(
+    target: &CredentialTarget<'_>,
+    usernames: &[String],
+    recorded: Option<IndexKeyringProvider>,
+    scope: KeyringScope,
+    keyrings: &mut IndexKeyrings,
+    dry_run: bool,
+    printer: Printer,
+) -> Result<()> {
+    for username in usernames {
+        if dry_run {
+            writeln!(
+                printer.stderr(),
+                "Would remove the credentials for {target} with user '{username}'"
+            )?;
+            continue;
+        }
+        if let Some(keyring) = keyrings.for_recorded_mut(recorded) {
+            unset_password(keyring, target.url(), username, scope).await;
+        }
+        writeln!(
+            printer.stderr(),
+            "Removed the credentials for {target} with user '{username}'"
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use uv_auth::SecretString;
+
+    use crate::commands::index::error_code;
+    use crate::commands::index::testing::{dummy_keyrings, index, stored, AUTH_CONFIG_PATHS};
+
+    use super::*;
+
+    /// Unsetting reports whether the keyring had a password and the auth config an entry.
+    #[tokio::test]
+    async fn unset_credentials_reports_removed() {
+        let _paths = AUTH_CONFIG_PATHS.lock().await;
+        let dir = tempfile::tempdir().unwrap();
+        uv_auth::set_test_config_path(dir.path().join("auth.toml"));
+
+        let indexes = vec![index("internal", "https://internal.example.com/simple")];
+        let url = indexes[0].raw_url().clone();
+        AuthConfig::update(|auth_config| {
+            auth_config.add_entry(&url, "bot".to_string());
+            auth_config.add_entry(&url, "config-only".to_string());
+        })
+        .unwrap();
+        let mut keyring_provider = KeyringProvider::dummy([
+            (("https://internal.example.com", "bot"), "password"),
+            (("https://internal.example.com", "keyring-only"), "password"),
+        ]);
+
+        for (username, expected) in [
+            (
+                "bot",
+                "Removed keyring entry and config entry for index 'internal' (user 'bot')",
+            ),
+            (
+                "config-only",
+                "Removed config entry for index 'internal' (user 'config-only')",
+            ),
+            (
+                "bot",
+                "No credentials were stored for index 'internal' (user 'bot')",
+            ),
+            (
+                "keyring-only",
+                "Removed keyring entry for index 'internal' (user 'keyring-only')",
+            ),
+        ] {
+            let removed = unset_credentials(
+                IndexUnsetCredentialsSettings {
+                    name: Some("internal".to_string()),
+                    username: Some(username.to_string()),
+                    index: indexes.clone(),
+                    ..IndexUnsetCredentialsSettings::default()
+                },
+                Some(&mut keyring_provider),
+                Printer::Quiet,
+            )
+            .await
+            .unwrap();
+            assert_eq!(removed.to_string(), expected);
+            assert_eq!(removed.any(), !expected.starts_with("No credentials"));
+        }
+        assert!(AuthConfig::load().unwrap().find_entry(&url).is_none());
+
+        uv_auth::reset_config_path();
+    }
+
+    #[tokio::test]
+    async fn remove_all_credentials_skips_missing_indexes() {
+        let indexes = [index("internal", "https://internal.example.com/simple")];
+        let indexes = indexes.iter().collect::<Vec<_>>();
+        let gone = Url::parse("https://gone.example.com/simple").unwrap();
+        let legacy = Url::parse("https://legacy.example.com/simple").unwrap();
+        let wheel =
+            Url::parse("https://files.example.com/wheels/pkg-1.0-py3-none-any.whl").unwrap();
+
+        let mut auth_config = AuthConfig::default();
+        auth_config.add_entry(indexes[0].raw_url(), "user".to_string());
+        auth_config.add_entry(indexes[0].raw_url(), "ci-bot".to_string());
+        auth_config.add_entry(&gone, "user".to_string());
+        auth_config.add_entry(&legacy, "user".to_string());
+        auth_config.add_url_entry(&wheel, "user".to_string());
+        // An entry written before the index URL was recorded.
+        auth_config
+            .indexes
+            .get_mut("legacy.example.com")
+            .unwrap()
+            .url = None;
+
+        let mut keyrings = dummy_keyrings(&[
+            ("https://internal.example.com", "user"),
+            ("https://internal.example.com", "ci-bot"),
+            ("https://gone.example.com", "user"),
+            ("https://legacy.example.com", "user"),
+            ("https://files.example.com", "user"),
+        ]);
+
+        // A dry run reports the entries, but leaves the keyring untouched.
+        let removed =
+            remove_all_credentials(&auth_config, &indexes, &mut keyrings, true, Printer::Quiet)
+                .await
+                .unwrap();
+        assert_eq!(
+            removed.indexes,
+            ["gone.example.com", "internal.example.com"]
+        );
+        assert_eq!(
+            removed.urls,
+            ["https://files.example.com/wheels/pkg-1.0-py3-none-any.whl"]
+        );
+        assert_eq!(removed.skipped, 1);
+        assert!(stored(&keyrings, indexes[0].raw_url(), "ci-bot").await);
+        assert!(stored(&keyrings, &wheel, "user").await);
+
+        // The credentials of an index that is no longer configured are found by the recorded
+        // URL, if any.
+        let removed =
+            remove_all_credentials(&auth_config, &indexes, &mut keyrings, false, Printer::Quiet)
+                .await
+                .unwrap();
+        assert_eq!(removed.len(), 3);
+        assert_eq!(removed.skipped, 1);
+        assert!(!stored(&keyrings, indexes[0].raw_url(), "user").await);
+        assert!(!stored(&keyrings, indexes[0].raw_url(), "ci-bot").await);
+        assert!(!stored(&keyrings, &wheel, "user").await);
+        assert!(!stored(&keyrings, &gone, "user").await);
+        assert!(stored(&keyrings, &legacy, "user").await);
+    }
+
+    /// If the auth config can't be stored, the removed password is restored in the keyring.
+    #[tokio::test]
+    async fn unset_credentials_rolls_back_on_store_failure() {
+        let _paths = AUTH_CONFIG_PATHS.lock().await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("auth.toml");
+        uv_auth::set_test_config_path(path.clone());
+        let auth_config = "version = 1\n\n[indexes.\"example.com\"]\nusernames = [\"user\"]\n";
+        fs_err::write(&path, auth_config).unwrap();
+        // The auth config can be loaded, but not locked to store it.
+        fs_err::create_dir(path.with_extension("toml.lock")).unwrap();
+
+        let indexes = vec![index("internal", "https://example.com/simple")];
+        let url = indexes[0].raw_url().clone();
+        let mut keyring_provider =
+            KeyringProvider::dummy([(("https://example.com", "user"), "secret")]);
+        let err = unset_credentials(
+            IndexUnsetCredentialsSettings {
+                name: Some("internal".to_string()),
+                username: Some("user".to_string()),
+                index: indexes.clone(),
+                ..IndexUnsetCredentialsSettings::default()
+            },
+            Some(&mut keyring_provider),
+            Printer::Quiet,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error_code(&err), "auth-config-not-updated");
+        assert_eq!(
+            err.to_string(),
+            "Removed the password from the keyring under `https://example.com`, but failed to update the auth config; the password was restored in the keyring"
+        );
+        let credentials = keyring_provider.fetch(&url, "user").await.unwrap();
+        assert_eq!(
+            credentials.password().map(SecretString::expose),
+            Some("secret")
+        );
+        assert_eq!(fs_err::read_to_string(&path).unwrap(), auth_config);
+
+        // Without a password in the keyring, there is nothing to restore.
+        let err = unset_credentials(
+            IndexUnsetCredentialsSettings {
+                name: Some("internal".to_string()),
+                username: Some("user".to_string()),
+                index: indexes,
+                ..IndexUnsetCredentialsSettings::default()
+            },
+            Some(&mut KeyringProvider::empty()),
+            Printer::Quiet,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error_code(&err), "auth-config");
+
+        uv_auth::reset_config_path();
+    }
+}
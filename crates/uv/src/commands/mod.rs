@@ -7,12 +7,30 @@ use std::path::{Path, PathBuf};
 use std::time::Duration;
 use std::{fmt::Display, fmt::Write, process::ExitCode};
 
+pub(crate) use auth_provenance::auth_provenance;
 pub(crate) use build_frontend::build_frontend;
 pub(crate) use cache_clean::cache_clean;
 pub(crate) use cache_dir::cache_dir;
 pub(crate) use cache_prune::cache_prune;
+pub(crate) use completion::index_name_completion;
 pub(crate) use help::help;
-pub(crate) use index::{list_credentials, set_credentials, unset_credentials};
+pub(crate) use index::check::check_credentials;
+pub(crate) use index::export::export_credentials;
+pub(crate) use index::import::import_credentials;
+pub(crate) use index::keyring::{
+    keyring_provider_for, keyring_provider_for_index, optional_keyring_provider_for,
+};
+pub(crate) use index::list::{list_credentials, password_display, verify_auth_config};
+pub(crate) use index::login::login;
+pub(crate) use index::logout::logout;
+pub(crate) use index::names::list_index_names;
+pub(crate) use index::prune::prune_credentials;
+pub(crate) use index::rotate::rotate_credentials;
+pub(crate) use index::set::{set_credential_helper, set_credentials, set_proxy_credentials};
+pub(crate) use index::setup::setup_credentials;
+pub(crate) use index::target::{select_configured_index, with_default_index};
+pub(crate) use index::unset::{unset_all_credentials, unset_credentials};
+pub(crate) use index::{adopt_discovered_usernames, write_json_result};
 pub(crate) use pip::check::pip_check;
 pub(crate) use pip::compile::pip_compile;
 pub(crate) use pip::freeze::pip_freeze;
@@ -60,11 +78,13 @@ pub(crate) use version::version;
 
 use crate::printer::Printer;
 
+mod auth_provenance;
 pub(crate) mod build_backend;
 mod build_frontend;
 mod cache_clean;
 mod cache_dir;
 mod cache_prune;
+mod completion;
 mod diagnostics;
 mod help;
 mod index;
@@ -97,6 +97,8 @@ pub(crate) async fn pip_install(
         .connectivity(network_settings.connectivity)
         .native_tls(network_settings.native_tls)
         .keyring(keyring_provider)
+        .keyring_from_cli(network_settings.keyring_provider_from_cli)
+        .keyring_metrics(network_settings.keyring_metrics.clone())
         .allow_insecure_host(network_settings.allow_insecure_host.clone());
 
     // Read all requirements from the provided sources.
@@ -206,6 +206,8 @@ pub(crate) async fn tree(
             .native_tls(network_settings.native_tls)
             .connectivity(network_settings.connectivity)
             .keyring(*keyring_provider)
+            .keyring_from_cli(network_settings.keyring_provider_from_cli)
+            .keyring_metrics(network_settings.keyring_metrics.clone())
             .allow_insecure_host(network_settings.allow_insecure_host.clone())
             .build();
             let download_concurrency = Semaphore::new(concurrency.downloads);
@@ -56,6 +56,8 @@ pub(crate) async fn publish(
     let upload_client = BaseClientBuilder::new()
         .retries(0)
         .keyring(keyring_provider)
+        .keyring_from_cli(network_settings.keyring_provider_from_cli)
+        .keyring_metrics(network_settings.keyring_metrics.clone())
         .native_tls(network_settings.native_tls)
         .allow_insecure_host(network_settings.allow_insecure_host.clone())
         // Don't try cloning the request to make an unauthenticated request first.
@@ -96,6 +98,8 @@ pub(crate) async fn publish(
             .connectivity(network_settings.connectivity)
             .index_urls(index_urls)
             .keyring(keyring_provider)
+            .keyring_from_cli(network_settings.keyring_provider_from_cli)
+            .keyring_metrics(network_settings.keyring_metrics.clone())
             .allow_insecure_host(network_settings.allow_insecure_host.clone());
         Some(CheckUrlClient {
             index_url: index_url.clone(),
@@ -289,7 +293,10 @@ async fn gather_credentials(
 
     // If applicable, fetch the password from the keyring eagerly to avoid user confusion about
     // missing keyring entries later.
-    if let Some(keyring_provider) = keyring_provider.to_provider() {
+    if let Some(keyring_provider) = keyring_provider
+        .to_provider()
+        .map(|provider| provider.with_total_metrics(network_settings.keyring_metrics.clone()))
+    {
         if password.is_none() {
             if let Some(username) = &username {
                 debug!("Fetching password from keyring");
@@ -299,7 +306,7 @@ async fn gather_credentials(
                     .as_ref()
                     .and_then(|credentials| credentials.password())
                 {
-                    password = Some(keyring_password.to_string());
+                    password = Some(keyring_password.expose().to_string());
                 } else {
                     warn_user_once!(
                         "Keyring has no password for URL `{publish_url}` and username `{username}`"
@@ -2,33 +2,46 @@ use std::borrow::Cow;
 use std::ffi::OsString;
 use std::fmt::Write;
 use std::io::stdout;
+use std::iter;
 use std::path::Path;
 use std::process::ExitCode;
 use std::str::FromStr;
 use std::sync::atomic::Ordering;
+use std::sync::Arc;
 
 use anstream::eprintln;
 use anyhow::{bail, Context, Result};
 use clap::error::{ContextKind, ContextValue};
-use clap::{CommandFactory, Parser};
-use commands::{list_credentials, set_credentials, unset_credentials};
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches};
+use commands::{
+    check_credentials, export_credentials, import_credentials, keyring_provider_for,
+    keyring_provider_for_index, list_credentials, list_index_names, login, logout,
+    optional_keyring_provider_for, password_display, prune_credentials, rotate_credentials,
+    select_configured_index, set_credential_helper, set_credentials, set_proxy_credentials,
+    setup_credentials, unset_all_credentials, unset_credentials, verify_auth_config,
+    write_json_result,
+};
 use futures::FutureExt;
 use owo_colors::OwoColorize;
 use settings::{
-    IndexAddCredentialsSettings, IndexListCredentialsSettings, IndexUnsetCredentialsSettings,
+    AuthSetupSettings, IndexAddCredentialsSettings, IndexCheckCredentialsSettings,
+    IndexImportCredentialsSettings, IndexListCredentialsSettings, IndexNamesSettings,
+    IndexPruneCredentialsSettings, IndexRotateCredentialsSettings, IndexUnsetCredentialsSettings,
     PipTreeSettings,
 };
 use tokio::task::spawn_blocking;
 use tracing::{debug, instrument};
+use uv_auth::KeyringMetrics;
 use uv_cache::{Cache, Refresh};
 use uv_cache_info::Timestamp;
 use uv_cli::{
-    compat::CompatArgs, BuildBackendCommand, CacheCommand, CacheNamespace, Cli, Commands,
-    PipCommand, PipNamespace, ProjectCommand,
+    compat::CompatArgs, AuthCommand, AuthNamespace, BuildBackendCommand, CacheCommand,
+    CacheNamespace, Cli, Commands, PipCommand, PipNamespace, ProjectCommand,
 };
 use uv_cli::{
-    IndexCommand, IndexCredentialsCommand, IndexNamespace, PythonCommand, PythonNamespace,
-    ToolCommand, ToolNamespace, TopLevelArgs,
+    IndexCommand, IndexCredentialsCommand, IndexCredentialsFormat, IndexNamespace, PythonCommand,
+    PythonNamespace, ToolCommand, ToolNamespace, TopLevelArgs,
 };
 #[cfg(feature = "self-update")]
 use uv_cli::{SelfCommand, SelfNamespace, SelfUpdateArgs};
@@ -54,7 +67,11 @@ pub(crate) mod printer;
 pub(crate) mod settings;
 
 #[instrument(skip_all)]
-async fn run(mut cli: Cli) -> Result<ExitStatus> {
+async fn run(
+    mut cli: Cli,
+    keyring_provider_from_cli: bool,
+    keyring_metrics: Arc<KeyringMetrics>,
+) -> Result<ExitStatus> {
     // Enable flag to pick up warnings generated by workspace loading.
     if !cli.top_level.global_args.quiet {
         uv_warnings::enable();
@@ -132,6 +149,8 @@ async fn run(mut cli: Cli) -> Result<ExitStatus> {
     } else if let Ok(workspace) =
         Workspace::discover(&project_dir, &DiscoveryOptions::default()).await
     {
+        // Layer the auth config of the workspace over that of the user.
+        uv_auth::set_project_config_path(workspace.install_path().join(".uv").join("auth.toml"));
         let project = FilesystemOptions::find(workspace.install_path())?;
         let system = FilesystemOptions::system()?;
         let user = FilesystemOptions::user()?;
@@ -153,7 +172,12 @@ async fn run(mut cli: Cli) -> Result<ExitStatus> {
             ..
         }) = &mut **command
         {
-            let settings = GlobalSettings::resolve(&cli.top_level.global_args, filesystem.as_ref());
+            let settings = GlobalSettings::resolve(
+                &cli.top_level.global_args,
+                filesystem.as_ref(),
+                keyring_provider_from_cli,
+                keyring_metrics.clone(),
+            );
             Some(
                 RunCommand::from_args(
                     command,
@@ -263,7 +287,12 @@ async fn run(mut cli: Cli) -> Result<ExitStatus> {
         .combine(filesystem);
 
     // Resolve the global settings.
-    let globals = GlobalSettings::resolve(&cli.top_level.global_args, filesystem.as_ref());
+    let globals = GlobalSettings::resolve(
+        &cli.top_level.global_args,
+        filesystem.as_ref(),
+        keyring_provider_from_cli,
+        keyring_metrics,
+    );
 
     // Resolve the cache settings.
     let cache_settings = CacheSettings::resolve(*cli.top_level.cache_args, filesystem.as_ref());
@@ -331,6 +360,34 @@ async fn run(mut cli: Cli) -> Result<ExitStatus> {
     // Don't initialize the rayon threadpool yet, this is too costly when we're doing a noop sync.
     uv_configuration::RAYON_PARALLELISM.store(globals.concurrency.installs, Ordering::SeqCst);
 
+    // Restrict the hosts that the keyring is queried for.
+    uv_auth::set_keyring_host_policy(uv_auth::HostPolicy::resolve(
+        filesystem
+            .as_ref()
+            .and_then(|filesystem| filesystem.globals.keyring_allow_hosts.clone()),
+        filesystem
+            .as_ref()
+            .and_then(|filesystem| filesystem.globals.keyring_deny_hosts.clone()),
+    )?);
+    // Check the keyring entries of other tools, if enabled.
+    uv_auth::set_keyring_compat(
+        filesystem
+            .as_ref()
+            .and_then(|filesystem| filesystem.globals.keyring_compat.clone())
+            .unwrap_or_default()
+            .into_iter()
+            .map(uv_auth::KeyringCompat::from)
+            .collect(),
+    );
+    // Use the auth config at the given path, if any.
+    if let Commands::Index(IndexNamespace {
+        auth_config: Some(auth_config),
+        ..
+    }) = &*cli.command
+    {
+        uv_auth::set_config_path(auth_config.clone());
+    }
+
     debug!("uv {}", uv_cli::version::version());
 
     // Write out any resolved settings.
@@ -353,6 +410,11 @@ async fn run(mut cli: Cli) -> Result<ExitStatus> {
     // Configure the cache.
     let cache = Cache::from_settings(cache_settings.no_cache, cache_settings.cache_dir)?;
 
+    // Record which credentials fetched the entries of the cache, if enabled.
+    if std::env::var(EnvVars::UV_AUTH_PROVENANCE).as_deref() == Ok("1") {
+        uv_auth::set_provenance_log_path(Some(cache.provenance_log()));
+    }
+
     let result = match *cli.command {
         Commands::Help(args) => commands::help(
             args.command.unwrap_or_default().as_slice(),
@@ -780,7 +842,7 @@ async fn run(mut cli: Cli) -> Result<ExitStatus> {
             command: CacheCommand::Prune(args),
         }) => {
             show_settings!(args);
-            commands::cache_prune(args.ci, &cache, printer)
+            commands::cache_prune(args.ci, args.credential.as_deref(), &cache, printer)
         }
         Commands::Cache(CacheNamespace {
             command: CacheCommand::Dir,
@@ -927,6 +989,7 @@ async fn run(mut cli: Cli) -> Result<ExitStatus> {
         }
         Commands::GenerateShellCompletion(args) => {
             args.shell.generate(&mut Cli::command(), &mut stdout());
+            commands::index_name_completion(args.shell, &mut stdout())?;
             Ok(ExitStatus::Success)
         }
         Commands::Tool(ToolNamespace {
@@ -1373,65 +1436,261 @@ async fn run(mut cli: Cli) -> Result<ExitStatus> {
         })
         .await
         .expect("tokio threadpool exited unexpectedly"),
-        // Commands::Index(IndexNamespace {
-        //     command: IndexCommand::Set(args),
-        // }) => {
-        //     println!("This is not implemented yet!");
-        //     println!("Set an index with name {}", args.name);
-        //     return Ok(ExitStatus::Success);
-        // }
-        // Commands::Index(IndexNamespace {
-        //     command: IndexCommand::List(_),
-        // }) => {
-        //     println!("This is not implemented yet!");
-        //     println!("List all indexes");
-        //     return Ok(ExitStatus::Success);
-        // }
-        // Commands::Index(IndexNamespace {
-        //     command: IndexCommand::Unset(args),
-        // }) => {
-        //     println!("This is not implemented yet!");
-        //     println!("Unset index {}", args.name);
-        //     return Ok(ExitStatus::Success);
-        // }
         Commands::Index(IndexNamespace {
             command: IndexCommand::Credentials(IndexCredentialsCommand::Set(args)),
+            ..
         }) => {
-            let IndexAddCredentialsSettings {
-                name,
-                username,
-                password,
-                keyring_provider,
-                index,
-            } = IndexAddCredentialsSettings::resolve(args, filesystem);
+            let mut settings = IndexAddCredentialsSettings::resolve(args, filesystem);
+
+            if let Some(proxy) = settings.proxy.take() {
+                let mut keyring_provider = keyring_provider_for(
+                    settings
+                        .keyring_provider
+                        .unwrap_or(settings.default_keyring_provider),
+                    settings.keyring_timeout,
+                    &globals.network_settings.keyring_metrics,
+                )?;
+                set_proxy_credentials(&proxy, settings, &mut keyring_provider, printer).await?;
+                return Ok(ExitStatus::Success);
+            }
+
+            // Without an index or a URL, the index is selected from the configured indexes.
+            let name = match (settings.name.take(), &settings.url) {
+                (None, None) => select_configured_index(&settings.index, printer).map(Some),
+                (name, _) => Ok(name),
+            };
 
-            let _ = set_credentials(name, username, password, keyring_provider, index).await;
+            if let Some(helper) = settings.helper.take() {
+                set_credential_helper(
+                    name?.as_deref(),
+                    settings.url.as_deref(),
+                    helper,
+                    settings.dry_run,
+                    &settings.index,
+                    printer,
+                )?;
+                return Ok(ExitStatus::Success);
+            }
+
+            let format = settings.format;
+            let stored = async {
+                settings.name = name?;
+                let keyring_provider_type = keyring_provider_for_index(
+                    settings.name.as_deref(),
+                    settings.url.as_deref(),
+                    &settings.index,
+                    settings.keyring_provider,
+                    settings.default_keyring_provider,
+                )?;
+                let mut keyring_provider = keyring_provider_for(
+                    keyring_provider_type,
+                    settings.keyring_timeout,
+                    &globals.network_settings.keyring_metrics,
+                )?;
+                set_credentials(settings, &mut keyring_provider, printer).await
+            }
+            .await;
+            if matches!(format, IndexCredentialsFormat::Json) {
+                return write_json_result(stored, printer);
+            }
+            stored?;
             return Ok(ExitStatus::Success);
         }
         Commands::Index(IndexNamespace {
-            command: IndexCommand::Credentials(IndexCredentialsCommand::List(args)),
+            command: IndexCommand::Credentials(IndexCredentialsCommand::Rotate(args)),
+            ..
         }) => {
-            let IndexListCredentialsSettings {
+            let settings = IndexRotateCredentialsSettings::resolve(args, filesystem);
+
+            let keyring_provider = keyring_provider_for_index(
+                settings.name.as_deref(),
+                settings.url.as_deref(),
+                &settings.index,
+                settings.keyring_provider,
+                settings.default_keyring_provider,
+            )?;
+            let mut keyring_provider = keyring_provider_for(
                 keyring_provider,
-                index,
-            } = IndexListCredentialsSettings::resolve(&args, filesystem);
-
-            let _ = list_credentials(keyring_provider, index, printer).await;
+                settings.keyring_timeout,
+                &globals.network_settings.keyring_metrics,
+            )?;
+            rotate_credentials(settings, &mut keyring_provider, printer).await?;
             return Ok(ExitStatus::Success);
         }
+        Commands::Index(IndexNamespace {
+            command: IndexCommand::Credentials(IndexCredentialsCommand::List(args)),
+            ..
+        }) => {
+            let settings = IndexListCredentialsSettings::resolve(&args, filesystem);
+
+            if settings.verify_config {
+                return verify_auth_config(printer);
+            }
+            let passwords =
+                password_display(settings.show_password, settings.yes_really, settings.format)?;
+
+            return list_credentials(
+                settings,
+                passwords,
+                &globals.network_settings.keyring_metrics,
+                printer,
+            )
+            .await;
+        }
         Commands::Index(IndexNamespace {
             command: IndexCommand::Credentials(IndexCredentialsCommand::Unset(args)),
+            ..
         }) => {
+            let settings = IndexUnsetCredentialsSettings::resolve(args, filesystem);
+
+            if settings.all {
+                unset_all_credentials(settings, &globals.network_settings.keyring_metrics, printer)
+                    .await?;
+                return Ok(ExitStatus::Success);
+            }
+
             let IndexUnsetCredentialsSettings {
-                name,
-                username,
-                keyring_provider,
-                index,
-            } = IndexUnsetCredentialsSettings::resolve(args, filesystem);
+                dry_run,
+                strict,
+                format,
+                ..
+            } = settings;
+            let removed = async {
+                let keyring_provider = keyring_provider_for_index(
+                    settings.name.as_deref(),
+                    settings.url.as_deref(),
+                    &settings.index,
+                    settings.keyring_provider,
+                    settings.default_keyring_provider,
+                )?;
+                let mut keyring_provider = optional_keyring_provider_for(
+                    keyring_provider,
+                    settings.keyring_timeout,
+                    &globals.network_settings.keyring_metrics,
+                )?;
+                unset_credentials(settings, keyring_provider.as_mut(), printer).await
+            }
+            .await;
+            if matches!(format, IndexCredentialsFormat::Json) {
+                let not_found = removed.as_ref().is_ok_and(|removed| !removed.any());
+                let exit_status = write_json_result(removed, printer)?;
+                if strict && not_found {
+                    return Ok(ExitStatus::Failure);
+                }
+                return Ok(exit_status);
+            }
+            let removed = removed?;
+            if dry_run {
+                return Ok(ExitStatus::Success);
+            }
+            writeln!(printer.stderr(), "{removed}")?;
+            if strict && !removed.any() {
+                return Ok(ExitStatus::Failure);
+            }
+            return Ok(ExitStatus::Success);
+        }
+        Commands::Index(IndexNamespace {
+            command: IndexCommand::Credentials(IndexCredentialsCommand::Check(args)),
+            ..
+        }) => {
+            let settings = IndexCheckCredentialsSettings::resolve(args, filesystem);
+
+            return check_credentials(settings, &globals.network_settings, printer).await;
+        }
+        Commands::Index(IndexNamespace {
+            command: IndexCommand::Names,
+            ..
+        }) => {
+            let IndexNamesSettings { index } = IndexNamesSettings::resolve(filesystem);
 
-            let _ = unset_credentials(name, username, keyring_provider, index).await;
+            list_index_names(&index, printer)?;
             return Ok(ExitStatus::Success);
         }
+        Commands::Index(IndexNamespace {
+            command: IndexCommand::Credentials(IndexCredentialsCommand::Prune(args)),
+            ..
+        }) => {
+            let settings = IndexPruneCredentialsSettings::resolve(args, filesystem);
+
+            prune_credentials(settings, &globals.network_settings.keyring_metrics, printer).await?;
+            return Ok(ExitStatus::Success);
+        }
+        Commands::Index(IndexNamespace {
+            command: IndexCommand::Credentials(IndexCredentialsCommand::Export),
+            ..
+        }) => {
+            export_credentials(printer)?;
+            return Ok(ExitStatus::Success);
+        }
+        Commands::Index(IndexNamespace {
+            command: IndexCommand::Credentials(IndexCredentialsCommand::Import(args)),
+            ..
+        }) => {
+            let settings = IndexImportCredentialsSettings::resolve(args, filesystem);
+
+            import_credentials(settings, &globals.network_settings.keyring_metrics, printer)
+                .await?;
+            return Ok(ExitStatus::Success);
+        }
+        Commands::Auth(AuthNamespace {
+            command: AuthCommand::Login(args),
+        }) => {
+            let settings = IndexAddCredentialsSettings::resolve_login(args, filesystem);
+
+            let format = settings.format;
+            let stored = login(settings, &globals.network_settings.keyring_metrics, printer).await;
+            if matches!(format, IndexCredentialsFormat::Json) {
+                return write_json_result(stored, printer);
+            }
+            stored?;
+            return Ok(ExitStatus::Success);
+        }
+        Commands::Auth(AuthNamespace {
+            command: AuthCommand::Logout(args),
+        }) => {
+            let settings = IndexUnsetCredentialsSettings::resolve_logout(args, filesystem);
+
+            let format = settings.format;
+            let removed =
+                logout(settings, &globals.network_settings.keyring_metrics, printer).await;
+            if matches!(format, IndexCredentialsFormat::Json) {
+                return write_json_result(removed, printer);
+            }
+            removed?;
+            return Ok(ExitStatus::Success);
+        }
+        Commands::Auth(AuthNamespace {
+            command: AuthCommand::Status(args),
+        }) => {
+            let settings = IndexListCredentialsSettings::resolve(&args, filesystem);
+
+            if settings.verify_config {
+                return verify_auth_config(printer);
+            }
+            let passwords =
+                password_display(settings.show_password, settings.yes_really, settings.format)?;
+
+            return list_credentials(
+                settings,
+                passwords,
+                &globals.network_settings.keyring_metrics,
+                printer,
+            )
+            .await;
+        }
+        Commands::Auth(AuthNamespace {
+            command: AuthCommand::Setup(args),
+        }) => {
+            let settings = AuthSetupSettings::resolve(args, filesystem);
+
+            return setup_credentials(settings, &globals.network_settings, printer).await;
+        }
+        Commands::Auth(AuthNamespace {
+            command: AuthCommand::Provenance(args),
+        }) => {
+            show_settings!(args);
+            commands::auth_provenance(&args.package, &cache, printer)
+        }
     };
     result
 }
@@ -1858,6 +2117,20 @@ async fn run_project(
     }
 }
 
+/// Whether `--keyring-provider` was passed on the command line to the invoked (sub)command.
+///
+/// A keyring provider set with `UV_KEYRING_PROVIDER` or in the configuration doesn't count, so
+/// that the keyring providers recorded for individual indexes take precedence over it.
+fn keyring_provider_from_cli(matches: &ArgMatches) -> bool {
+    iter::successors(Some(matches), |matches| {
+        matches.subcommand().map(|(_, matches)| matches)
+    })
+    .any(|matches| {
+        matches.ids().any(|id| id.as_str() == "keyring_provider")
+            && matches.value_source("keyring_provider") == Some(ValueSource::CommandLine)
+    })
+}
+
 /// The main entry point for a uv invocation.
 ///
 /// # Usage
@@ -1891,8 +2164,16 @@ where
 
     // `std::env::args` is not `Send` so we parse before passing to our runtime
     // https://github.com/rust-lang/rust/pull/48005
-    let cli = match Cli::try_parse_from(args) {
-        Ok(cli) => cli,
+    let parsed = Cli::command()
+        .try_get_matches_from(args)
+        .and_then(|mut matches| {
+            let keyring_provider_from_cli = keyring_provider_from_cli(&matches);
+            let cli = Cli::from_arg_matches_mut(&mut matches)
+                .map_err(|err| err.format(&mut Cli::command()))?;
+            Ok((cli, keyring_provider_from_cli))
+        });
+    let (cli, keyring_provider_from_cli) = match parsed {
+        Ok(parsed) => parsed,
         Err(mut err) => {
             if let Some(ContextValue::String(subcommand)) = err.get(ContextKind::InvalidSubcommand)
             {
@@ -1968,8 +2249,25 @@ where
             .enable_all()
             .build()
             .expect("Failed building the Runtime");
+        // Record the queries of the keyring providers built for the command, to summarize them.
+        let keyring_metrics = Arc::new(KeyringMetrics::default());
         // Box the large main future to avoid stack overflows.
-        let result = runtime.block_on(Box::pin(run(cli)));
+        let result = runtime.block_on(Box::pin(run(
+            cli,
+            keyring_provider_from_cli,
+            keyring_metrics.clone(),
+        )));
+        // Offer to remember any usernames that had to be discovered in the keyring.
+        if result.is_ok() {
+            if let Err(err) = commands::adopt_discovered_usernames() {
+                debug!("Failed to persist discovered keyring usernames: {err}");
+            }
+        }
+        uv_auth::flush_audit_log();
+        uv_auth::flush_provenance_log();
+        if keyring_metrics.calls() > 0 {
+            debug!("Made {keyring_metrics}");
+        }
         // Avoid waiting for pending tasks to complete.
         //
         // The resolver may have kicked off HTTP requests during resolution that
@@ -3,9 +3,14 @@ use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::process;
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
 
 use url::Url;
 
+use uv_auth::{
+    AuthScheme, ClientIdentity, ConfigLayer, CredentialHelper, KeyringMetrics, KeyringScope,
+};
 use uv_cache::{CacheArgs, Refresh};
 use uv_cli::comma::CommaSeparatedRequirements;
 use uv_cli::{
@@ -14,12 +19,16 @@ use uv_cli::{
     ToolUpgradeArgs,
 };
 use uv_cli::{
-    AddArgs, ColorChoice, ExternalCommand, GlobalArgs, IndexListCredentialsArgs,
-    IndexSetCredentialsArgs, IndexUnsetCredentialsArgs, InitArgs, ListFormat, LockArgs, Maybe,
-    PipCheckArgs, PipCompileArgs, PipFreezeArgs, PipInstallArgs, PipListArgs, PipShowArgs,
-    PipSyncArgs, PipTreeArgs, PipUninstallArgs, PythonFindArgs, PythonInstallArgs, PythonListArgs,
-    PythonListFormat, PythonPinArgs, PythonUninstallArgs, RemoveArgs, RunArgs, SyncArgs,
-    ToolDirArgs, ToolInstallArgs, ToolListArgs, ToolRunArgs, ToolUninstallArgs, TreeArgs, VenvArgs,
+    AddArgs, AuthLoginArgs, AuthLogoutArgs, AuthSetupArgs, ColorChoice, ConfirmationArgs,
+    CredentialHelperArgs, CredentialHelperKind, CredentialsArgs, ExternalCommand, GlobalArgs,
+    IndexAuthScheme, IndexCheckCredentialsArgs, IndexCredentialsFormat, IndexCredentialsScope,
+    IndexImportCredentialsArgs, IndexListCredentialsArgs, IndexPruneCredentialsArgs,
+    IndexRotateCredentialsArgs, IndexSetCredentialsArgs, IndexUnsetCredentialsArgs, InitArgs,
+    KeyringArgs, ListFormat, LockArgs, Maybe, PipCheckArgs, PipCompileArgs, PipFreezeArgs,
+    PipInstallArgs, PipListArgs, PipShowArgs, PipSyncArgs, PipTreeArgs, PipUninstallArgs,
+    PythonFindArgs, PythonInstallArgs, PythonListArgs, PythonListFormat, PythonPinArgs,
+    PythonUninstallArgs, RemoveArgs, RunArgs, SyncArgs, ToolDirArgs, ToolInstallArgs, ToolListArgs,
+    ToolRunArgs, ToolUninstallArgs, TreeArgs, VenvArgs,
 };
 use uv_client::Connectivity;
 use uv_configuration::{
@@ -46,8 +55,8 @@ use uv_static::EnvVars;
 use uv_warnings::warn_user_once;
 use uv_workspace::pyproject::DependencyType;
 
-use crate::commands::ToolRunCommand;
 use crate::commands::{pip::operations::Modifications, InitKind, InitProjectKind};
+use crate::commands::{with_default_index, ToolRunCommand};
 
 /// The default publish URL.
 const PYPI_PUBLISH_URL: &str = "https://upload.pypi.org/legacy/";
@@ -72,8 +81,14 @@ pub(crate) struct GlobalSettings {
 
 impl GlobalSettings {
     /// Resolve the [`GlobalSettings`] from the CLI and filesystem configuration.
-    pub(crate) fn resolve(args: &GlobalArgs, workspace: Option<&FilesystemOptions>) -> Self {
-        let network_settings = NetworkSettings::resolve(args, workspace);
+    pub(crate) fn resolve(
+        args: &GlobalArgs,
+        workspace: Option<&FilesystemOptions>,
+        keyring_provider_from_cli: bool,
+        keyring_metrics: Arc<KeyringMetrics>,
+    ) -> Self {
+        let network_settings =
+            NetworkSettings::resolve(args, workspace, keyring_provider_from_cli, keyring_metrics);
         Self {
             required_version: workspace
                 .and_then(|workspace| workspace.globals.required_version.clone()),
@@ -144,10 +159,20 @@ pub(crate) struct NetworkSettings {
     pub(crate) connectivity: Connectivity,
     pub(crate) native_tls: bool,
     pub(crate) allow_insecure_host: Vec<TrustedHost>,
+    /// Whether `--keyring-provider` was passed on the command line, in which case it takes
+    /// precedence over the keyring providers recorded for individual indexes.
+    pub(crate) keyring_provider_from_cli: bool,
+    /// The queries to the keyring backends of the providers built for the command.
+    pub(crate) keyring_metrics: Arc<KeyringMetrics>,
 }
 
 impl NetworkSettings {
-    pub(crate) fn resolve(args: &GlobalArgs, workspace: Option<&FilesystemOptions>) -> Self {
+    pub(crate) fn resolve(
+        args: &GlobalArgs,
+        workspace: Option<&FilesystemOptions>,
+        keyring_provider_from_cli: bool,
+        keyring_metrics: Arc<KeyringMetrics>,
+    ) -> Self {
         let connectivity = if flag(args.offline, args.no_offline)
             .combine(workspace.and_then(|workspace| workspace.globals.offline))
             .unwrap_or(false)
@@ -180,6 +205,8 @@ impl NetworkSettings {
             connectivity,
             native_tls,
             allow_insecure_host,
+            keyring_provider_from_cli,
+            keyring_metrics,
         }
     }
 }
@@ -3125,16 +3152,32 @@ impl PublishSettings {
     }
 }
 
+/// The resolved settings to use for an `index credentials set` or `auth login` invocation.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Default)]
 pub(crate) struct IndexAddCredentialsSettings {
     // CLI only settings
-    pub(crate) name: String,
+    pub(crate) name: Option<String>,
+    pub(crate) url: Option<String>,
     pub(crate) username: Option<String>,
     pub(crate) password: Option<String>,
-
-    // CLI and Filesystem settings
-    pub(crate) keyring_provider: KeyringProviderType,
+    pub(crate) password_stdin: bool,
+    pub(crate) force: bool,
+    pub(crate) force_write: bool,
+    pub(crate) azure: bool,
+    pub(crate) helper: Option<CredentialHelper>,
+    pub(crate) scheme: AuthScheme,
+    pub(crate) scope: KeyringScope,
+    pub(crate) client_identity: Option<ClientIdentity>,
+    pub(crate) proxy: Option<String>,
+    pub(crate) layer: ConfigLayer,
+    pub(crate) dry_run: bool,
+    pub(crate) format: IndexCredentialsFormat,
+    pub(crate) keyring_timeout: Option<Duration>,
+    pub(crate) keyring_provider: Option<KeyringProviderType>,
 
     // Filesystem only settings
+    pub(crate) default_keyring_provider: KeyringProviderType,
     pub(crate) index: Vec<Index>,
 }
 
@@ -3143,6 +3186,81 @@ impl IndexAddCredentialsSettings {
     pub(crate) fn resolve(
         args: IndexSetCredentialsArgs,
         filesystem: Option<FilesystemOptions>,
+    ) -> Self {
+        let helper = if args.from_az_cli {
+            Some(CredentialHelper::Azure)
+        } else {
+            credential_helper(args.helper)
+        };
+        let scheme = match args.scheme {
+            Some(IndexAuthScheme::Bearer) => AuthScheme::Bearer,
+            Some(IndexAuthScheme::Basic) | None => AuthScheme::Basic,
+        };
+        let scope = match args.scope {
+            Some(IndexCredentialsScope::Url) => KeyringScope::Url,
+            Some(IndexCredentialsScope::Host) | None => KeyringScope::Host,
+        };
+        let client_identity = args.client_cert.map(|cert| ClientIdentity {
+            cert,
+            key: args.client_key,
+        });
+        Self {
+            force_write: args.force_write,
+            azure: args.azure,
+            scheme,
+            scope,
+            client_identity,
+            proxy: args.proxy,
+            layer: if args.local {
+                ConfigLayer::Project
+            } else {
+                ConfigLayer::User
+            },
+            ..Self::from_args(
+                args.index.name,
+                args.index.url,
+                args.index.default_index,
+                args.credentials,
+                args.confirmation,
+                args.output.output_format,
+                helper,
+                args.keyring,
+                filesystem,
+            )
+        }
+    }
+
+    /// Resolve the [`IndexSettings`] for `uv auth login` from the CLI and filesystem
+    /// configuration.
+    pub(crate) fn resolve_login(
+        args: AuthLoginArgs,
+        filesystem: Option<FilesystemOptions>,
+    ) -> Self {
+        let (name, url) = split_index_argument(args.index);
+        Self::from_args(
+            name,
+            url,
+            None,
+            args.credentials,
+            args.confirmation,
+            args.output.output_format,
+            None,
+            args.keyring,
+            filesystem,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn from_args(
+        name: Option<String>,
+        url: Option<String>,
+        default_index: Option<Maybe<Index>>,
+        credentials: CredentialsArgs,
+        confirmation: ConfirmationArgs,
+        format: IndexCredentialsFormat,
+        helper: Option<CredentialHelper>,
+        keyring: KeyringArgs,
+        filesystem: Option<FilesystemOptions>,
     ) -> Self {
         let Options { top_level, .. } = filesystem
             .map(FilesystemOptions::into_options)
@@ -3154,24 +3272,139 @@ impl IndexAddCredentialsSettings {
             ..
         } = top_level;
 
+        // Tokens are stored in the same way as username/password
+        let (username, password) = if let Some(token) = credentials.token {
+            (Some("__token__".to_string()), token)
+        } else {
+            (credentials.username, credentials.password)
+        };
+
         Self {
-            name: args.name,
+            name,
+            url,
+            username,
+            password,
+            password_stdin: credentials.password_stdin,
+            force: confirmation.yes,
+            force_write: false,
+            azure: false,
+            helper,
+            scheme: AuthScheme::Basic,
+            scope: KeyringScope::Host,
+            client_identity: None,
+            proxy: None,
+            layer: ConfigLayer::User,
+            dry_run: confirmation.dry_run,
+            format,
+            keyring_timeout: keyring.keyring_timeout.map(Duration::from_secs),
+            keyring_provider: keyring.keyring_provider,
+            default_keyring_provider: keyring_provider.unwrap_or_default(),
+            index: credentials_indexes(default_index, index),
+        }
+    }
+}
+
+/// The indexes for the credentials commands, including the default index under its reserved name,
+/// see [`with_default_index`].
+///
+/// The default index given with `--default-index`, if any, takes precedence over the configured
+/// default index.
+fn credentials_indexes(
+    default_index: Option<Maybe<Index>>,
+    index: Option<Vec<Index>>,
+) -> Vec<Index> {
+    with_default_index(
+        default_index
+            .and_then(Maybe::into_option)
+            .into_iter()
+            .chain(index.into_iter().flatten())
+            .collect(),
+    )
+}
+
+/// The [`CredentialHelper`] selected with `--helper`, if any.
+fn credential_helper(args: CredentialHelperArgs) -> Option<CredentialHelper> {
+    match args.helper? {
+        CredentialHelperKind::AwsCodeartifact => Some(CredentialHelper::AwsCodeartifact {
+            domain: args.domain.expect("`--helper` requires `--domain`"),
+            domain_owner: args
+                .domain_owner
+                .expect("`--helper` requires `--domain-owner`"),
+            region: args.region,
+        }),
+        CredentialHelperKind::Gcloud => Some(CredentialHelper::Gcloud { adc: args.adc }),
+    }
+}
+
+/// The resolved settings to use for an `index credentials rotate` invocation.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct IndexRotateCredentialsSettings {
+    // CLI only settings
+    pub(crate) name: Option<String>,
+    pub(crate) url: Option<String>,
+    pub(crate) username: Option<String>,
+    pub(crate) password: Option<String>,
+    pub(crate) password_stdin: bool,
+    pub(crate) force: bool,
+    pub(crate) dry_run: bool,
+    pub(crate) keyring_timeout: Option<Duration>,
+    pub(crate) keyring_provider: Option<KeyringProviderType>,
+
+    // Filesystem only settings
+    pub(crate) default_keyring_provider: KeyringProviderType,
+    pub(crate) index: Vec<Index>,
+}
+
+impl IndexRotateCredentialsSettings {
+    /// Resolve the [`IndexSettings`] from the CLI and filesystem configuration.
+    pub(crate) fn resolve(
+        args: IndexRotateCredentialsArgs,
+        filesystem: Option<FilesystemOptions>,
+    ) -> Self {
+        let Options { top_level, .. } = filesystem
+            .map(FilesystemOptions::into_options)
+            .unwrap_or_default();
+
+        let ResolverInstallerOptions {
+            keyring_provider,
+            index,
+            ..
+        } = top_level;
+
+        Self {
+            name: args.index.name,
+            url: args.index.url,
             username: args.username,
             password: args.password,
-            keyring_provider: args
-                .keyring_provider
-                .combine(keyring_provider)
-                .unwrap_or_default(),
-            index: index.unwrap_or_default(),
+            password_stdin: args.password_stdin,
+            force: args.confirmation.yes,
+            dry_run: args.confirmation.dry_run,
+            keyring_timeout: args.keyring.keyring_timeout.map(Duration::from_secs),
+            keyring_provider: args.keyring.keyring_provider,
+            default_keyring_provider: keyring_provider.unwrap_or_default(),
+            index: credentials_indexes(args.index.default_index, index),
         }
     }
 }
 
+/// The resolved settings to use for an `index credentials list` or `auth status` invocation.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Default)]
 pub(crate) struct IndexListCredentialsSettings {
-    // CLI and Filesystem settings
-    pub(crate) keyring_provider: KeyringProviderType,
+    // CLI only settings
+    pub(crate) format: IndexCredentialsFormat,
+    pub(crate) strict: bool,
+    pub(crate) ignore: Vec<String>,
+    pub(crate) index_names: Vec<String>,
+    pub(crate) verify_config: bool,
+    pub(crate) no_verify: bool,
+    pub(crate) show_password: bool,
+    pub(crate) yes_really: bool,
+    pub(crate) keyring_timeout: Option<Duration>,
+    pub(crate) keyring_provider: Option<KeyringProviderType>,
 
     // Filesystem only settings
+    pub(crate) default_keyring_provider: KeyringProviderType,
     pub(crate) index: Vec<Index>,
 }
 
@@ -3192,24 +3425,211 @@ impl IndexListCredentialsSettings {
         } = top_level;
 
         Self {
-            keyring_provider: args
-                .keyring_provider
-                .combine(keyring_provider)
-                .unwrap_or_default(),
+            format: args.output.output_format,
+            strict: args.strict,
+            ignore: args.ignore.clone(),
+            index_names: args.index.clone(),
+            verify_config: args.verify_config,
+            no_verify: args.no_verify,
+            show_password: args.show_password,
+            yes_really: args.yes_really,
+            keyring_timeout: args.keyring.keyring_timeout.map(Duration::from_secs),
+            keyring_provider: args.keyring.keyring_provider,
+            default_keyring_provider: keyring_provider.unwrap_or_default(),
+            index: credentials_indexes(args.default_index.clone(), index),
+        }
+    }
+}
+
+/// The resolved settings to use for an `index credentials check` invocation.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct IndexCheckCredentialsSettings {
+    // CLI only settings
+    pub(crate) name: Option<String>,
+    pub(crate) format: IndexCredentialsFormat,
+    pub(crate) keyring_timeout: Option<Duration>,
+    pub(crate) keyring_provider: Option<KeyringProviderType>,
+
+    // Filesystem only settings
+    pub(crate) default_keyring_provider: KeyringProviderType,
+    pub(crate) index: Vec<Index>,
+}
+
+impl IndexCheckCredentialsSettings {
+    /// Resolve the [`IndexSettings`] from the CLI and filesystem configuration.
+    pub(crate) fn resolve(
+        args: IndexCheckCredentialsArgs,
+        filesystem: Option<FilesystemOptions>,
+    ) -> Self {
+        let Options { top_level, .. } = filesystem
+            .map(FilesystemOptions::into_options)
+            .unwrap_or_default();
+
+        let ResolverInstallerOptions {
+            keyring_provider,
+            index,
+            ..
+        } = top_level;
+
+        Self {
+            name: args.name,
+            format: args.output.output_format,
+            keyring_timeout: args.keyring.keyring_timeout.map(Duration::from_secs),
+            keyring_provider: args.keyring.keyring_provider,
+            default_keyring_provider: keyring_provider.unwrap_or_default(),
+            index: index.unwrap_or_default(),
+        }
+    }
+}
+
+/// The resolved settings to use for an `auth setup` invocation.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct AuthSetupSettings {
+    // CLI only settings
+    pub(crate) keyring_timeout: Option<Duration>,
+    pub(crate) keyring_provider: Option<KeyringProviderType>,
+
+    // Filesystem only settings
+    pub(crate) default_keyring_provider: KeyringProviderType,
+    pub(crate) index: Vec<Index>,
+}
+
+impl AuthSetupSettings {
+    /// Resolve the [`AuthSetupSettings`] from the CLI and filesystem configuration.
+    pub(crate) fn resolve(args: AuthSetupArgs, filesystem: Option<FilesystemOptions>) -> Self {
+        let Options { top_level, .. } = filesystem
+            .map(FilesystemOptions::into_options)
+            .unwrap_or_default();
+
+        let ResolverInstallerOptions {
+            keyring_provider,
+            index,
+            ..
+        } = top_level;
+
+        Self {
+            keyring_timeout: args.keyring.keyring_timeout.map(Duration::from_secs),
+            keyring_provider: args.keyring.keyring_provider,
+            default_keyring_provider: keyring_provider.unwrap_or_default(),
+            index: index.unwrap_or_default(),
+        }
+    }
+}
+
+/// The resolved settings to use for an `index credentials prune` invocation.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct IndexPruneCredentialsSettings {
+    // CLI only settings
+    pub(crate) yes: bool,
+    pub(crate) dry_run: bool,
+    pub(crate) keyring_timeout: Option<Duration>,
+    pub(crate) keyring_provider: Option<KeyringProviderType>,
+
+    // Filesystem only settings
+    pub(crate) default_keyring_provider: KeyringProviderType,
+    pub(crate) index: Vec<Index>,
+}
+
+impl IndexPruneCredentialsSettings {
+    /// Resolve the [`IndexSettings`] from the CLI and filesystem configuration.
+    pub(crate) fn resolve(
+        args: IndexPruneCredentialsArgs,
+        filesystem: Option<FilesystemOptions>,
+    ) -> Self {
+        let Options { top_level, .. } = filesystem
+            .map(FilesystemOptions::into_options)
+            .unwrap_or_default();
+
+        let ResolverInstallerOptions {
+            keyring_provider,
+            index,
+            ..
+        } = top_level;
+
+        Self {
+            yes: args.confirmation.yes,
+            dry_run: args.confirmation.dry_run,
+            keyring_timeout: args.keyring.keyring_timeout.map(Duration::from_secs),
+            keyring_provider: args.keyring.keyring_provider,
+            default_keyring_provider: keyring_provider.unwrap_or_default(),
             index: index.unwrap_or_default(),
         }
     }
 }
 
+/// The resolved settings to use for an `index credentials import` invocation.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct IndexImportCredentialsSettings {
+    // CLI only settings
+    pub(crate) file: Option<PathBuf>,
+    pub(crate) overwrite: bool,
+    pub(crate) skip_passwords: bool,
+    pub(crate) keyring_timeout: Option<Duration>,
+    pub(crate) keyring_provider: Option<KeyringProviderType>,
+
+    // Filesystem only settings
+    pub(crate) default_keyring_provider: KeyringProviderType,
+}
+
+impl IndexImportCredentialsSettings {
+    /// Resolve the [`IndexImportCredentialsSettings`] from the CLI and filesystem configuration.
+    pub(crate) fn resolve(
+        args: IndexImportCredentialsArgs,
+        filesystem: Option<FilesystemOptions>,
+    ) -> Self {
+        let Options { top_level, .. } = filesystem
+            .map(FilesystemOptions::into_options)
+            .unwrap_or_default();
+
+        Self {
+            file: args.file,
+            overwrite: args.overwrite,
+            skip_passwords: args.skip_passwords,
+            keyring_timeout: args.keyring.keyring_timeout.map(Duration::from_secs),
+            keyring_provider: args.keyring.keyring_provider,
+            default_keyring_provider: top_level.keyring_provider.unwrap_or_default(),
+        }
+    }
+}
+
+/// The resolved settings to use for an `index names` invocation.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct IndexNamesSettings {
+    // Filesystem only settings
+    pub(crate) index: Vec<Index>,
+}
+
+impl IndexNamesSettings {
+    /// Resolve the [`IndexNamesSettings`] from the filesystem configuration.
+    pub(crate) fn resolve(filesystem: Option<FilesystemOptions>) -> Self {
+        let Options { top_level, .. } = filesystem
+            .map(FilesystemOptions::into_options)
+            .unwrap_or_default();
+
+        Self {
+            index: top_level.index.unwrap_or_default(),
+        }
+    }
+}
+
+/// The resolved settings to use for an `index credentials unset` or `auth logout` invocation.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Default)]
 pub(crate) struct IndexUnsetCredentialsSettings {
     // CLI only settings
-    pub(crate) name: String,
+    pub(crate) name: Option<String>,
+    pub(crate) url: Option<String>,
     pub(crate) username: Option<String>,
-
-    // CLI and Filesystem settings
-    pub(crate) keyring_provider: KeyringProviderType,
+    pub(crate) all: bool,
+    pub(crate) all_layers: bool,
+    pub(crate) dry_run: bool,
+    pub(crate) strict: bool,
+    pub(crate) format: IndexCredentialsFormat,
+    pub(crate) keyring_timeout: Option<Duration>,
+    pub(crate) keyring_provider: Option<KeyringProviderType>,
 
     // Filesystem only settings
+    pub(crate) default_keyring_provider: KeyringProviderType,
     pub(crate) index: Vec<Index>,
 }
 
@@ -3230,17 +3650,64 @@ impl IndexUnsetCredentialsSettings {
         } = top_level;
 
         Self {
-            name: args.name,
+            name: args.index.name,
+            url: args.index.url,
             username: args.username,
-            keyring_provider: args
-                .keyring_provider
-                .combine(keyring_provider)
-                .unwrap_or_default(),
+            all: args.all,
+            all_layers: args.all_layers,
+            dry_run: args.confirmation.dry_run,
+            strict: args.strict,
+            format: args.output.output_format,
+            keyring_timeout: args.keyring.keyring_timeout.map(Duration::from_secs),
+            keyring_provider: args.keyring.keyring_provider,
+            default_keyring_provider: keyring_provider.unwrap_or_default(),
+            index: credentials_indexes(args.index.default_index, index),
+        }
+    }
+
+    /// Resolve the [`IndexSettings`] for `uv auth logout` from the CLI and filesystem
+    /// configuration.
+    pub(crate) fn resolve_logout(
+        args: AuthLogoutArgs,
+        filesystem: Option<FilesystemOptions>,
+    ) -> Self {
+        let Options { top_level, .. } = filesystem
+            .map(FilesystemOptions::into_options)
+            .unwrap_or_default();
+
+        let ResolverInstallerOptions {
+            keyring_provider,
+            index,
+            ..
+        } = top_level;
+
+        let (name, url) = split_index_argument(args.index);
+        Self {
+            name,
+            url,
+            username: args.username,
+            all: false,
+            all_layers: false,
+            dry_run: args.confirmation.dry_run,
+            strict: false,
+            format: args.output.output_format,
+            keyring_timeout: args.keyring.keyring_timeout.map(Duration::from_secs),
+            keyring_provider: args.keyring.keyring_provider,
+            default_keyring_provider: keyring_provider.unwrap_or_default(),
             index: index.unwrap_or_default(),
         }
     }
 }
 
+/// Split the index argument of `uv auth login` and `uv auth logout` into the name of an index
+/// and a URL, depending on whether it has a scheme.
+fn split_index_argument(index: Option<String>) -> (Option<String>, Option<String>) {
+    match index {
+        Some(url) if url.contains("://") => (None, Some(url)),
+        name => (name, None),
+    }
+}
+
 // Environment variables that are not exposed as CLI arguments.
 mod env {
     use uv_static::EnvVars;
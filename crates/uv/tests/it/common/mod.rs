@@ -719,6 +719,30 @@ impl TestContext {
         command
     }
 
+    /// Create a `uv index` command with options shared across scenarios.
+    pub fn index(&self) -> Command {
+        let mut command = self.new_command();
+        command.arg("index");
+        self.add_shared_options(&mut command, false);
+        command
+    }
+
+    /// Create a `uv index credentials` command with options shared across scenarios.
+    pub fn index_credentials(&self) -> Command {
+        let mut command = self.new_command();
+        command.arg("index").arg("credentials");
+        self.add_shared_options(&mut command, false);
+        command
+    }
+
+    /// Create a `uv auth` command with options shared across scenarios.
+    pub fn auth(&self) -> Command {
+        let mut command = self.new_command();
+        command.arg("auth");
+        self.add_shared_options(&mut command, false);
+        command
+    }
+
     /// Create a `uv init` command with options shared across scenarios and
     /// isolated from any git repository that may exist in a parent directory.
     pub fn init(&self) -> Command {
@@ -8437,6 +8437,93 @@ fn lock_redact_url_sources() -> Result<()> {
     Ok(())
 }
 
+/// A keyring provider passed on the command line takes precedence over the keyring provider
+/// recorded for the index in the auth config, which takes precedence over the setting.
+#[test]
+fn lock_keyring_provider_cli_over_index() -> Result<()> {
+    let keyring_context = TestContext::new("3.12");
+
+    // Install our keyring plugin
+    keyring_context
+        .pip_install()
+        .arg(
+            keyring_context
+                .workspace_root
+                .join("scripts")
+                .join("packages")
+                .join("keyring_test_plugin"),
+        )
+        .assert()
+        .success();
+
+    let context = TestContext::new("3.12");
+
+    let pyproject_toml = context.temp_dir.child("pyproject.toml");
+    pyproject_toml.write_str(
+        r#"
+        [project]
+        name = "foo"
+        version = "0.1.0"
+        requires-python = ">=3.12"
+        dependencies = ["iniconfig"]
+
+        [tool.uv]
+        keyring-provider = "subprocess"
+
+        [[tool.uv.index]]
+        name = "proxy"
+        url = "https://pypi-proxy.fly.dev/basic-auth/simple"
+        default = true
+        "#,
+    )?;
+
+    // The keyring is disabled for the index in the auth config.
+    let data_home = context.temp_dir.child("data/home");
+    data_home.child("uv/auth.toml").write_str(
+        r#"
+        version = 1
+
+        [indexes."pypi-proxy.fly.dev"]
+        usernames = ["public"]
+        keyring-provider = "disabled"
+        "#,
+    )?;
+
+    // The provider recorded for the index wins over the setting.
+    uv_snapshot!(context.filters(), context.lock()
+        .env(EnvVars::XDG_DATA_HOME, data_home.as_os_str())
+        .env(EnvVars::KEYRING_TEST_CREDENTIALS, r#"{"pypi-proxy.fly.dev": {"public": "heron"}}"#)
+        .env(EnvVars::PATH, venv_bin_path(&keyring_context.venv)), @r###"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+      × No solution found when resolving dependencies:
+      ╰─▶ Because iniconfig was not found in the package registry and your project depends on iniconfig, we can conclude that your project's requirements are unsatisfiable.
+
+          hint: An index URL (https://pypi-proxy.fly.dev/basic-auth/simple) could not be queried due to a lack of valid authentication credentials (401 Unauthorized).
+    "###);
+
+    // The provider passed on the command line wins over the provider recorded for the index.
+    uv_snapshot!(context.filters(), context.lock()
+        .arg("--keyring-provider")
+        .arg("subprocess")
+        .env(EnvVars::XDG_DATA_HOME, data_home.as_os_str())
+        .env(EnvVars::KEYRING_TEST_CREDENTIALS, r#"{"pypi-proxy.fly.dev": {"public": "heron"}}"#)
+        .env(EnvVars::PATH, venv_bin_path(&keyring_context.venv)), @r###"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    warning: Using credentials stored in the keyring for `pypi-proxy.fly.dev`, which is deprecated; store them again with `uv index credentials set` to use `https://pypi-proxy.fly.dev` instead
+    Resolved 2 packages in [TIME]
+    "###);
+
+    Ok(())
+}
+
 /// Pass credentials for a named index via environment variables.
 #[test]
 fn lock_env_credentials() -> Result<()> {
@@ -18035,8 +18122,7 @@ fn lock_keyring_credentials() -> Result<()> {
     ----- stdout -----
 
     ----- stderr -----
-    Request for public@https://pypi-proxy.fly.dev/basic-auth/simple/iniconfig/
-    Request for public@pypi-proxy.fly.dev
+    warning: Using credentials stored in the keyring for `pypi-proxy.fly.dev`, which is deprecated; store them again with `uv index credentials set` to use `https://pypi-proxy.fly.dev` instead
     Resolved 2 packages in [TIME]
     "###);
 
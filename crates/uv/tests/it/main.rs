@@ -28,6 +28,8 @@ mod export;
 
 mod help;
 
+mod index;
+
 #[cfg(all(feature = "python", feature = "pypi", feature = "git"))]
 mod init;
 
@@ -5245,8 +5245,7 @@ fn install_package_basic_auth_from_keyring() {
     ----- stdout -----
 
     ----- stderr -----
-    Request for public@https://pypi-proxy.fly.dev/basic-auth/simple/anyio/
-    Request for public@pypi-proxy.fly.dev
+    warning: Using credentials stored in the keyring for `pypi-proxy.fly.dev`, which is deprecated; store them again with `uv index credentials set` to use `https://pypi-proxy.fly.dev` instead
     Resolved 3 packages in [TIME]
     Prepared 3 packages in [TIME]
     Installed 3 packages in [TIME]
@@ -5259,6 +5258,92 @@ fn install_package_basic_auth_from_keyring() {
     context.assert_command("import anyio").success();
 }
 
+/// Install a package from an index that requires authentication from the keyring, with the
+/// credentials stored for the scheme and host rather than the host alone.
+#[test]
+fn install_package_basic_auth_from_keyring_realm() {
+    let context = TestContext::new("3.12");
+
+    // Install our keyring plugin
+    context
+        .pip_install()
+        .arg(
+            context
+                .workspace_root
+                .join("scripts")
+                .join("packages")
+                .join("keyring_test_plugin"),
+        )
+        .assert()
+        .success();
+
+    uv_snapshot!(context.pip_install()
+        .arg("anyio")
+        .arg("--index-url")
+        .arg("https://public@pypi-proxy.fly.dev/basic-auth/simple")
+        .arg("--keyring-provider")
+        .arg("subprocess")
+        .arg("--strict")
+        .env(EnvVars::KEYRING_TEST_CREDENTIALS, r#"{"https://pypi-proxy.fly.dev": {"public": "heron"}}"#)
+        .env(EnvVars::PATH, venv_bin_path(&context.venv)), @r###"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    Resolved 3 packages in [TIME]
+    Prepared 3 packages in [TIME]
+    Installed 3 packages in [TIME]
+     + anyio==4.3.0
+     + idna==3.6
+     + sniffio==1.3.1
+    "###
+    );
+
+    context.assert_command("import anyio").success();
+}
+
+/// Install a package from an index that requires authentication, but the keyring only has
+/// credentials stored for the host with another scheme.
+#[test]
+fn install_package_basic_auth_from_keyring_realm_other_scheme() {
+    let context = TestContext::new("3.12");
+
+    // Install our keyring plugin
+    context
+        .pip_install()
+        .arg(
+            context
+                .workspace_root
+                .join("scripts")
+                .join("packages")
+                .join("keyring_test_plugin"),
+        )
+        .assert()
+        .success();
+
+    uv_snapshot!(context.pip_install()
+        .arg("anyio")
+        .arg("--index-url")
+        .arg("https://public@pypi-proxy.fly.dev/basic-auth/simple")
+        .arg("--keyring-provider")
+        .arg("subprocess")
+        .arg("--strict")
+        .env(EnvVars::KEYRING_TEST_CREDENTIALS, r#"{"http://pypi-proxy.fly.dev": {"public": "heron"}}"#)
+        .env(EnvVars::PATH, venv_bin_path(&context.venv)), @r###"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+      × No solution found when resolving dependencies:
+      ╰─▶ Because anyio was not found in the package registry and you require anyio, we can conclude that your requirements are unsatisfiable.
+
+          hint: An index URL (https://pypi-proxy.fly.dev/basic-auth/simple) could not be queried due to a lack of valid authentication credentials (401 Unauthorized).
+    "###
+    );
+}
+
 /// Install a package from an index that requires authentication
 /// but the keyring has the wrong password
 #[test]
@@ -5292,8 +5377,7 @@ fn install_package_basic_auth_from_keyring_wrong_password() {
     ----- stdout -----
 
     ----- stderr -----
-    Request for public@https://pypi-proxy.fly.dev/basic-auth/simple/anyio/
-    Request for public@pypi-proxy.fly.dev
+    warning: Using credentials stored in the keyring for `pypi-proxy.fly.dev`, which is deprecated; store them again with `uv index credentials set` to use `https://pypi-proxy.fly.dev` instead
       × No solution found when resolving dependencies:
       ╰─▶ Because anyio was not found in the package registry and you require anyio, we can conclude that your requirements are unsatisfiable.
 
@@ -5335,8 +5419,6 @@ fn install_package_basic_auth_from_keyring_wrong_username() {
     ----- stdout -----
 
     ----- stderr -----
-    Request for public@https://pypi-proxy.fly.dev/basic-auth/simple/anyio/
-    Request for public@pypi-proxy.fly.dev
       × No solution found when resolving dependencies:
       ╰─▶ Because anyio was not found in the package registry and you require anyio, we can conclude that your requirements are unsatisfiable.
 
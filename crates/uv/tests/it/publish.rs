@@ -283,12 +283,8 @@ fn check_keyring_behaviours() {
 
     ----- stderr -----
     Publishing 1 file to https://test.pypi.org/legacy/?ok
-    Request for dummy@https://test.pypi.org/legacy/?ok
-    Request for dummy@test.pypi.org
     warning: Keyring has no password for URL `https://test.pypi.org/legacy/?ok` and username `dummy`
     Uploading ok-1.0.0-py3-none-any.whl ([SIZE])
-    Request for dummy@https://test.pypi.org/legacy/?ok
-    Request for dummy@test.pypi.org
     error: Failed to publish `../../scripts/links/ok-1.0.0-py3-none-any.whl` to https://test.pypi.org/legacy/?ok
       Caused by: Upload failed with status code 403 Forbidden. Server says: 403 Username/Password authentication is no longer supported. Migrate to API Tokens or Trusted Publishers instead. See https://test.pypi.org/help/#apitoken and https://test.pypi.org/help/#trusted-publishers
     "###
@@ -312,7 +308,6 @@ fn check_keyring_behaviours() {
 
     ----- stderr -----
     Publishing 1 file to https://test.pypi.org/legacy/?ok
-    Request for dummy@https://test.pypi.org/legacy/?ok
     Uploading ok-1.0.0-py3-none-any.whl ([SIZE])
     error: Failed to publish `../../scripts/links/ok-1.0.0-py3-none-any.whl` to https://test.pypi.org/legacy/?ok
       Caused by: Upload failed with status code 403 Forbidden. Server says: 403 Username/Password authentication is no longer supported. Migrate to API Tokens or Trusted Publishers instead. See https://test.pypi.org/help/#apitoken and https://test.pypi.org/help/#trusted-publishers
@@ -64,6 +64,13 @@ fn resolve_uv_toml() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -238,6 +245,13 @@ fn resolve_uv_toml() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -413,6 +427,13 @@ fn resolve_uv_toml() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -620,6 +641,13 @@ fn resolve_pyproject_toml() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -796,6 +824,13 @@ fn resolve_pyproject_toml() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -951,6 +986,13 @@ fn resolve_pyproject_toml() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -1150,6 +1192,13 @@ fn resolve_index_url() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -1355,6 +1404,13 @@ fn resolve_index_url() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -1614,6 +1670,13 @@ fn resolve_find_links() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -1812,6 +1875,13 @@ fn resolve_top_level() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -1973,6 +2043,13 @@ fn resolve_top_level() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -2176,6 +2253,13 @@ fn resolve_top_level() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -2403,6 +2487,13 @@ fn resolve_user_configuration() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -2554,6 +2645,13 @@ fn resolve_user_configuration() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -2705,6 +2803,13 @@ fn resolve_user_configuration() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -2858,6 +2963,13 @@ fn resolve_user_configuration() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -3030,6 +3142,13 @@ fn resolve_tool() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -3191,6 +3310,13 @@ fn resolve_poetry_toml() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -3370,6 +3496,13 @@ fn resolve_both() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -3667,6 +3800,13 @@ fn resolve_config_file() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -3942,6 +4082,13 @@ fn resolve_skip_empty() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -4096,6 +4243,13 @@ fn resolve_skip_empty() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -4269,6 +4423,13 @@ fn allow_insecure_host() -> anyhow::Result<()> {
                     port: None,
                 },
             ],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -4434,6 +4595,13 @@ fn index_priority() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -4639,6 +4807,13 @@ fn index_priority() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -4850,6 +5025,13 @@ fn index_priority() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -5056,6 +5238,13 @@ fn index_priority() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -5269,6 +5458,13 @@ fn index_priority() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -5475,6 +5671,13 @@ fn index_priority() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -5694,6 +5897,13 @@ fn verify_hashes() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -5839,6 +6049,13 @@ fn verify_hashes() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -5982,6 +6199,13 @@ fn verify_hashes() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -6127,6 +6351,13 @@ fn verify_hashes() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -6270,6 +6501,13 @@ fn verify_hashes() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,
@@ -6414,6 +6652,13 @@ fn verify_hashes() -> anyhow::Result<()> {
             connectivity: Online,
             native_tls: false,
             allow_insecure_host: [],
+            keyring_provider_from_cli: false,
+            keyring_metrics: KeyringMetrics {
+                hits: 0,
+                misses: 0,
+                errors: 0,
+                elapsed: 0,
+            },
         },
         concurrency: Concurrency {
             downloads: 50,